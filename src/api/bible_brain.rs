@@ -1,109 +1,280 @@
+use crate::config::HttpConfig;
 use crate::error::{AppError, Result};
-use serde::{Deserialize, Serialize};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use super::http::{CoalescingStats, UpstreamHttp};
+use super::models;
+use super::provider::BibleProvider;
+use super::video::{Video, VideoCache, VideoRecord};
+
+/// DBP API version sent with every request
+const API_VERSION: &str = "4";
+
+/// Upper bound on pages followed when collecting a paginated listing
+const MAX_PAGES: u32 = 50;
+
+/// Page size requested when collecting every page of a listing
+const PAGE_LIMIT: u32 = 500;
 
 #[derive(Debug, Clone)]
 pub struct BibleBrainClient {
-    client: reqwest::Client,
+    http: UpstreamHttp,
     base_url: String,
     api_key: String,
+    videos: VideoCache,
 }
 
 impl BibleBrainClient {
     pub fn new(base_url: String, api_key: String) -> Self {
+        Self::with_http(base_url, api_key, HttpConfig::default())
+    }
+
+    /// Create a client with explicit timeout/retry settings
+    pub fn with_http(base_url: String, api_key: String, http_config: HttpConfig) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            http: UpstreamHttp::new(http_config),
             base_url,
             api_key,
+            videos: VideoCache::default(),
         }
     }
 
-    /// Get list of available languages
-    pub async fn get_languages(&self) -> Result<Vec<Language>> {
-        let url = format!("{}/languages", self.base_url);
+    /// How many upstream fetches were shared between concurrent requests
+    pub fn coalescing_stats(&self) -> CoalescingStats {
+        self.http.coalescing_stats()
+    }
+
+    /// GET `path` and unwrap DBP's `{ "data": ..., "meta": ... }` envelope.
+    ///
+    /// Bodies without the envelope are treated as bare data.
+    async fn get_data<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        what: &str,
+    ) -> Result<DbpResponse<T>> {
+        let url = format!("{}{}", self.base_url, path);
+        tracing::debug!("Fetching {} from: {}", what, url);
+
+        let mut params = vec![("v", API_VERSION.to_string())];
+        params.extend_from_slice(query);
         let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
+            .http
+            .get(&url, &[("dbp-api-key", &self.api_key)], &params)
             .await?;
 
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch languages: {}",
-                response.status()
-            )));
+        if !response.status.is_success() {
+            return Err(AppError::from_upstream_status(
+                response.status,
+                what,
+                &response.body,
+                response.retry_after_secs,
+            ));
         }
 
-        let languages: Vec<Language> = response.json().await?;
-        Ok(languages)
+        let mut json: serde_json::Value = serde_json::from_str(&response.body).map_err(|e| {
+            AppError::UpstreamSchemaChanged(format!("Failed to parse {} response: {}", what, e))
+        })?;
+        let (data, meta) = match json.get_mut("data") {
+            Some(data) => (data.take(), json.get("meta").cloned()),
+            None => (json, None),
+        };
+
+        let data = serde_json::from_value::<T>(data).map_err(|e| {
+            tracing::error!("Failed to deserialize {}: {}", what, e);
+            AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+        })?;
+        let meta = meta
+            .and_then(|meta| serde_json::from_value::<Meta>(meta).ok())
+            .unwrap_or_default();
+
+        Ok(DbpResponse { data, meta })
     }
 
-    /// Get list of available Bibles for a language
-    pub async fn get_bibles(&self, language_code: &str) -> Result<Vec<Bible>> {
-        let url = format!("{}/bibles", self.base_url);
-        let mut params = HashMap::new();
-        params.insert("language_code", language_code);
-
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .query(&params)
-            .send()
-            .await?;
+    /// GET one page of a paginated listing
+    async fn get_page<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        page: PageRequest,
+        what: &str,
+    ) -> Result<Page<T>> {
+        let mut query = query.to_vec();
+        query.push(("page", page.page.to_string()));
+        query.push(("limit", page.limit.to_string()));
+
+        let response = self.get_data::<Vec<T>>(path, &query, what).await?;
+        Ok(Page {
+            data: response.data,
+            pagination: response.meta.pagination,
+        })
+    }
 
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch bibles: {}",
-                response.status()
-            )));
+    /// GET every page of a paginated listing, up to `MAX_PAGES`
+    async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        what: &str,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let request = PageRequest {
+                page,
+                limit: PAGE_LIMIT,
+            };
+            let response = self.get_page::<T>(path, query, request, what).await?;
+            let received = response.data.len();
+            items.extend(response.data);
+
+            let last_page = match response.pagination {
+                Some(pagination) => pagination.current_page >= pagination.total_pages,
+                None => true,
+            };
+            if last_page || received == 0 {
+                return Ok(items);
+            }
         }
 
-        let bibles: Vec<Bible> = response.json().await?;
-        Ok(bibles)
+        tracing::warn!("Stopped collecting {} after {} pages", what, MAX_PAGES);
+        Ok(items)
+    }
+
+    /// Get one page of available languages
+    pub async fn get_languages(&self, page: PageRequest) -> Result<Page<Language>> {
+        self.get_page("/languages", &[], page, "languages").await
+    }
+
+    /// Get one page of available Bibles, optionally for a single language
+    pub async fn get_bibles(
+        &self,
+        language_code: Option<&str>,
+        page: PageRequest,
+    ) -> Result<Page<Bible>> {
+        let query: Vec<(&str, String)> = language_code
+            .map(|code| ("language_code", code.to_string()))
+            .into_iter()
+            .collect();
+        self.get_page("/bibles", &query, page, "bibles").await
+    }
+
+    /// Get list of all available Bibles, following every page
+    pub async fn get_all_bibles(&self) -> Result<Vec<Bible>> {
+        self.get_all_pages("/bibles", &[], "bibles").await
+    }
+
+    /// Get a single Bible with its filesets
+    pub async fn get_bible(&self, bible_id: &str) -> Result<Bible> {
+        let path = format!("/bibles/{}", bible_id);
+        Ok(self.get_data(&path, &[], "bible").await?.data)
+    }
+
+    /// Get the filesets (text, audio, drama audio, video) published for a Bible
+    pub async fn get_filesets(&self, bible_id: &str) -> Result<Vec<Fileset>> {
+        let bible = self.get_bible(bible_id).await?;
+        Ok(bible.filesets())
     }
 
     /// Get books for a specific Bible
     pub async fn get_books(&self, bible_id: &str) -> Result<Vec<Book>> {
-        let url = format!("{}/bibles/{}/books", self.base_url, bible_id);
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
+        let path = format!("/bibles/{}/book", bible_id);
+        Ok(self.get_data(&path, &[], "books").await?.data)
+    }
 
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch books: {}",
-                response.status()
-            )));
-        }
+    /// Get the verses of a chapter from a text fileset
+    pub async fn get_text_chapter(
+        &self,
+        fileset_id: &str,
+        book_id: &str,
+        chapter: u32,
+    ) -> Result<Vec<TextVerse>> {
+        let path = format!("/bibles/filesets/{}/{}/{}", fileset_id, book_id, chapter);
+        Ok(self.get_data(&path, &[], "chapter text").await?.data)
+    }
 
-        let books: Vec<Book> = response.json().await?;
-        Ok(books)
+    /// Get the audio file(s) for a chapter from an audio fileset.
+    ///
+    /// The returned `path` URLs are signed and expire after a while.
+    pub async fn get_audio_files(
+        &self,
+        fileset_id: &str,
+        book_id: &str,
+        chapter: u32,
+    ) -> Result<Vec<AudioFile>> {
+        let path = format!("/bibles/filesets/{}/{}/{}", fileset_id, book_id, chapter);
+        Ok(self.get_data(&path, &[], "chapter audio").await?.data)
     }
 
-    /// Get chapters for a specific book
-    pub async fn get_chapters(&self, bible_id: &str, book_id: &str) -> Result<Vec<Chapter>> {
-        let url = format!("{}/bibles/{}/books/{}/chapters", self.base_url, bible_id, book_id);
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
+    /// Get the verse start times for a chapter of an audio fileset
+    pub async fn get_timestamps(
+        &self,
+        fileset_id: &str,
+        book_id: &str,
+        chapter: u32,
+    ) -> Result<Vec<Timestamp>> {
+        let path = format!("/timestamps/{}/{}/{}", fileset_id, book_id, chapter);
+        Ok(self.get_data(&path, &[], "timestamps").await?.data)
+    }
 
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch chapters: {}",
-                response.status()
-            )));
-        }
+    /// Get a chapter's audio URL together with its verse timestamps.
+    ///
+    /// Many filesets have no timing data; they yield empty `timestamps`
+    /// rather than an error.
+    pub async fn get_audio_chapter(
+        &self,
+        fileset_id: &str,
+        book_id: &str,
+        chapter: u32,
+    ) -> Result<AudioChapter> {
+        let (files, timestamps) = futures::join!(
+            self.get_audio_files(fileset_id, book_id, chapter),
+            self.get_timestamps(fileset_id, book_id, chapter),
+        );
 
-        let chapters: Vec<Chapter> = response.json().await?;
-        Ok(chapters)
+        let file = files?.into_iter().next().ok_or_else(|| {
+            AppError::NotFound(format!(
+                "No audio for {} {} in fileset {}",
+                book_id, chapter, fileset_id
+            ))
+        })?;
+
+        let mut timestamps = timestamps.unwrap_or_else(|e| {
+            tracing::debug!(
+                "No timestamps for {} {} {}: {}",
+                fileset_id,
+                book_id,
+                chapter,
+                e
+            );
+            Vec::new()
+        });
+        timestamps.sort_by_key(|timestamp| timestamp.verse_start);
+
+        Ok(AudioChapter {
+            fileset_id: fileset_id.to_string(),
+            book: book_id.to_string(),
+            chapter,
+            url: file.path,
+            duration: file.duration,
+            timestamps: timestamps
+                .into_iter()
+                .map(|timestamp| VerseTimestamp {
+                    verse: timestamp.verse_start,
+                    start: timestamp.timestamp,
+                })
+                .collect(),
+        })
+    }
+
+    /// Get chapters for a specific book
+    pub async fn get_chapters(&self, bible_id: &str, book_id: &str) -> Result<Vec<Chapter>> {
+        let path = format!("/bibles/{}/books/{}/chapters", bible_id, book_id);
+        Ok(self.get_data(&path, &[], "chapters").await?.data)
     }
 
     /// Get verses for a specific chapter
@@ -113,26 +284,11 @@ impl BibleBrainClient {
         book_id: &str,
         chapter_id: &str,
     ) -> Result<Vec<Verse>> {
-        let url = format!(
-            "{}/bibles/{}/books/{}/chapters/{}/verses",
-            self.base_url, bible_id, book_id, chapter_id
+        let path = format!(
+            "/bibles/{}/books/{}/chapters/{}/verses",
+            bible_id, book_id, chapter_id
         );
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch verses: {}",
-                response.status()
-            )));
-        }
-
-        let verses: Vec<Verse> = response.json().await?;
-        Ok(verses)
+        Ok(self.get_data(&path, &[], "verses").await?.data)
     }
 
     /// Get verse content
@@ -143,72 +299,42 @@ impl BibleBrainClient {
         chapter_id: &str,
         verse_id: &str,
     ) -> Result<VerseContent> {
-        let url = format!(
-            "{}/bibles/{}/books/{}/chapters/{}/verses/{}",
-            self.base_url, bible_id, book_id, chapter_id, verse_id
+        let path = format!(
+            "/bibles/{}/books/{}/chapters/{}/verses/{}",
+            bible_id, book_id, chapter_id, verse_id
         );
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
+        Ok(self.get_data(&path, &[], "verse content").await?.data)
+    }
 
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch verse content: {}",
-                response.status()
-            )));
+    /// Get a video listing, reusing a cached copy while its signed links
+    /// are still valid
+    async fn get_video_listing(&self, path: &str, what: &str) -> Result<Arc<Vec<Video>>> {
+        if let Some(videos) = self.videos.get(path) {
+            return Ok(videos);
         }
 
-        let content: VerseContent = response.json().await?;
-        Ok(content)
+        let fetched_at = SystemTime::now();
+        let records: Vec<VideoRecord> = self.get_data(path, &[], what).await?.data;
+        let videos: Arc<Vec<Video>> = Arc::new(
+            records
+                .into_iter()
+                .map(|record| Video::from_record(record, fetched_at))
+                .collect(),
+        );
+        self.videos.insert(path, videos.clone());
+        Ok(videos)
     }
 
     /// Get videos for a specific Bible
-    pub async fn get_bible_videos(&self, bible_id: &str) -> Result<Vec<Video>> {
-        let url = format!("{}/bibles/{}/videos", self.base_url, bible_id);
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch videos: {}",
-                response.status()
-            )));
-        }
-
-        let videos: Vec<Video> = response.json().await?;
-        Ok(videos)
+    pub async fn get_bible_videos(&self, bible_id: &str) -> Result<Arc<Vec<Video>>> {
+        let path = format!("/bibles/{}/videos", bible_id);
+        self.get_video_listing(&path, "videos").await
     }
 
     /// Get videos for a specific book
-    pub async fn get_book_videos(
-        &self,
-        bible_id: &str,
-        book_id: &str,
-    ) -> Result<Vec<Video>> {
-        let url = format!("{}/bibles/{}/books/{}/videos", self.base_url, bible_id, book_id);
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch book videos: {}",
-                response.status()
-            )));
-        }
-
-        let videos: Vec<Video> = response.json().await?;
-        Ok(videos)
+    pub async fn get_book_videos(&self, bible_id: &str, book_id: &str) -> Result<Arc<Vec<Video>>> {
+        let path = format!("/bibles/{}/books/{}/videos", bible_id, book_id);
+        self.get_video_listing(&path, "book videos").await
     }
 
     /// Get videos for a specific chapter
@@ -216,81 +342,326 @@ impl BibleBrainClient {
         &self,
         bible_id: &str,
         book_id: &str,
-        chapter_id: &str,
-    ) -> Result<Vec<Video>> {
-        let url = format!(
-            "{}/bibles/{}/books/{}/chapters/{}/videos",
-            self.base_url, bible_id, book_id, chapter_id
+        chapter: u32,
+    ) -> Result<Arc<Vec<Video>>> {
+        let path = format!(
+            "/bibles/{}/books/{}/chapters/{}/videos",
+            bible_id, book_id, chapter
         );
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
+        self.get_video_listing(&path, "chapter videos").await
+    }
 
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch chapter videos: {}",
-                response.status()
-            )));
-        }
+    /// Get all available videos
+    pub async fn get_videos(&self) -> Result<Arc<Vec<Video>>> {
+        self.get_video_listing("/videos", "videos").await
+    }
 
-        let videos: Vec<Video> = response.json().await?;
-        Ok(videos)
+    /// Get one of a Bible's videos with a link that is good for at least a
+    /// few more minutes, re-signing it upstream if the cached one expired
+    pub async fn get_video(&self, bible_id: &str, video_id: &str) -> Result<Video> {
+        let path = format!("/bibles/{}/videos", bible_id);
+        let find = |videos: &[Video]| videos.iter().find(|video| video.id == video_id).cloned();
+
+        let videos = self.get_video_listing(&path, "videos").await?;
+        let video = match find(&videos) {
+            Some(video) if !video.link_expired(SystemTime::now()) => return Ok(video),
+            // Possibly stale: refetch before concluding anything
+            _ => {
+                self.videos.invalidate(&path);
+                let videos = self.get_video_listing(&path, "videos").await?;
+                find(&videos)
+            }
+        };
+
+        video.ok_or_else(|| {
+            AppError::NotFound(format!("Video {} not found in {}", video_id, bible_id))
+        })
     }
+}
 
-    /// Get all available videos
-    pub async fn get_videos(&self) -> Result<Vec<Video>> {
-        let url = format!("{}/videos", self.base_url);
-        let response = self
-            .client
-            .get(&url)
-            .header("dbp-api-key", &self.api_key)
-            .send()
-            .await?;
+#[async_trait]
+impl BibleProvider for BibleBrainClient {
+    fn name(&self) -> &str {
+        "bible_brain"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<models::Translation>> {
+        let bibles = self.get_all_bibles().await?;
+        Ok(bibles.into_iter().map(models::Translation::from).collect())
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<models::Book>> {
+        let books = BibleBrainClient::get_books(self, translation).await?;
+        Ok(books
+            .into_iter()
+            .enumerate()
+            .map(|(index, book)| models::Book {
+                id: book.id,
+                common_name: book.name_short.or_else(|| Some(book.name.clone())),
+                name: book.name,
+                order: Some(index as u32 + 1),
+                number_of_chapters: Some(book.chapters.len() as u32),
+            })
+            .collect())
+    }
+
+    async fn get_chapter(
+        &self,
+        translation: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<models::Chapter> {
+        // DBP serves text per fileset, so find the Bible's plain-text one
+        let filesets = self.get_filesets(translation).await?;
+        let fileset = filesets
+            .iter()
+            .find(|fileset| fileset.fileset_type == "text_plain")
+            .ok_or_else(|| AppError::NotFound(format!("No text fileset for {}", translation)))?;
+
+        let verses = self
+            .get_text_chapter(&fileset.id, book, chapter)
+            .await?
+            .into_iter()
+            .map(|verse| models::Verse {
+                verse: verse.verse_start,
+                text: verse.verse_text,
+                footnotes: None,
+            })
+            .collect();
+
+        Ok(models::Chapter {
+            translation: translation.to_string(),
+            book: book.to_string(),
+            chapter,
+            verses,
+            footnotes: None,
+            content: Vec::new(),
+        })
+    }
+}
 
-        if !response.status().is_success() {
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch videos: {}",
-                response.status()
-            )));
+impl From<Bible> for models::Translation {
+    fn from(bible: Bible) -> Self {
+        Self {
+            name: bible.name_local.unwrap_or_else(|| bible.name.clone()),
+            short_name: Some(bible.id.clone()),
+            id: bible.id,
+            language: bible.iso,
+            english_name: Some(bible.name),
+            language_name: bible.autonym,
+            language_english_name: bible.language,
+            description: None,
+            number_of_books: None,
+            canon: None,
         }
+    }
+}
 
-        let videos: Vec<Video> = response.json().await?;
-        Ok(videos)
+/// DBP sends some numeric fields as JSON numbers and others as strings
+pub(crate) fn string_or_number<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(text) => Ok(text),
+        serde_json::Value::Number(number) => Ok(number.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected string or number, got {}",
+            other
+        ))),
     }
 }
 
+/// Like `string_or_number`, for fields that must be a verse or chapter number.
+///
+/// Partial verses such as "12b" keep their leading number.
+fn number_from_string<'de, D>(deserializer: D) -> std::result::Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = string_or_number(deserializer)?;
+    let digits: String = text
+        .trim()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits
+        .parse()
+        .map_err(|_| serde::de::Error::custom(format!("expected a number, got {:?}", text)))
+}
+
+// Response Envelope Types
+#[derive(Debug, Clone)]
+struct DbpResponse<T> {
+    data: T,
+    meta: Meta,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Meta {
+    #[serde(default)]
+    pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Pagination {
+    pub total: u64,
+    pub count: u64,
+    pub per_page: u64,
+    pub current_page: u64,
+    pub total_pages: u64,
+}
+
+/// Which page of a listing to request
+#[derive(Debug, Clone, Copy)]
+pub struct PageRequest {
+    /// 1-based page number
+    pub page: u32,
+    pub limit: u32,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self { page: 1, limit: 25 }
+    }
+}
+
+/// One page of a listing, with DBP's pagination details when it sends them
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub pagination: Option<Pagination>,
+}
+
 // API Response Types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Language {
+    #[serde(deserialize_with = "string_or_number")]
     pub id: String,
     pub name: String,
+    #[serde(default)]
     pub iso: Option<String>,
-    pub iso_name: Option<String>,
+    #[serde(default)]
+    pub autonym: Option<String>,
+    /// Number of Bibles published in the language
+    #[serde(default)]
+    pub bibles: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bible {
+    #[serde(alias = "abbr")]
     pub id: String,
-    pub dbl_id: Option<String>,
-    pub abbreviation: String,
     pub name: String,
+    #[serde(default, alias = "vname")]
     pub name_local: Option<String>,
-    pub description: Option<String>,
-    pub description_local: Option<String>,
-    pub language: Option<Language>,
+    /// English name of the Bible's language
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub autonym: Option<String>,
+    #[serde(default)]
+    pub iso: Option<String>,
+    #[serde(default)]
+    pub date: Option<String>,
+    /// Filesets grouped by the storage bucket that hosts them
+    #[serde(default)]
+    pub filesets: HashMap<String, Vec<Fileset>>,
+}
+
+impl Bible {
+    /// Every fileset of the Bible, regardless of bucket, sorted by ID
+    pub fn filesets(&self) -> Vec<Fileset> {
+        let mut filesets: Vec<Fileset> = self.filesets.values().flatten().cloned().collect();
+        filesets.sort_by(|a, b| a.id.cmp(&b.id));
+        filesets.dedup_by(|a, b| a.id == b.id && a.fileset_type == b.fileset_type);
+        filesets
+    }
+}
+
+/// A published rendition of a Bible: plain or formatted text, audio,
+/// drama audio or video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fileset {
+    pub id: String,
+    /// e.g. "text_plain", "audio", "audio_drama", "video_stream"
+    #[serde(rename = "type")]
+    pub fileset_type: String,
+    /// How much of the Bible it covers, e.g. "NT", "OT", "C"
+    #[serde(default)]
+    pub size: Option<String>,
+    #[serde(default)]
+    pub codec: Option<String>,
+    #[serde(default)]
+    pub bitrate: Option<String>,
+}
+
+impl Fileset {
+    pub fn is_audio(&self) -> bool {
+        self.fileset_type.starts_with("audio")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Book {
+    #[serde(alias = "book_id")]
     pub id: String,
-    pub bible_id: String,
-    pub abbreviation: String,
     pub name: String,
-    pub name_long: String,
-    pub chapters: Option<Vec<Chapter>>,
+    #[serde(default)]
+    pub name_short: Option<String>,
+    #[serde(default)]
+    pub chapters: Vec<u32>,
+    #[serde(default)]
+    pub testament: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextVerse {
+    pub book_id: String,
+    #[serde(deserialize_with = "number_from_string")]
+    pub chapter: u32,
+    #[serde(deserialize_with = "number_from_string")]
+    pub verse_start: u32,
+    pub verse_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioFile {
+    pub book_id: String,
+    #[serde(deserialize_with = "number_from_string")]
+    pub chapter_start: u32,
+    /// Signed URL of the audio file
+    pub path: String,
+    /// Length in seconds
+    #[serde(default)]
+    pub duration: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timestamp {
+    #[serde(deserialize_with = "number_from_string")]
+    pub verse_start: u32,
+    /// Seconds from the start of the chapter audio
+    pub timestamp: f64,
+}
+
+/// A chapter's audio with the time each verse starts
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioChapter {
+    pub fileset_id: String,
+    pub book: String,
+    pub chapter: u32,
+    /// Signed URL; it expires, so clients should not store it
+    pub url: String,
+    pub duration: Option<u32>,
+    pub timestamps: Vec<VerseTimestamp>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerseTimestamp {
+    pub verse: u32,
+    /// Seconds from the start of the chapter audio
+    pub start: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -321,23 +692,3 @@ pub struct VerseContent {
     pub content: String,
     pub reference: Option<String>,
 }
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Video {
-    pub id: String,
-    pub bible_id: Option<String>,
-    pub book_id: Option<String>,
-    pub chapter_id: Option<String>,
-    pub verse_id: Option<String>,
-    pub url: Option<String>,
-    pub thumbnail_url: Option<String>,
-    pub duration: Option<u32>,
-    pub title: Option<String>,
-    pub description: Option<String>,
-    pub language_code: Option<String>,
-    pub resolution: Option<String>,
-    pub size: Option<u64>,
-    #[serde(rename = "type")]
-    pub video_type: Option<String>,
-}
-
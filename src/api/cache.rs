@@ -0,0 +1,177 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::CacheConfig;
+
+/// Upstream endpoint families, each with its own time-to-live
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheEndpoint {
+    Translations,
+    Books,
+    Chapter,
+    Commentaries,
+    Commentary,
+    /// A commentary's book list, which carries the book introductions
+    CommentaryBooks,
+    /// Commentary profile lists and articles
+    Profiles,
+    /// The dataset list and each dataset's book list
+    Datasets,
+    DatasetChapter,
+}
+
+/// Hit/miss counters and current occupancy of a `ResponseCache`
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub bytes: usize,
+    pub max_bytes: usize,
+}
+
+struct CacheEntry {
+    body: Arc<str>,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<String, CacheEntry>,
+    bytes: usize,
+    clock: u64,
+}
+
+/// Bounded in-memory cache of raw upstream response bodies, keyed by URL.
+///
+/// Entries expire after their endpoint's TTL, and the least recently used
+/// entries are evicted once the total body size exceeds `max_bytes`.
+pub struct ResponseCache {
+    inner: Mutex<CacheInner>,
+    config: CacheConfig,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            inner: Mutex::new(CacheInner::default()),
+            config,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    fn ttl(&self, endpoint: CacheEndpoint) -> Duration {
+        let secs = match endpoint {
+            CacheEndpoint::Translations => self.config.translations_ttl_secs,
+            CacheEndpoint::Books => self.config.books_ttl_secs,
+            CacheEndpoint::Chapter => self.config.chapter_ttl_secs,
+            CacheEndpoint::Commentaries => self.config.commentaries_ttl_secs,
+            CacheEndpoint::Commentary => self.config.commentary_ttl_secs,
+            CacheEndpoint::CommentaryBooks => self.config.commentary_books_ttl_secs,
+            CacheEndpoint::Profiles => self.config.profiles_ttl_secs,
+            CacheEndpoint::Datasets => self.config.datasets_ttl_secs,
+            CacheEndpoint::DatasetChapter => self.config.dataset_chapter_ttl_secs,
+        };
+        Duration::from_secs(secs)
+    }
+
+    /// Look up a cached body, counting the hit or miss
+    pub fn get(&self, key: &str) -> Option<Arc<str>> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let expired = match inner.entries.get_mut(key) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                entry.last_used = clock;
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Some(entry.body.clone());
+            }
+            Some(_) => true,
+            None => false,
+        };
+
+        if expired {
+            if let Some(entry) = inner.entries.remove(key) {
+                inner.bytes -= entry.body.len();
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Look up a cached body without counting it as a hit or miss or
+    /// marking it as recently used
+    pub fn peek(&self, key: &str) -> Option<Arc<str>> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .entries
+            .get(key)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.body.clone())
+    }
+
+    /// Store a body, evicting least recently used entries to stay within budget
+    pub fn insert(&self, endpoint: CacheEndpoint, key: &str, body: Arc<str>) {
+        let ttl = self.ttl(endpoint);
+        if ttl.is_zero()
+            || body.len() > self.config.max_entry_bytes
+            || body.len() > self.config.max_bytes
+        {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        if let Some(previous) = inner.entries.remove(key) {
+            inner.bytes -= previous.body.len();
+        }
+
+        while inner.bytes + body.len() > self.config.max_bytes {
+            let oldest = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            let Some(oldest) = oldest else { break };
+            if let Some(entry) = inner.entries.remove(&oldest) {
+                inner.bytes -= entry.body.len();
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        inner.bytes += body.len();
+        inner.entries.insert(
+            key.to_string(),
+            CacheEntry {
+                body,
+                expires_at: Instant::now() + ttl,
+                last_used: clock,
+            },
+        );
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let inner = self.inner.lock().unwrap();
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: inner.entries.len(),
+            bytes: inner.bytes,
+            max_bytes: self.config.max_bytes,
+        }
+    }
+}
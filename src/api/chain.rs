@@ -0,0 +1,356 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::models::{Book, Chapter, Translation};
+use super::provider::{BibleProvider, Served};
+use crate::error::{AppError, Result};
+
+/// Providers tried in order until one answers.
+///
+/// A provider that fails or takes longer than `timeout` is skipped in
+/// favour of the next; the request only fails when every provider has.
+/// Providers whose catalogs are snapshots (see `BibleProvider::is_snapshot`)
+/// are asked for translation and book lists only after the others, so a
+/// stored list never hides translations added upstream since.
+///
+/// The translation list is the union of every live provider's list, as
+/// each provider may carry translations the others lack; snapshots are
+/// only listed when no live provider answers.
+pub struct ProviderChain {
+    links: Vec<Arc<dyn BibleProvider>>,
+    catalog_links: Vec<Arc<dyn BibleProvider>>,
+    /// How many of `catalog_links` (the leading ones) are live
+    live_links: usize,
+    timeout: Duration,
+    name: String,
+}
+
+impl ProviderChain {
+    pub fn new(links: Vec<Arc<dyn BibleProvider>>, timeout: Duration) -> Self {
+        let name = links
+            .iter()
+            .map(|link| link.name())
+            .collect::<Vec<_>>()
+            .join(",");
+        let (live, snapshots): (Vec<_>, Vec<_>) =
+            links.iter().cloned().partition(|link| !link.is_snapshot());
+        Self {
+            live_links: live.len(),
+            catalog_links: live.into_iter().chain(snapshots).collect(),
+            links,
+            timeout,
+            name,
+        }
+    }
+
+    /// `call`, failing with a timeout error once the chain's timeout passes
+    async fn within_timeout<T>(&self, call: impl Future<Output = Result<T>>) -> Result<T> {
+        match tokio::time::timeout(self.timeout, call).await {
+            Ok(result) => result,
+            Err(_) => Err(AppError::UpstreamTimeout(format!(
+                "timed out after {}s",
+                self.timeout.as_secs_f32()
+            ))),
+        }
+    }
+
+    async fn first_success<'a, T, F, Fut>(
+        &'a self,
+        links: &'a [Arc<dyn BibleProvider>],
+        what: &str,
+        mut failures: Vec<(&'a str, AppError)>,
+        call: F,
+    ) -> Result<Served<T>>
+    where
+        F: Fn(Arc<dyn BibleProvider>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        for link in links {
+            let error = match self.within_timeout(call(link.clone())).await {
+                Ok(value) => {
+                    if !failures.is_empty() {
+                        tracing::info!(
+                            "{} served {} after: {}",
+                            link.name(),
+                            what,
+                            describe(&failures)
+                        );
+                    }
+                    return Ok(Served::new(link.name(), value));
+                }
+                Err(e) => e,
+            };
+            tracing::debug!("{} failed for {}: {}", link.name(), what, error);
+            failures.push((link.name(), error));
+        }
+
+        // Report as "not found" only when every provider agreed; otherwise
+        // the first real failure explains why nothing was served
+        let (_, representative) = failures
+            .iter()
+            .find(|(_, e)| !matches!(e, AppError::NotFound(_)))
+            .or(failures.first())
+            .expect("a chain has at least one provider");
+
+        Err(representative.with_message(format!(
+            "No provider could serve {} ({})",
+            what,
+            describe(&failures)
+        )))
+    }
+}
+
+fn describe(failures: &[(&str, AppError)]) -> String {
+    failures
+        .iter()
+        .map(|(name, error)| format!("{}: {}", name, error))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[async_trait]
+impl BibleProvider for ProviderChain {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(self.serve_translations().await?.value)
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        Ok(self.serve_books(translation).await?.value)
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        Ok(self.serve_chapter(translation, book, chapter).await?.value)
+    }
+
+    async fn serve_translations(&self) -> Result<Served<Vec<Translation>>> {
+        let (live, snapshots) = self.catalog_links.split_at(self.live_links);
+        let answers = futures::future::join_all(
+            live.iter()
+                .map(|link| self.within_timeout(link.get_translations())),
+        )
+        .await;
+
+        // Merge in chain order; an ID listed twice keeps its first entry
+        let mut seen = HashSet::new();
+        let mut translations = Vec::new();
+        let mut providers = Vec::new();
+        let mut failures = Vec::new();
+        for (link, answer) in live.iter().zip(answers) {
+            match answer {
+                Ok(list) => {
+                    providers.push(link.name());
+                    translations.extend(
+                        list.into_iter()
+                            .filter(|translation| seen.insert(translation.id.clone())),
+                    );
+                }
+                Err(e) => {
+                    tracing::debug!("{} failed for translation list: {}", link.name(), e);
+                    failures.push((link.name(), e));
+                }
+            }
+        }
+        if !providers.is_empty() {
+            if !failures.is_empty() {
+                tracing::info!("Translation list lacks {}", describe(&failures));
+            }
+            return Ok(Served::new(&providers.join(","), translations));
+        }
+
+        self.first_success(snapshots, "translation list", failures, |link| async move {
+            link.get_translations().await
+        })
+        .await
+    }
+
+    async fn serve_books(&self, translation: &str) -> Result<Served<Vec<Book>>> {
+        let what = format!("book list for {}", translation);
+        self.first_success(&self.catalog_links, &what, Vec::new(), |link| async move {
+            link.get_books(translation).await
+        })
+        .await
+    }
+
+    async fn serve_chapter(
+        &self,
+        translation: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<Served<Chapter>> {
+        let what = format!("{} {} {}", translation, book, chapter);
+        self.first_success(&self.links, &what, Vec::new(), |link| async move {
+            link.get_chapter(translation, book, chapter).await
+        })
+        .await
+    }
+}
+
+/// Translates our translation IDs to a provider's own IDs on the way in,
+/// and back on the way out, so every provider answers in one ID space
+pub struct MappedProvider {
+    inner: Arc<dyn BibleProvider>,
+    to_provider: HashMap<String, String>,
+    to_ours: HashMap<String, String>,
+}
+
+impl MappedProvider {
+    /// `ids` maps our translation IDs to the provider's
+    pub fn new(inner: Arc<dyn BibleProvider>, ids: HashMap<String, String>) -> Self {
+        let to_ours = ids
+            .iter()
+            .map(|(ours, theirs)| (theirs.clone(), ours.clone()))
+            .collect();
+        Self {
+            inner,
+            to_provider: ids,
+            to_ours,
+        }
+    }
+
+    fn theirs<'a>(&'a self, translation: &'a str) -> &'a str {
+        self.to_provider
+            .get(translation)
+            .map(String::as_str)
+            .unwrap_or(translation)
+    }
+}
+
+#[async_trait]
+impl BibleProvider for MappedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        let mut translations = self.inner.get_translations().await?;
+        for translation in &mut translations {
+            if let Some(ours) = self.to_ours.get(&translation.id) {
+                translation.id = ours.clone();
+            }
+        }
+        Ok(translations)
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        self.inner.get_books(self.theirs(translation)).await
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        let mut data = self
+            .inner
+            .get_chapter(self.theirs(translation), book, chapter)
+            .await?;
+        data.translation = translation.to_string();
+        Ok(data)
+    }
+}
+
+/// Serves the translations in `local_ids` from `local` and everything else
+/// from `upstream`, with one translation list covering both. A local
+/// translation hides an upstream one with the same ID.
+///
+/// While `upstream`'s list is unavailable, `serve_translations` lists the
+/// local translations alone. `get_translations` fails instead, since it is
+/// the catalog translation IDs are checked against, and a local-only
+/// catalog would make every upstream translation unknown.
+pub struct OverlayProvider {
+    local: Arc<dyn BibleProvider>,
+    local_ids: HashSet<String>,
+    upstream: Arc<dyn BibleProvider>,
+    name: String,
+}
+
+impl OverlayProvider {
+    pub fn new(
+        local: Arc<dyn BibleProvider>,
+        local_ids: HashSet<String>,
+        upstream: Arc<dyn BibleProvider>,
+    ) -> Self {
+        let name = format!("{},{}", local.name(), upstream.name());
+        Self {
+            local,
+            local_ids,
+            upstream,
+            name,
+        }
+    }
+
+    /// The local translations followed by the upstream ones they don't hide
+    fn merge(&self, mut local: Vec<Translation>, upstream: Vec<Translation>) -> Vec<Translation> {
+        local.extend(
+            upstream
+                .into_iter()
+                .filter(|translation| !self.local_ids.contains(&translation.id)),
+        );
+        local
+    }
+
+    fn route(&self, translation: &str) -> &Arc<dyn BibleProvider> {
+        if self.local_ids.contains(translation) {
+            &self.local
+        } else {
+            &self.upstream
+        }
+    }
+}
+
+#[async_trait]
+impl BibleProvider for OverlayProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        let local = self.local.get_translations().await?;
+        let upstream = self.upstream.get_translations().await?;
+        Ok(self.merge(local, upstream))
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        self.route(translation).get_books(translation).await
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        self.route(translation)
+            .get_chapter(translation, book, chapter)
+            .await
+    }
+
+    async fn serve_translations(&self) -> Result<Served<Vec<Translation>>> {
+        let local = self.local.get_translations().await?;
+        let upstream = match self.upstream.serve_translations().await {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                tracing::warn!(
+                    "Upstream translation list unavailable ({}); listing imports only",
+                    e
+                );
+                return Ok(Served::new(self.local.name(), local));
+            }
+        };
+        let provider = format!("{},{}", self.local.name(), upstream.provider);
+        Ok(Served::new(&provider, self.merge(local, upstream.value)))
+    }
+
+    async fn serve_books(&self, translation: &str) -> Result<Served<Vec<Book>>> {
+        self.route(translation).serve_books(translation).await
+    }
+
+    async fn serve_chapter(
+        &self,
+        translation: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<Served<Chapter>> {
+        self.route(translation)
+            .serve_chapter(translation, book, chapter)
+            .await
+    }
+}
@@ -0,0 +1,1432 @@
+//! Translations imported from local files.
+//!
+//! Every entry of the import directory is one translation, named by the
+//! entry (which must be a valid translation ID):
+//!
+//! - a directory of USFM files (`*.usfm`, `*.sfm`), one book per file
+//! - a directory holding a USX bundle (`*.usx`, in any subdirectory), with
+//!   the bundle's DBL `metadata.xml` when present
+//! - an OSIS XML file (`<osis>` root)
+//! - a Zefania XML file (`<XMLBIBLE>` root)
+//!
+//! Headings, Psalm titles, poetry, words of Jesus and footnotes are kept;
+//! cross-references, figures and introductions are dropped. A
+//! `translation.json` in a directory (or `{id}.json` beside a file) may set
+//! `name`, `language`, `englishName`, `languageName`,
+//! `languageEnglishName`, `shortName` and `description`.
+//!
+//! Everything is parsed when the server starts, and structural errors name
+//! the file and line.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::models::{Book, Chapter, ContentNode, Footnote, Translation, Verse, VerseSegment};
+use super::provider::BibleProvider;
+use crate::books;
+use crate::canons;
+use crate::error::{AppError, Result};
+use crate::ids;
+
+/// Translation details from the source files or a `translation.json`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationInfo {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub english_name: Option<String>,
+    #[serde(default)]
+    pub language_name: Option<String>,
+    #[serde(default)]
+    pub language_english_name: Option<String>,
+    #[serde(default)]
+    pub short_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl TranslationInfo {
+    /// Fields set in `other` replace those set here
+    fn merge(self, other: TranslationInfo) -> Self {
+        Self {
+            name: other.name.or(self.name),
+            language: other.language.or(self.language),
+            english_name: other.english_name.or(self.english_name),
+            language_name: other.language_name.or(self.language_name),
+            language_english_name: other.language_english_name.or(self.language_english_name),
+            short_name: other.short_name.or(self.short_name),
+            description: other.description.or(self.description),
+        }
+    }
+}
+
+/// One book read from a source file
+#[derive(Debug)]
+pub struct ImportedBook {
+    pub book: &'static books::Book,
+    /// Name given by the source, e.g. USFM `\h`
+    pub name: Option<String>,
+    /// Chapters by number; their `translation` is set once the book is
+    /// added to a provider
+    pub chapters: BTreeMap<u32, Chapter>,
+}
+
+/// The books and details read from a single-file translation
+#[derive(Debug)]
+pub struct ImportedTranslation {
+    pub info: TranslationInfo,
+    pub books: Vec<ImportedBook>,
+}
+
+/// Collapse runs of whitespace into single spaces, keeping one at either end
+fn collapse(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            space = true;
+        } else {
+            if space {
+                collapsed.push(' ');
+                space = false;
+            }
+            collapsed.push(c);
+        }
+    }
+    if space {
+        collapsed.push(' ');
+    }
+    collapsed
+}
+
+/// The number a chapter or verse label starts with: "12" -> 12, "3-4" -> 3
+fn leading_number(label: &str) -> Option<u32> {
+    let digits: String = label.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok().filter(|n| *n > 0)
+}
+
+/// Trim the text at the edges of a verse and around its line breaks, and
+/// drop what is left empty
+fn trim_segments(segments: Vec<VerseSegment>) -> Vec<VerseSegment> {
+    let breaks: Vec<bool> = segments
+        .iter()
+        .map(|segment| matches!(segment, VerseSegment::LineBreak))
+        .collect();
+    let mut trimmed = Vec::with_capacity(segments.len());
+    for (index, mut segment) in segments.into_iter().enumerate() {
+        let first = index == 0 || breaks[index - 1];
+        let last = breaks.get(index + 1).copied().unwrap_or(true);
+        if let VerseSegment::Text { text, .. } | VerseSegment::WordsOfJesus { text, .. } =
+            &mut segment
+        {
+            if first {
+                *text = text.trim_start().to_string();
+            }
+            if last {
+                *text = text.trim_end().to_string();
+            }
+            if text.is_empty() {
+                continue;
+            }
+        }
+        trimmed.push(segment);
+    }
+    while let Some(VerseSegment::LineBreak) = trimmed.last() {
+        trimmed.pop();
+    }
+    while let Some(VerseSegment::LineBreak) = trimmed.first() {
+        trimmed.remove(0);
+    }
+    trimmed
+}
+
+/// One chapter as it is being read
+struct ChapterBuilder {
+    number: u32,
+    content: Vec<ContentNode>,
+    verses: Vec<Verse>,
+    footnotes: Vec<Footnote>,
+    verse: Option<(u32, Vec<VerseSegment>)>,
+}
+
+impl ChapterBuilder {
+    fn finish_verse(&mut self) {
+        let Some((number, segments)) = self.verse.take() else {
+            return;
+        };
+        let segments = trim_segments(segments);
+
+        // Flat text keeps only the words, with line breaks as spaces
+        let text = segments
+            .iter()
+            .map(|segment| match segment {
+                VerseSegment::LineBreak => " ",
+                other => other.text().unwrap_or_default(),
+            })
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let footnotes: Vec<String> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                VerseSegment::FootnoteMarker { id } => Some(id.clone()),
+                _ => None,
+            })
+            .collect();
+
+        self.verses.push(Verse {
+            verse: number,
+            text,
+            footnotes: (!footnotes.is_empty()).then_some(footnotes),
+        });
+        self.content.push(ContentNode::Verse {
+            number,
+            content: segments,
+        });
+    }
+}
+
+/// Accumulates one book as a parser walks its source. Structural errors
+/// are returned as bare messages for the parser to place.
+struct BookBuilder {
+    book: &'static books::Book,
+    name: Option<String>,
+    chapters: BTreeMap<u32, Chapter>,
+    chapter: Option<ChapterBuilder>,
+}
+
+impl BookBuilder {
+    fn new(book: &'static books::Book) -> Self {
+        Self {
+            book,
+            name: None,
+            chapters: BTreeMap::new(),
+            chapter: None,
+        }
+    }
+
+    fn finish_chapter(&mut self) {
+        let Some(mut chapter) = self.chapter.take() else {
+            return;
+        };
+        chapter.finish_verse();
+        self.chapters.insert(
+            chapter.number,
+            Chapter {
+                translation: String::new(),
+                book: self.book.usfm.to_string(),
+                chapter: chapter.number,
+                verses: chapter.verses,
+                footnotes: (!chapter.footnotes.is_empty()).then_some(chapter.footnotes),
+                content: chapter.content,
+            },
+        );
+    }
+
+    fn start_chapter(&mut self, number: u32) -> std::result::Result<(), String> {
+        self.finish_chapter();
+        if self.chapters.contains_key(&number) {
+            return Err(format!("chapter {} appears twice", number));
+        }
+        self.chapter = Some(ChapterBuilder {
+            number,
+            content: Vec::new(),
+            verses: Vec::new(),
+            footnotes: Vec::new(),
+            verse: None,
+        });
+        Ok(())
+    }
+
+    fn start_verse(&mut self, number: u32) -> std::result::Result<(), String> {
+        let Some(chapter) = &mut self.chapter else {
+            return Err(format!("verse {} before the first chapter", number));
+        };
+        chapter.finish_verse();
+        if chapter.verses.iter().any(|verse| verse.verse == number) {
+            return Err(format!(
+                "verse {} appears twice in chapter {}",
+                number, chapter.number
+            ));
+        }
+        chapter.verse = Some((number, Vec::new()));
+        Ok(())
+    }
+
+    fn end_verse(&mut self) {
+        if let Some(chapter) = &mut self.chapter {
+            chapter.finish_verse();
+        }
+    }
+
+    fn has_chapter(&self) -> bool {
+        self.chapter.is_some()
+    }
+
+    /// Verse text; text outside a verse (introductions, labels) is dropped
+    fn text(&mut self, text: &str, poem: Option<u32>, words_of_jesus: bool) {
+        let Some((_, segments)) = self.chapter.as_mut().and_then(|c| c.verse.as_mut()) else {
+            return;
+        };
+        let mut text = collapse(text);
+        if text.is_empty() {
+            return;
+        }
+
+        match segments.last_mut() {
+            Some(VerseSegment::Text {
+                text: last,
+                poem: p,
+            }) if !words_of_jesus && *p == poem => {
+                if last.ends_with(' ') && text.starts_with(' ') {
+                    text.remove(0);
+                }
+                last.push_str(&text);
+            }
+            Some(VerseSegment::WordsOfJesus {
+                text: last,
+                poem: p,
+            }) if words_of_jesus && *p == poem => {
+                if last.ends_with(' ') && text.starts_with(' ') {
+                    text.remove(0);
+                }
+                last.push_str(&text);
+            }
+            _ if words_of_jesus => segments.push(VerseSegment::WordsOfJesus { text, poem }),
+            _ => segments.push(VerseSegment::Text { text, poem }),
+        }
+    }
+
+    /// A poetry line break inside the open verse, once it has text
+    fn line_break(&mut self) {
+        let Some((_, segments)) = self.chapter.as_mut().and_then(|c| c.verse.as_mut()) else {
+            return;
+        };
+        if !matches!(segments.last(), None | Some(VerseSegment::LineBreak)) {
+            segments.push(VerseSegment::LineBreak);
+        }
+    }
+
+    /// A blank line between stanzas: a line break inside a verse, or a
+    /// break between verses
+    fn stanza_break(&mut self) {
+        let Some(chapter) = &mut self.chapter else {
+            return;
+        };
+        if chapter.verse.is_some() {
+            self.line_break();
+        } else if !matches!(chapter.content.last(), None | Some(ContentNode::LineBreak)) {
+            chapter.content.push(ContentNode::LineBreak);
+        }
+    }
+
+    /// A section heading, which ends the verse before it
+    fn heading(&mut self, text: &str) {
+        let text = collapse(text).trim().to_string();
+        let Some(chapter) = &mut self.chapter else {
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+        chapter.finish_verse();
+        chapter.content.push(ContentNode::Heading { text });
+    }
+
+    /// A Psalm title
+    fn subtitle(&mut self, text: &str) {
+        let text = collapse(text).trim().to_string();
+        let Some(chapter) = &mut self.chapter else {
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+        chapter.finish_verse();
+        chapter.content.push(ContentNode::HebrewSubtitle {
+            content: vec![VerseSegment::Text { text, poem: None }],
+        });
+    }
+
+    /// A footnote, marked in the open verse when `marked`
+    fn footnote(&mut self, caller: Option<String>, text: &str, marked: bool) {
+        let text = collapse(text).trim().to_string();
+        let Some(chapter) = &mut self.chapter else {
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+        let id = (chapter.footnotes.len() + 1).to_string();
+        let verse = chapter.verse.as_mut().filter(|_| marked);
+        chapter.footnotes.push(Footnote {
+            id: id.clone(),
+            text,
+            caller: caller.filter(|c| !c.is_empty() && c != "-"),
+            verse: verse.as_ref().map(|(number, _)| *number),
+        });
+        if let Some((_, segments)) = verse {
+            segments.push(VerseSegment::FootnoteMarker { id });
+        }
+    }
+
+    fn finish(mut self) -> ImportedBook {
+        self.finish_chapter();
+        ImportedBook {
+            book: self.book,
+            name: self.name.map(|name| collapse(&name).trim().to_string()),
+            chapters: self.chapters,
+        }
+    }
+}
+
+/// What a USFM marker (or the USX style of the same name) does
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    /// Running header: the book name
+    Name,
+    Heading,
+    /// Psalm title
+    Subtitle,
+    /// Titles, introductions and other text outside the chapters
+    Ignored,
+    Paragraph,
+    Poetry(u32),
+    Break,
+    Footnote,
+    /// Cross-references, figures and alternative numbers
+    Skipped,
+    WordsOfJesus,
+    /// Character markers whose text carries `|attributes`
+    Attributed,
+    Inline,
+}
+
+fn style(marker: &str) -> Style {
+    let base = marker.trim_end_matches(|c: char| c.is_ascii_digit());
+    let level = marker[base.len()..].parse().unwrap_or(1);
+    match base {
+        "h" => Style::Name,
+        "s" | "ms" | "sp" | "qa" => Style::Heading,
+        "d" => Style::Subtitle,
+        "id" | "ide" | "rem" | "usfm" | "sts" | "restore" | "toc" | "toca" | "mt" | "mte"
+        | "imt" | "imte" | "is" | "ip" | "ipi" | "im" | "imi" | "ipq" | "imq" | "ipr" | "iq"
+        | "ib" | "ili" | "iot" | "io" | "iex" | "ie" | "mr" | "r" | "sr" | "cl" | "cd"
+        | "periph" => Style::Ignored,
+        "p" | "m" | "pi" | "mi" | "nb" | "pc" | "pm" | "pmo" | "pmc" | "pmr" | "po" | "pr"
+        | "cls" | "li" | "lim" | "lh" | "lf" | "ph" | "lit" | "qd" | "tr" => Style::Paragraph,
+        "q" | "qr" | "qc" | "qm" => Style::Poetry(level),
+        "b" => Style::Break,
+        "f" | "fe" | "ef" => Style::Footnote,
+        "x" | "ex" | "fig" | "ca" | "va" | "vp" | "cp" | "cat" => Style::Skipped,
+        "wj" => Style::WordsOfJesus,
+        "w" | "rb" | "xt" | "jmp" => Style::Attributed,
+        _ => Style::Inline,
+    }
+}
+
+/// Markers inside a footnote whose text is not part of the note
+fn is_note_reference(marker: &str) -> bool {
+    matches!(marker, "fr" | "fv" | "xo")
+}
+
+#[derive(Debug)]
+enum UsfmToken<'a> {
+    Marker { name: &'a str, closing: bool },
+    Text(String),
+}
+
+/// Split USFM into markers and text, each with its line. Text never spans
+/// lines; a line end becomes a trailing space.
+fn usfm_tokens(text: &str) -> Vec<(usize, UsfmToken<'_>)> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut plain = String::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\\' => {
+                let mut end = start + 1;
+                while let Some(&(index, next)) = chars.peek() {
+                    if !(next.is_ascii_alphanumeric() || next == '+' || next == '-') {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+                let name = text[start + 1..end].trim_start_matches('+');
+                if name.is_empty() {
+                    plain.push(c);
+                    continue;
+                }
+                let closing = chars.next_if(|&(_, next)| next == '*').is_some();
+                if !closing {
+                    chars.next_if(|&(_, next)| next == ' ' || next == '\t');
+                }
+                if !plain.is_empty() {
+                    tokens.push((line, UsfmToken::Text(std::mem::take(&mut plain))));
+                }
+                tokens.push((line, UsfmToken::Marker { name, closing }));
+            }
+            '\n' => {
+                plain.push(' ');
+                tokens.push((line, UsfmToken::Text(std::mem::take(&mut plain))));
+                line += 1;
+            }
+            '\r' => {}
+            other => plain.push(other),
+        }
+    }
+    if !plain.is_empty() {
+        tokens.push((line, UsfmToken::Text(plain)));
+    }
+    tokens
+}
+
+/// A footnote being read
+struct OpenNote {
+    line: usize,
+    caller: Option<String>,
+    text: String,
+    /// The caller is the first word after `\f`
+    expects_caller: bool,
+    /// Inside `\fr` or `\fv`, whose text is left out
+    in_reference: bool,
+    /// Opened inside a heading rather than verse text
+    in_heading: bool,
+}
+
+/// Parse one USFM book; `source` names the file in errors
+pub fn parse_usfm(text: &str, source: &str) -> Result<ImportedBook> {
+    let error =
+        |line: usize, message: String| AppError::Data(format!("{}:{}: {}", source, line, message));
+
+    let mut book: Option<BookBuilder> = None;
+    // A marker whose text runs to the end of its line, e.g. `\s1`
+    let mut line_marker: Option<(&str, usize, String)> = None;
+    // `\c` or `\v` waiting for its number
+    let mut number: Option<(&str, usize)> = None;
+    let mut note: Option<OpenNote> = None;
+    let mut skipping: Option<&str> = None;
+    let mut in_attributes = false;
+    let mut poem = None;
+    let mut words_of_jesus = false;
+
+    let finish_line = |book: &mut Option<BookBuilder>, marker: &str, line: usize, text: &str| {
+        if marker == "id" {
+            let code = text.split_whitespace().next().unwrap_or_default();
+            let found = books::by_code(code)
+                .ok_or_else(|| error(line, format!("unknown book code {:?}", code)))?;
+            if book.is_some() {
+                return Err(error(line, "a second \\id line".to_string()));
+            }
+            *book = Some(BookBuilder::new(found));
+            return Ok(());
+        }
+        let Some(book) = book else {
+            return Ok(());
+        };
+        match style(marker) {
+            Style::Name => book.name = Some(text.to_string()),
+            Style::Heading => book.heading(text),
+            Style::Subtitle => book.subtitle(text),
+            _ => {}
+        }
+        Ok(())
+    };
+
+    for (line, token) in usfm_tokens(text) {
+        if let Some((marker, start, text)) = &line_marker {
+            let ends_line = match &token {
+                _ if line != *start => true,
+                UsfmToken::Marker {
+                    name,
+                    closing: false,
+                } => {
+                    *name == "c"
+                        || *name == "v"
+                        || !matches!(
+                            style(name),
+                            Style::Inline
+                                | Style::WordsOfJesus
+                                | Style::Attributed
+                                | Style::Footnote
+                                | Style::Skipped
+                        )
+                }
+                _ => false,
+            };
+            if ends_line && note.is_none() {
+                finish_line(&mut book, marker, *start, text)?;
+                line_marker = None;
+            }
+        }
+
+        let text = match token {
+            UsfmToken::Marker { name, closing } => {
+                if let Some(skipped) = skipping {
+                    if closing && name == skipped {
+                        skipping = None;
+                    }
+                    continue;
+                }
+                if closing {
+                    match style(name) {
+                        Style::WordsOfJesus => words_of_jesus = false,
+                        Style::Attributed => in_attributes = false,
+                        Style::Footnote => {
+                            if let (Some(open), Some(book)) = (note.take(), &mut book) {
+                                book.footnote(open.caller, &open.text, !open.in_heading);
+                            }
+                        }
+                        _ if is_note_reference(name) => {
+                            if let Some(open) = &mut note {
+                                open.in_reference = false;
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                if let Some((marker, start)) = number {
+                    return Err(error(start, format!("missing number after \\{}", marker)));
+                }
+
+                if let Some(open) = &mut note {
+                    match style(name) {
+                        _ if name == "c" || name == "v" => {
+                            return Err(error(open.line, "footnote is not closed".to_string()))
+                        }
+                        Style::Inline => open.in_reference = is_note_reference(name),
+                        Style::Skipped => skipping = Some(name),
+                        Style::WordsOfJesus | Style::Attributed => {}
+                        _ => return Err(error(open.line, "footnote is not closed".to_string())),
+                    }
+                    continue;
+                }
+
+                match name {
+                    "c" | "v" => number = Some((name, line)),
+                    "id" => line_marker = Some((name, line, String::new())),
+                    _ => match style(name) {
+                        Style::Name | Style::Heading | Style::Subtitle | Style::Ignored => {
+                            line_marker = Some((name, line, String::new()))
+                        }
+                        Style::Paragraph => poem = None,
+                        Style::Poetry(level) => {
+                            poem = Some(level);
+                            if let Some(book) = &mut book {
+                                book.line_break();
+                            }
+                        }
+                        Style::Break => {
+                            if let Some(book) = &mut book {
+                                book.stanza_break();
+                            }
+                        }
+                        Style::Footnote => {
+                            note = Some(OpenNote {
+                                line,
+                                caller: None,
+                                text: String::new(),
+                                expects_caller: true,
+                                in_reference: false,
+                                in_heading: line_marker.is_some(),
+                            })
+                        }
+                        Style::Skipped => skipping = Some(name),
+                        Style::WordsOfJesus => words_of_jesus = true,
+                        Style::Attributed => in_attributes = false,
+                        Style::Inline => {}
+                    },
+                }
+                continue;
+            }
+            UsfmToken::Text(text) => text,
+        };
+
+        if skipping.is_some() || in_attributes {
+            continue;
+        }
+        let mut text = text.as_str();
+        if let Some((before, _)) = text.split_once('|') {
+            text = before;
+            in_attributes = true;
+        }
+
+        if let Some((marker, start)) = number.take() {
+            let trimmed = text.trim_start();
+            let label = trimmed.split_whitespace().next().unwrap_or_default();
+            let Some(value) = leading_number(label) else {
+                return Err(error(
+                    start,
+                    format!("invalid number after \\{}: {:?}", marker, label),
+                ));
+            };
+            let Some(book) = &mut book else {
+                return Err(error(start, format!("\\{} before the \\id line", marker)));
+            };
+            let result = if marker == "c" {
+                book.start_chapter(value)
+            } else {
+                book.start_verse(value)
+            };
+            result.map_err(|e| error(start, e))?;
+            if marker == "c" {
+                continue;
+            }
+            text = &trimmed[label.len()..];
+        }
+
+        if let Some(open) = &mut note {
+            let mut text = text;
+            if open.expects_caller {
+                let trimmed = text.trim_start();
+                let caller = trimmed.split_whitespace().next().unwrap_or_default();
+                if !caller.is_empty() {
+                    open.caller = Some(caller.to_string());
+                    open.expects_caller = false;
+                }
+                text = &trimmed[caller.len()..];
+            }
+            if !open.in_reference {
+                open.text.push_str(text);
+            }
+        } else if let Some((_, _, collected)) = &mut line_marker {
+            collected.push_str(text);
+        } else if let Some(book) = &mut book {
+            book.text(text, poem, words_of_jesus);
+        }
+    }
+
+    if let Some(open) = note {
+        return Err(error(open.line, "footnote is not closed".to_string()));
+    }
+    if let Some((marker, start)) = number {
+        return Err(error(start, format!("missing number after \\{}", marker)));
+    }
+    if let Some((marker, start, text)) = &line_marker {
+        finish_line(&mut book, marker, *start, text)?;
+    }
+    match book {
+        Some(book) => Ok(book.finish()),
+        None => Err(error(1, "missing \\id line".to_string())),
+    }
+}
+
+/// The text of an element and its descendants, leaving out notes
+fn plain_text(node: roxmltree::Node) -> String {
+    let mut text = String::new();
+    for descendant in node.descendants() {
+        if descendant.is_text()
+            && !descendant
+                .ancestors()
+                .any(|a| a.has_tag_name("note") || a.has_tag_name("NOTE"))
+        {
+            text.push_str(descendant.text().unwrap_or_default());
+        }
+    }
+    text
+}
+
+/// The text of a footnote, leaving out the references it starts with
+fn note_text(node: roxmltree::Node) -> String {
+    let mut text = String::new();
+    for descendant in node.descendants().filter(roxmltree::Node::is_text) {
+        let reference = descendant.ancestors().take_while(|a| *a != node).any(|a| {
+            is_note_reference(a.attribute("style").unwrap_or_default())
+                || a.attribute("type") == Some("annotateRef")
+        });
+        if !reference {
+            text.push_str(descendant.text().unwrap_or_default());
+        }
+    }
+    text
+}
+
+/// Walks a USX, OSIS or Zefania document into books
+struct XmlReader<'a, 'input> {
+    document: &'a roxmltree::Document<'input>,
+    source: &'a str,
+    books: Vec<ImportedBook>,
+    book: Option<BookBuilder>,
+    poem: Option<u32>,
+    words_of_jesus: bool,
+}
+
+impl<'a, 'input> XmlReader<'a, 'input> {
+    fn new(document: &'a roxmltree::Document<'input>, source: &'a str) -> Self {
+        Self {
+            document,
+            source,
+            books: Vec::new(),
+            book: None,
+            poem: None,
+            words_of_jesus: false,
+        }
+    }
+
+    fn error(&self, node: roxmltree::Node, message: String) -> AppError {
+        let line = self.document.text_pos_at(node.range().start).row;
+        AppError::Data(format!("{}:{}: {}", self.source, line, message))
+    }
+
+    fn number(&self, node: roxmltree::Node, what: &str, label: &str) -> Result<u32> {
+        leading_number(label.trim())
+            .ok_or_else(|| self.error(node, format!("invalid {} number {:?}", what, label)))
+    }
+
+    fn begin_book(&mut self, node: roxmltree::Node, code: &str) -> Result<()> {
+        let book = books::by_code(code)
+            .ok_or_else(|| self.error(node, format!("unknown book code {:?}", code)))?;
+        self.end_book();
+        self.book = Some(BookBuilder::new(book));
+        self.poem = None;
+        Ok(())
+    }
+
+    fn end_book(&mut self) {
+        if let Some(book) = self.book.take() {
+            self.books.push(book.finish());
+        }
+    }
+
+    fn book(&mut self, node: roxmltree::Node, what: &str) -> Result<&mut BookBuilder> {
+        if self.book.is_none() {
+            return Err(self.error(node, format!("{} outside a book", what)));
+        }
+        Ok(self.book.as_mut().expect("checked above"))
+    }
+
+    fn start_chapter(&mut self, node: roxmltree::Node, label: &str) -> Result<()> {
+        let number = self.number(node, "chapter", label)?;
+        self.poem = None;
+        let result = self.book(node, "chapter")?.start_chapter(number);
+        result.map_err(|e| self.error(node, e))
+    }
+
+    fn start_verse(&mut self, node: roxmltree::Node, label: &str) -> Result<()> {
+        let number = self.number(node, "verse", label)?;
+        let result = self.book(node, "verse")?.start_verse(number);
+        result.map_err(|e| self.error(node, e))
+    }
+
+    fn end_verse(&mut self) {
+        if let Some(book) = &mut self.book {
+            book.end_verse();
+        }
+    }
+
+    fn text(&mut self, node: roxmltree::Node) {
+        if let Some(book) = &mut self.book {
+            book.text(
+                node.text().unwrap_or_default(),
+                self.poem,
+                self.words_of_jesus,
+            );
+        }
+    }
+
+    fn line_break(&mut self) {
+        if let Some(book) = &mut self.book {
+            book.line_break();
+        }
+    }
+
+    fn stanza_break(&mut self) {
+        if let Some(book) = &mut self.book {
+            book.stanza_break();
+        }
+    }
+
+    fn footnote(&mut self, node: roxmltree::Node, caller: Option<&str>) {
+        if let Some(book) = &mut self.book {
+            book.footnote(caller.map(str::to_string), &note_text(node), true);
+        }
+    }
+
+    fn with_words_of_jesus(
+        &mut self,
+        node: roxmltree::Node,
+        read: fn(&mut Self, roxmltree::Node) -> Result<()>,
+    ) -> Result<()> {
+        let outer = std::mem::replace(&mut self.words_of_jesus, true);
+        let result = read(self, node);
+        self.words_of_jesus = outer;
+        result
+    }
+
+    fn usx(&mut self, node: roxmltree::Node) -> Result<()> {
+        for child in node.children() {
+            if child.is_text() {
+                self.text(child);
+                continue;
+            }
+            if !child.is_element() {
+                continue;
+            }
+            let child_style = style(child.attribute("style").unwrap_or_default());
+            match child.tag_name().name() {
+                "book" => self.begin_book(child, child.attribute("code").unwrap_or_default())?,
+                "chapter" => {
+                    if let Some(number) = child.attribute("number") {
+                        self.start_chapter(child, number)?;
+                    }
+                }
+                "verse" => match child.attribute("number") {
+                    Some(number) => self.start_verse(child, number)?,
+                    None => self.end_verse(),
+                },
+                "para" => match child_style {
+                    Style::Name => {
+                        let name = plain_text(child);
+                        self.book(child, "book name")?.name = Some(name);
+                    }
+                    Style::Heading => self.book(child, "heading")?.heading(&plain_text(child)),
+                    Style::Subtitle => self.book(child, "title")?.subtitle(&plain_text(child)),
+                    Style::Ignored => {}
+                    Style::Break => self.stanza_break(),
+                    Style::Poetry(level) => {
+                        self.poem = Some(level);
+                        self.line_break();
+                        self.usx(child)?;
+                    }
+                    _ => {
+                        self.poem = None;
+                        self.usx(child)?;
+                    }
+                },
+                "note" => {
+                    if child_style == Style::Footnote {
+                        self.footnote(child, child.attribute("caller"));
+                    }
+                }
+                "char" => match child_style {
+                    Style::WordsOfJesus => self.with_words_of_jesus(child, Self::usx)?,
+                    Style::Skipped => {}
+                    _ => self.usx(child)?,
+                },
+                "figure" | "ms" | "optbreak" => {}
+                _ => self.usx(child)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn osis(&mut self, node: roxmltree::Node) -> Result<()> {
+        for child in node.children() {
+            if child.is_text() {
+                self.text(child);
+                continue;
+            }
+            if !child.is_element() {
+                continue;
+            }
+            // The last part of an OSIS ID is the number: "Gen.1.1" -> "1"
+            let osis_id = child
+                .attribute("osisID")
+                .or_else(|| child.attribute("sID"))
+                .and_then(|id| id.split_whitespace().next())
+                .map(|id| id.rsplit('.').next().unwrap_or(id));
+            match child.tag_name().name() {
+                "header" => {}
+                "div" if child.attribute("type") == Some("book") => {
+                    let code = child.attribute("osisID").unwrap_or_default();
+                    self.begin_book(child, code)?;
+                    self.osis(child)?;
+                    self.end_book();
+                }
+                "chapter" => {
+                    if let Some(number) = osis_id {
+                        self.start_chapter(child, number)?;
+                    }
+                    self.osis(child)?;
+                }
+                "verse" => {
+                    let Some(number) = osis_id else {
+                        self.end_verse();
+                        continue;
+                    };
+                    self.start_verse(child, number)?;
+                    self.osis(child)?;
+                    if !child.has_attribute("sID") {
+                        self.end_verse();
+                    }
+                }
+                "title" => {
+                    let text = plain_text(child);
+                    // Titles of book groups and the work itself name no book
+                    let Some(book) = &mut self.book else {
+                        continue;
+                    };
+                    match child.attribute("type") {
+                        Some("chapter") => {}
+                        Some("psalm") => book.subtitle(&text),
+                        _ if book.has_chapter() => book.heading(&text),
+                        _ => {
+                            book.name.get_or_insert(text);
+                        }
+                    }
+                }
+                "note" => {
+                    if child.attribute("type") != Some("crossReference") {
+                        self.footnote(child, child.attribute("n"));
+                    }
+                }
+                "q" if child.attribute("who") == Some("Jesus") => {
+                    self.with_words_of_jesus(child, Self::osis)?
+                }
+                "l" => {
+                    let level = child.attribute("level").and_then(leading_number);
+                    self.poem = Some(level.unwrap_or(1));
+                    self.line_break();
+                    self.osis(child)?;
+                }
+                "lg" => {
+                    self.osis(child)?;
+                    self.poem = None;
+                    self.stanza_break();
+                }
+                "lb" => self.line_break(),
+                "p" => {
+                    self.poem = None;
+                    self.osis(child)?;
+                }
+                "figure" | "milestone" => {}
+                _ => self.osis(child)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn zefania(&mut self, root: roxmltree::Node) -> Result<()> {
+        for book in root.children().filter(|n| n.has_tag_name("BIBLEBOOK")) {
+            // Books 1-66 are numbered canonically; others need a `bsname` code
+            let number = book.attribute("bnumber").and_then(leading_number);
+            let found = books::all()
+                .iter()
+                .find(|b| number.is_some_and(|n| n <= 66 && u32::from(b.order) == n))
+                .or_else(|| book.attribute("bsname").and_then(books::by_code))
+                .ok_or_else(|| {
+                    self.error(
+                        book,
+                        format!(
+                            "unknown book number {:?}",
+                            book.attribute("bnumber").unwrap_or_default()
+                        ),
+                    )
+                })?;
+            self.end_book();
+            let mut builder = BookBuilder::new(found);
+            builder.name = book.attribute("bname").map(str::to_string);
+            self.book = Some(builder);
+
+            for chapter in book.children().filter(|n| n.has_tag_name("CHAPTER")) {
+                let number = chapter.attribute("cnumber").unwrap_or_default();
+                self.start_chapter(chapter, number)?;
+                for child in chapter.children().filter(roxmltree::Node::is_element) {
+                    match child.tag_name().name() {
+                        "CAPTION" => self.book(child, "caption")?.heading(&plain_text(child)),
+                        "VERS" => {
+                            let number = child.attribute("vnumber").unwrap_or_default();
+                            self.start_verse(child, number)?;
+                            self.zefania_verse(child);
+                            self.end_verse();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            self.end_book();
+        }
+        Ok(())
+    }
+
+    fn zefania_verse(&mut self, node: roxmltree::Node) {
+        for child in node.children() {
+            if child.is_text() {
+                self.text(child);
+                continue;
+            }
+            match child.tag_name().name() {
+                "NOTE" => self.footnote(child, None),
+                "BR" => self.line_break(),
+                "XREF" => {}
+                _ => self.zefania_verse(child),
+            }
+        }
+    }
+}
+
+fn parse_document<'input>(text: &'input str, source: &str) -> Result<roxmltree::Document<'input>> {
+    roxmltree::Document::parse(text)
+        .map_err(|e| AppError::Data(format!("{}:{}: {}", source, e.pos().row, e)))
+}
+
+/// Parse one USX book; `source` names the file in errors
+pub fn parse_usx(text: &str, source: &str) -> Result<ImportedBook> {
+    let document = parse_document(text, source)?;
+    let mut reader = XmlReader::new(&document, source);
+    reader.usx(document.root_element())?;
+    reader.end_book();
+    reader
+        .books
+        .pop()
+        .ok_or_else(|| AppError::Data(format!("{}:1: missing book element", source)))
+}
+
+/// Parse an OSIS or Zefania XML translation; `source` names the file in
+/// errors
+pub fn parse_xml(text: &str, source: &str) -> Result<ImportedTranslation> {
+    let document = parse_document(text, source)?;
+    let root = document.root_element();
+    let mut reader = XmlReader::new(&document, source);
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|child| child.has_tag_name(name))
+            .map(|child| collapse(&plain_text(child)).trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    let info = match root.tag_name().name() {
+        "osis" => {
+            let text = root.children().find(|n| n.has_tag_name("osisText"));
+            let work = text
+                .and_then(|text| text.children().find(|n| n.has_tag_name("header")))
+                .and_then(|header| header.children().find(|n| n.has_tag_name("work")));
+            reader.osis(root)?;
+            TranslationInfo {
+                name: work.and_then(|work| child_text(work, "title")),
+                language: text.and_then(|text| {
+                    text.attribute((roxmltree::NS_XML_URI, "lang"))
+                        .map(str::to_string)
+                }),
+                description: work.and_then(|work| child_text(work, "description")),
+                ..Default::default()
+            }
+        }
+        "XMLBIBLE" => {
+            let information = root.children().find(|n| n.has_tag_name("INFORMATION"));
+            reader.zefania(root)?;
+            TranslationInfo {
+                name: information
+                    .and_then(|info| child_text(info, "title"))
+                    .or_else(|| root.attribute("biblename").map(str::to_string)),
+                language: information
+                    .and_then(|info| child_text(info, "language"))
+                    .map(|language| language.to_lowercase()),
+                description: information.and_then(|info| child_text(info, "description")),
+                ..Default::default()
+            }
+        }
+        other => {
+            return Err(AppError::Data(format!(
+                "{}:{}: expected an OSIS or Zefania document, found <{}>",
+                source,
+                document.text_pos_at(root.range().start).row,
+                other
+            )))
+        }
+    };
+    reader.end_book();
+
+    Ok(ImportedTranslation {
+        info,
+        books: reader.books,
+    })
+}
+
+/// Translation details from a DBL bundle's `metadata.xml`
+fn parse_dbl_metadata(text: &str, source: &str) -> Result<TranslationInfo> {
+    let document = parse_document(text, source)?;
+    let find = |parent: &str, name: &str| {
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name(parent))
+            .flat_map(|node| node.children())
+            .find(|node| node.has_tag_name(name))
+            .and_then(|node| node.text())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    Ok(TranslationInfo {
+        name: find("identification", "nameLocal").or_else(|| find("identification", "name")),
+        english_name: find("identification", "name"),
+        short_name: find("identification", "abbreviation"),
+        description: find("identification", "description"),
+        language: find("language", "iso"),
+        language_name: find("language", "name"),
+        ..Default::default()
+    })
+}
+
+/// The catalog language for a translation's language code, for book names
+fn catalog_language(language: &str) -> Option<&'static str> {
+    let code = match language.to_ascii_lowercase().as_str() {
+        "eng" => "en",
+        "afr" => "af",
+        "nld" | "dut" => "nl",
+        "deu" | "ger" => "de",
+        "fra" | "fre" => "fr",
+        "spa" => "es",
+        "por" => "pt",
+        other => return books::LANGUAGES.iter().copied().find(|code| *code == other),
+    };
+    Some(code)
+}
+
+fn read(path: &Path) -> Result<String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Data(format!("Cannot read {}: {}", path.display(), e)))?;
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// Files under `dir` and its subdirectories, sorted by path
+fn files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| AppError::Data(format!("Cannot read {}: {}", dir.display(), e)))?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| AppError::Data(format!("Cannot read {}: {}", dir.display(), e)))?
+            .path();
+        if path.is_dir() {
+            files.extend(files_in(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Translations imported from USFM, USX, OSIS and Zefania files
+#[derive(Default)]
+pub struct FileProvider {
+    translations: Vec<Translation>,
+    books: HashMap<String, Vec<Book>>,
+    chapters: HashMap<(String, String, u32), Chapter>,
+}
+
+impl FileProvider {
+    /// Import every translation in `dir` (see the module docs)
+    pub fn load(dir: &str) -> Result<Self> {
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            AppError::Data(format!(
+                "Cannot read imported translations from {}: {}",
+                dir, e
+            ))
+        })?;
+        let mut paths: Vec<PathBuf> = entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<_>>()
+            .map_err(|e| AppError::Data(format!("Cannot read {}: {}", dir, e)))?;
+        paths.sort();
+
+        let mut provider = Self::default();
+        for path in paths {
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let (info, books) = if path.is_dir() {
+                match Self::read_directory(&path)? {
+                    Some(imported) => imported,
+                    None => {
+                        tracing::warn!("Skipping {}: no USFM or USX files", path.display());
+                        continue;
+                    }
+                }
+            } else if extension(&path) == "xml" {
+                let imported = parse_xml(&read(&path)?, &path.display().to_string())?;
+                let sidecar = path.with_extension("json");
+                let info = if sidecar.is_file() {
+                    imported.info.merge(Self::read_info(&sidecar)?)
+                } else {
+                    imported.info
+                };
+                (info, imported.books)
+            } else {
+                continue;
+            };
+
+            let id = ids::segment("translation", id)
+                .map_err(|e| AppError::Data(format!("{}: {}", path.display(), e.detail())))?;
+            if provider.books.contains_key(id) {
+                return Err(AppError::Data(format!(
+                    "{}: translation {} is already imported",
+                    path.display(),
+                    id
+                )));
+            }
+            tracing::info!(
+                "Imported {} ({} books) from {}",
+                id,
+                books.len(),
+                path.display()
+            );
+            provider.add(id, info, books);
+        }
+        Ok(provider)
+    }
+
+    /// A USFM directory or USX bundle, or `None` when `dir` has neither
+    fn read_directory(dir: &Path) -> Result<Option<(TranslationInfo, Vec<ImportedBook>)>> {
+        let files = files_in(dir)?;
+        let usfm: Vec<&PathBuf> = files
+            .iter()
+            .filter(|path| matches!(extension(path).as_str(), "usfm" | "sfm"))
+            .collect();
+        let usx: Vec<&PathBuf> = files
+            .iter()
+            .filter(|path| extension(path) == "usx")
+            .collect();
+
+        let (mut info, parse, sources): (_, fn(&str, &str) -> Result<ImportedBook>, _) =
+            if !usfm.is_empty() {
+                (TranslationInfo::default(), parse_usfm, usfm)
+            } else if !usx.is_empty() {
+                let metadata = files.iter().find(|path| {
+                    path.file_name().and_then(|name| name.to_str()) == Some("metadata.xml")
+                });
+                let info = match metadata {
+                    Some(path) => parse_dbl_metadata(&read(path)?, &path.display().to_string())?,
+                    None => TranslationInfo::default(),
+                };
+                (info, parse_usx, usx)
+            } else {
+                return Ok(None);
+            };
+
+        let mut books: Vec<ImportedBook> = Vec::new();
+        for path in sources {
+            let source = path.display().to_string();
+            let book = parse(&read(path)?, &source)?;
+            if books.iter().any(|b| b.book.usfm == book.book.usfm) {
+                return Err(AppError::Data(format!(
+                    "{}: {} is already imported from another file",
+                    source, book.book.usfm
+                )));
+            }
+            books.push(book);
+        }
+
+        let metadata = dir.join("translation.json");
+        if metadata.is_file() {
+            info = info.merge(Self::read_info(&metadata)?);
+        }
+        Ok(Some((info, books)))
+    }
+
+    fn read_info(path: &Path) -> Result<TranslationInfo> {
+        serde_json::from_str(&read(path)?).map_err(|e| {
+            AppError::Data(format!(
+                "{}:{}: invalid translation details: {}",
+                path.display(),
+                e.line(),
+                e
+            ))
+        })
+    }
+
+    fn add(&mut self, id: &str, info: TranslationInfo, mut imported: Vec<ImportedBook>) {
+        imported.sort_by_key(|book| book.book.order);
+        let language = info.language.as_deref().and_then(catalog_language);
+
+        let mut books = Vec::new();
+        for (index, book) in imported.into_iter().enumerate() {
+            let name = book
+                .name
+                .filter(|name| !name.is_empty())
+                .or_else(|| {
+                    language
+                        .and_then(|l| book.book.localized_name(l))
+                        .map(str::to_string)
+                })
+                .unwrap_or_else(|| book.book.name().to_string());
+            books.push(Book {
+                id: book.book.usfm.to_string(),
+                name,
+                common_name: Some(book.book.name().to_string()),
+                order: Some(index as u32 + 1),
+                number_of_chapters: Some(book.chapters.len() as u32),
+            });
+            for (number, mut chapter) in book.chapters {
+                chapter.translation = id.to_string();
+                self.chapters.insert(
+                    (id.to_string(), book.book.usfm.to_string(), number),
+                    chapter,
+                );
+            }
+        }
+
+        // The imported books are all there is, so they tell the canon
+        let canon = if books.is_empty() {
+            None
+        } else {
+            canons::infer(books.iter().map(|book| book.id.as_str()))
+        };
+        self.translations.push(Translation {
+            id: id.to_string(),
+            name: info.name.unwrap_or_else(|| id.to_string()),
+            language: info.language,
+            english_name: info.english_name,
+            language_name: info.language_name,
+            language_english_name: info.language_english_name,
+            short_name: info.short_name,
+            description: info.description,
+            number_of_books: Some(books.len() as u32),
+            canon: canon.map(|canon| canon.id.to_string()),
+        });
+        self.books.insert(id.to_string(), books);
+    }
+
+    /// IDs of the imported translations
+    pub fn translation_ids(&self) -> impl Iterator<Item = &str> {
+        self.translations.iter().map(|t| t.id.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.translations.is_empty()
+    }
+}
+
+#[async_trait]
+impl BibleProvider for FileProvider {
+    fn name(&self) -> &str {
+        "files"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(self.translations.clone())
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        self.books.get(translation).cloned().ok_or_else(|| {
+            AppError::NotFound(format!("Translation {} is not imported", translation))
+        })
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        let key = (translation.to_string(), book.to_ascii_uppercase(), chapter);
+        self.chapters.get(&key).cloned().ok_or_else(|| {
+            AppError::NotFound(format!(
+                "{} {} {} is not in the imported files",
+                translation, book, chapter
+            ))
+        })
+    }
+}
@@ -1,117 +1,166 @@
 use crate::error::{AppError, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tracing;
 
-#[derive(Debug, Clone)]
+use super::cache::{CacheEndpoint, CacheStats, ResponseCache};
+use super::http::{CoalescingStats, UpstreamHttp};
+use super::provider::BibleProvider;
+use crate::canons;
+use crate::config::{CacheConfig, HttpConfig};
+use crate::reference::{self, FoundReference};
+
+pub use super::models::{Book, Chapter, ContentNode, Footnote, Translation, Verse, VerseSegment};
+
+#[derive(Clone)]
 pub struct HelloAOBibleClient {
-    client: reqwest::Client,
+    http: UpstreamHttp,
     base_url: String,
+    cache: Arc<ResponseCache>,
+}
+
+impl std::fmt::Debug for HelloAOBibleClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HelloAOBibleClient")
+            .field("base_url", &self.base_url)
+            .finish()
+    }
 }
 
 impl HelloAOBibleClient {
     pub fn new(base_url: String) -> Self {
+        Self::with_cache(base_url, CacheConfig::default())
+    }
+
+    /// Create a client whose responses are cached according to `cache_config`
+    pub fn with_cache(base_url: String, cache_config: CacheConfig) -> Self {
+        Self::with_options(base_url, cache_config, HttpConfig::default())
+    }
+
+    /// Create a client with explicit cache and timeout/retry settings
+    pub fn with_options(
+        base_url: String,
+        cache_config: CacheConfig,
+        http_config: HttpConfig,
+    ) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            http: UpstreamHttp::new(http_config),
             base_url,
+            cache: Arc::new(ResponseCache::new(cache_config)),
         }
     }
 
+    /// Hit/miss counters and occupancy of the response cache
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// How many upstream fetches were shared between concurrent requests
+    pub fn coalescing_stats(&self) -> CoalescingStats {
+        self.http.coalescing_stats()
+    }
+
+    /// Fetch a JSON document, serving it from the response cache when possible
+    async fn fetch_json(
+        &self,
+        endpoint: CacheEndpoint,
+        url: &str,
+        what: &str,
+    ) -> Result<serde_json::Value> {
+        let body = match self.cache.get(url) {
+            Some(body) => {
+                tracing::debug!("Cache hit for: {}", url);
+                body
+            }
+            None => {
+                let response = self.http.get(url, &[], &[]).await.map_err(|e| {
+                    tracing::error!("Failed to fetch {} from HelloAO API: {}", what, e);
+                    e
+                })?;
+
+                let status = response.status;
+                tracing::debug!("API response status: {}", status);
+
+                if !status.is_success() {
+                    tracing::error!("API error response: {}", response.body);
+                    return Err(AppError::from_upstream_status(
+                        status,
+                        what,
+                        &response.body,
+                        response.retry_after_secs,
+                    ));
+                }
+
+                let body: Arc<str> = response.body.into();
+                self.cache.insert(endpoint, url, body.clone());
+                body
+            }
+        };
+
+        serde_json::from_str(&body).map_err(|e| {
+            tracing::error!("Failed to parse {} JSON: {}", what, e);
+            AppError::UpstreamSchemaChanged(format!("Failed to parse response: {}", e))
+        })
+    }
+
     /// Get list of available translations
     pub async fn get_translations(&self) -> Result<Vec<Translation>> {
         let url = format!("{}/available_translations.json", self.base_url);
         tracing::debug!("Fetching translations from: {}", url);
-        
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            tracing::error!("Failed to connect to HelloAO API: {}", e);
-            AppError::BibleBrainApi(format!("Failed to connect to API: {}", e))
-        })?;
-
-        let status = response.status();
-        tracing::debug!("API response status: {}", status);
-
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            tracing::error!("API error response: {}", error_text);
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch translations: {} - {}",
-                status, error_text
-            )));
-        }
 
         // The API returns either a direct array or an object with a "translations" field
-        let json: serde_json::Value = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse translations JSON: {}", e);
-            AppError::BibleBrainApi(format!("Failed to parse response: {}", e))
-        })?;
+        let json = self
+            .fetch_json(CacheEndpoint::Translations, &url, "translations")
+            .await?;
 
-        let translations = if json.is_array() {
+        let mut translations = if json.is_array() {
             // Direct array response
             serde_json::from_value::<Vec<Translation>>(json).map_err(|e| {
                 tracing::error!("Failed to deserialize translations array: {}", e);
-                AppError::BibleBrainApi(format!("Failed to deserialize response: {}", e))
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
             })?
         } else if let Some(translations_array) = json.get("translations") {
             // Wrapped in "translations" field
             serde_json::from_value::<Vec<Translation>>(translations_array.clone()).map_err(|e| {
                 tracing::error!("Failed to deserialize translations from wrapper: {}", e);
-                AppError::BibleBrainApi(format!("Failed to deserialize response: {}", e))
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
             })?
         } else {
-            return Err(AppError::BibleBrainApi(
-                "Unexpected API response format".to_string()
+            return Err(AppError::UpstreamSchemaChanged(
+                "Unexpected API response format".to_string(),
             ));
         };
-        
+
+        for translation in &mut translations {
+            if translation.canon.is_none() {
+                translation.canon = self.cached_canon(&translation.id);
+            }
+        }
+
         tracing::debug!("Successfully loaded {} translations", translations.len());
         Ok(translations)
     }
 
+    /// The canon of a translation's books, when its book list is cached;
+    /// the catalog does not list books, and fetching every list to tag it
+    /// would cost one request per translation
+    fn cached_canon(&self, translation: &str) -> Option<String> {
+        let url = format!("{}/{}/books.json", self.base_url, translation);
+        let body = self.cache.peek(&url)?;
+        let books = parse_books(serde_json::from_str(&body).ok()?).ok()?;
+        canons::infer(books.iter().map(|book| book.id.as_str())).map(|canon| canon.id.to_string())
+    }
+
     /// Get list of books for a translation
     pub async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
         let url = format!("{}/{}/books.json", self.base_url, translation);
         tracing::debug!("Fetching books from: {}", url);
-        
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            tracing::error!("Failed to connect to HelloAO API: {}", e);
-            AppError::BibleBrainApi(format!("Failed to connect to API: {}", e))
-        })?;
-
-        let status = response.status();
-        tracing::debug!("API response status: {}", status);
-
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            tracing::error!("API error response: {}", error_text);
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch books: {} - {}",
-                status, error_text
-            )));
-        }
 
         // The API returns either a direct array or an object with a "books" field
-        let json: serde_json::Value = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse books JSON: {}", e);
-            AppError::BibleBrainApi(format!("Failed to parse response: {}", e))
-        })?;
+        let json = self.fetch_json(CacheEndpoint::Books, &url, "books").await?;
+        let books = parse_books(json)?;
 
-        let books = if json.is_array() {
-            // Direct array response
-            serde_json::from_value::<Vec<Book>>(json).map_err(|e| {
-                tracing::error!("Failed to deserialize books array: {}", e);
-                AppError::BibleBrainApi(format!("Failed to deserialize response: {}", e))
-            })?
-        } else if let Some(books_array) = json.get("books") {
-            // Wrapped in "books" field
-            serde_json::from_value::<Vec<Book>>(books_array.clone()).map_err(|e| {
-                tracing::error!("Failed to deserialize books from wrapper: {}", e);
-                AppError::BibleBrainApi(format!("Failed to deserialize response: {}", e))
-            })?
-        } else {
-            return Err(AppError::BibleBrainApi(
-                "Unexpected API response format for books".to_string()
-            ));
-        };
-        
         tracing::debug!("Successfully loaded {} books", books.len());
         Ok(books)
     }
@@ -123,90 +172,54 @@ impl HelloAOBibleClient {
         book: &str,
         chapter: u32,
     ) -> Result<Chapter> {
-        let url = format!("{}/{}/{}/{}.json", self.base_url, translation, book, chapter);
+        let url = format!(
+            "{}/{}/{}/{}.json",
+            self.base_url, translation, book, chapter
+        );
         tracing::debug!("Fetching chapter from: {}", url);
-        
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            tracing::error!("Failed to connect to HelloAO API: {}", e);
-            AppError::BibleBrainApi(format!("Failed to connect to API: {}", e))
-        })?;
-
-        let status = response.status();
-        tracing::debug!("API response status: {}", status);
-
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            tracing::error!("API error response: {}", error_text);
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch chapter: {} - {}",
-                status, error_text
-            )));
-        }
 
         // The API returns a wrapped object with translation, book, and chapter fields
-        let json: serde_json::Value = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse chapter JSON: {}", e);
-            AppError::BibleBrainApi(format!("Failed to parse response: {}", e))
-        })?;
+        let json = self
+            .fetch_json(CacheEndpoint::Chapter, &url, "chapter")
+            .await?;
 
         // Extract the chapter data from the wrapped response
         let chapter_data = if let Some(chapter_obj) = json.get("chapter") {
             // Parse the chapter object
-            let chapter_number = chapter_obj.get("number")
+            let chapter_number = chapter_obj
+                .get("number")
                 .and_then(|n| n.as_u64())
                 .unwrap_or(chapter as u64) as u32;
-            
-            // Extract verses from content array
+
+            // Extract headings, verses and formatting from content array
             let empty_array: Vec<serde_json::Value> = Vec::new();
-            let content = chapter_obj.get("content")
+            let content = chapter_obj
+                .get("content")
                 .and_then(|c| c.as_array())
                 .unwrap_or(&empty_array);
-            
-            let mut verses = Vec::new();
-            for item in content {
-                if let Some(verse_obj) = item.as_object() {
-                    if verse_obj.get("type").and_then(|t| t.as_str()) == Some("verse") {
-                        if let (Some(verse_num), Some(content_arr)) = (
-                            verse_obj.get("number").and_then(|n| n.as_u64()),
-                            verse_obj.get("content").and_then(|c| c.as_array())
-                        ) {
-                            // Join all content strings for the verse
-                            let verse_text: String = content_arr
-                                .iter()
-                                .filter_map(|v| {
-                                    if v.is_string() {
-                                        Some(v.as_str().unwrap_or(""))
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect::<Vec<_>>()
-                                .join(" ");
-                            
-                            verses.push(Verse {
-                                verse: verse_num as u32,
-                                text: verse_text,
-                                footnotes: None,
-                            });
-                        }
-                    }
-                }
-            }
-            
+
+            let (content, verses) = parse_chapter_content(content);
+            let footnotes = parse_footnotes(chapter_obj.get("footnotes"));
+
             Chapter {
                 translation: translation.to_string(),
                 book: book.to_string(),
                 chapter: chapter_number,
                 verses,
-                footnotes: None,
+                footnotes,
+                content,
             }
         } else {
-            return Err(AppError::BibleBrainApi(
-                "Unexpected API response format for chapter".to_string()
+            return Err(AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for chapter".to_string(),
             ));
         };
-        
-        tracing::debug!("Successfully loaded chapter {} with {} verses", chapter, chapter_data.verses.len());
+
+        tracing::debug!(
+            "Successfully loaded chapter {} with {} verses",
+            chapter,
+            chapter_data.verses.len()
+        );
         Ok(chapter_data)
     }
 
@@ -214,52 +227,91 @@ impl HelloAOBibleClient {
     pub async fn get_commentaries(&self) -> Result<Vec<Commentary>> {
         let url = format!("{}/available_commentaries.json", self.base_url);
         tracing::debug!("Fetching commentaries from: {}", url);
-        
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            tracing::error!("Failed to connect to HelloAO API: {}", e);
-            AppError::BibleBrainApi(format!("Failed to connect to API: {}", e))
-        })?;
-
-        let status = response.status();
-        tracing::debug!("API response status: {}", status);
-
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            tracing::error!("API error response: {}", error_text);
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch commentaries: {} - {}",
-                status, error_text
-            )));
-        }
 
         // The API returns either a direct array or an object with a "commentaries" field
-        let json: serde_json::Value = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse commentaries JSON: {}", e);
-            AppError::BibleBrainApi(format!("Failed to parse response: {}", e))
-        })?;
+        let json = self
+            .fetch_json(CacheEndpoint::Commentaries, &url, "commentaries")
+            .await?;
 
         let commentaries = if json.is_array() {
             // Direct array response
             serde_json::from_value::<Vec<Commentary>>(json).map_err(|e| {
                 tracing::error!("Failed to deserialize commentaries array: {}", e);
-                AppError::BibleBrainApi(format!("Failed to deserialize response: {}", e))
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
             })?
         } else if let Some(commentaries_array) = json.get("commentaries") {
             // Wrapped in "commentaries" field
             serde_json::from_value::<Vec<Commentary>>(commentaries_array.clone()).map_err(|e| {
                 tracing::error!("Failed to deserialize commentaries from wrapper: {}", e);
-                AppError::BibleBrainApi(format!("Failed to deserialize response: {}", e))
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
             })?
         } else {
-            return Err(AppError::BibleBrainApi(
-                "Unexpected API response format for commentaries".to_string()
+            return Err(AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for commentaries".to_string(),
             ));
         };
-        
+
         tracing::debug!("Successfully loaded {} commentaries", commentaries.len());
         Ok(commentaries)
     }
 
+    /// Get the books covered by a commentary, with their introductions
+    pub async fn get_commentary_books(&self, commentary_id: &str) -> Result<Vec<CommentaryBook>> {
+        let url = format!("{}/c/{}/books.json", self.base_url, commentary_id);
+        tracing::debug!("Fetching commentary books from: {}", url);
+
+        // The API returns an object with commentary and books fields
+        let json = self
+            .fetch_json(CacheEndpoint::CommentaryBooks, &url, "commentary books")
+            .await?;
+
+        let books_array = json.get("books").ok_or_else(|| {
+            AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for commentary books".to_string(),
+            )
+        })?;
+        let books =
+            serde_json::from_value::<Vec<CommentaryBook>>(books_array.clone()).map_err(|e| {
+                tracing::error!("Failed to deserialize commentary books: {}", e);
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+            })?;
+
+        tracing::debug!("Successfully loaded {} commentary books", books.len());
+        Ok(books)
+    }
+
+    /// Get a commentary's introduction to a book
+    pub async fn get_commentary_book_introduction(
+        &self,
+        commentary_id: &str,
+        book: &str,
+    ) -> Result<CommentaryBookIntroduction> {
+        let books = self.get_commentary_books(commentary_id).await?;
+        let entry = books
+            .into_iter()
+            .find(|b| b.id.eq_ignore_ascii_case(book))
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Book {} is not covered by commentary {}",
+                    book, commentary_id
+                ))
+            })?;
+
+        let introduction = entry
+            .introduction
+            .as_deref()
+            .map(|text| parse_paragraphs(&serde_json::Value::from(text)))
+            .unwrap_or_default();
+
+        Ok(CommentaryBookIntroduction {
+            commentary_id: commentary_id.to_string(),
+            book: entry.id,
+            name: entry.name,
+            summary: entry.introduction_summary,
+            introduction,
+        })
+    }
+
     /// Get commentary for a specific chapter
     pub async fn get_commentary(
         &self,
@@ -267,173 +319,624 @@ impl HelloAOBibleClient {
         book: &str,
         chapter: u32,
     ) -> Result<CommentaryChapter> {
-        let url = format!("{}/c/{}/{}/{}.json", self.base_url, commentary_id, book, chapter);
+        let url = format!(
+            "{}/c/{}/{}/{}.json",
+            self.base_url, commentary_id, book, chapter
+        );
         tracing::debug!("Fetching commentary from: {}", url);
-        
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            tracing::error!("Failed to connect to HelloAO API: {}", e);
-            AppError::BibleBrainApi(format!("Failed to connect to API: {}", e))
-        })?;
-
-        let status = response.status();
-        tracing::debug!("API response status: {}", status);
-
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            tracing::error!("API error response: {}", error_text);
-            return Err(AppError::BibleBrainApi(format!(
-                "Failed to fetch commentary: {} - {}",
-                status, error_text
-            )));
-        }
 
         // The API returns a wrapped object with commentary, book, and chapter fields
-        let json: serde_json::Value = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse commentary JSON: {}", e);
-            AppError::BibleBrainApi(format!("Failed to parse response: {}", e))
-        })?;
+        let json = self
+            .fetch_json(CacheEndpoint::Commentary, &url, "commentary")
+            .await?;
 
         // Extract the chapter data from the wrapped response
         let commentary_chapter = if let Some(chapter_obj) = json.get("chapter") {
-            let chapter_number = chapter_obj.get("number")
+            let chapter_number = chapter_obj
+                .get("number")
                 .and_then(|n| n.as_u64())
                 .unwrap_or(chapter as u64) as u32;
-            
-            // Extract commentary content from content array
+
+            // Extract verse commentary and section notes from content array
             let empty_array: Vec<serde_json::Value> = Vec::new();
-            let content = chapter_obj.get("content")
+            let content = chapter_obj
+                .get("content")
                 .and_then(|c| c.as_array())
                 .unwrap_or(&empty_array);
-            
-            let mut verses = Vec::new();
-            for item in content {
-                if let Some(verse_obj) = item.as_object() {
-                    if verse_obj.get("type").and_then(|t| t.as_str()) == Some("verse") {
-                        if let (Some(verse_num), Some(content_arr)) = (
-                            verse_obj.get("number").and_then(|n| n.as_u64()),
-                            verse_obj.get("content").and_then(|c| c.as_array())
-                        ) {
-                            // Join all content strings for the verse commentary
-                            let verse_text: String = content_arr
-                                .iter()
-                                .filter_map(|v| {
-                                    if v.is_string() {
-                                        Some(v.as_str().unwrap_or(""))
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect::<Vec<_>>()
-                                .join(" ");
-                            
-                            verses.push(CommentaryVerse {
-                                verse: verse_num as u32,
-                                content: verse_text,
-                            });
-                        }
-                    }
-                }
-            }
-            
+
+            let (verses, notes) = parse_commentary_content(content);
+            let introduction = chapter_obj
+                .get("introduction")
+                .map(parse_paragraphs)
+                .filter(|paragraphs| !paragraphs.is_empty());
+
             CommentaryChapter {
                 commentary_id: commentary_id.to_string(),
                 book: book.to_string(),
                 chapter: chapter_number,
+                introduction,
                 verses,
+                notes,
             }
         } else {
-            return Err(AppError::BibleBrainApi(
-                "Unexpected API response format for commentary".to_string()
+            return Err(AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for commentary".to_string(),
             ));
         };
-        
-        tracing::debug!("Successfully loaded commentary chapter {} with {} verses", chapter, commentary_chapter.verses.len());
+
+        tracing::debug!(
+            "Successfully loaded commentary chapter {} with {} verses",
+            chapter,
+            commentary_chapter.verses.len()
+        );
         Ok(commentary_chapter)
     }
+
+    /// Get the profiles (topical and person articles) a commentary provides
+    pub async fn get_commentary_profiles(
+        &self,
+        commentary_id: &str,
+    ) -> Result<Vec<CommentaryProfile>> {
+        let url = format!("{}/c/{}/profiles.json", self.base_url, commentary_id);
+        tracing::debug!("Fetching commentary profiles from: {}", url);
+
+        // The API returns an object with commentary and profiles fields
+        let json = self
+            .fetch_json(CacheEndpoint::Profiles, &url, "commentary profiles")
+            .await?;
+
+        let profiles_array = json.get("profiles").ok_or_else(|| {
+            AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for commentary profiles".to_string(),
+            )
+        })?;
+        let profiles = serde_json::from_value::<Vec<CommentaryProfile>>(profiles_array.clone())
+            .map_err(|e| {
+                tracing::error!("Failed to deserialize commentary profiles: {}", e);
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+            })?;
+
+        tracing::debug!("Successfully loaded {} commentary profiles", profiles.len());
+        Ok(profiles)
+    }
+
+    /// Get a single commentary profile with its article text
+    pub async fn get_commentary_profile(
+        &self,
+        commentary_id: &str,
+        profile_id: &str,
+    ) -> Result<CommentaryProfileArticle> {
+        let url = format!(
+            "{}/c/{}/profiles/{}.json",
+            self.base_url, commentary_id, profile_id
+        );
+        tracing::debug!("Fetching commentary profile from: {}", url);
+
+        // The API returns an object with commentary, profile and content fields
+        let json = self
+            .fetch_json(CacheEndpoint::Profiles, &url, "commentary profile")
+            .await?;
+
+        let profile_obj = json.get("profile").ok_or_else(|| {
+            AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for commentary profile".to_string(),
+            )
+        })?;
+        let profile =
+            serde_json::from_value::<CommentaryProfile>(profile_obj.clone()).map_err(|e| {
+                tracing::error!("Failed to deserialize commentary profile: {}", e);
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+            })?;
+        let content = json
+            .get("content")
+            .map(parse_paragraphs)
+            .unwrap_or_default();
+
+        Ok(CommentaryProfileArticle {
+            commentary_id: commentary_id.to_string(),
+            profile,
+            content,
+        })
+    }
+
+    /// Get list of available datasets, such as open cross-reference data
+    pub async fn get_datasets(&self) -> Result<Vec<Dataset>> {
+        let url = format!("{}/available_datasets.json", self.base_url);
+        tracing::debug!("Fetching datasets from: {}", url);
+
+        // The API returns an object with a "datasets" field
+        let json = self
+            .fetch_json(CacheEndpoint::Datasets, &url, "datasets")
+            .await?;
+
+        let datasets_array = json.get("datasets").ok_or_else(|| {
+            AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for datasets".to_string(),
+            )
+        })?;
+        let datasets =
+            serde_json::from_value::<Vec<Dataset>>(datasets_array.clone()).map_err(|e| {
+                tracing::error!("Failed to deserialize datasets: {}", e);
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+            })?;
+
+        tracing::debug!("Successfully loaded {} datasets", datasets.len());
+        Ok(datasets)
+    }
+
+    /// Get the books a dataset covers
+    pub async fn get_dataset_books(&self, dataset_id: &str) -> Result<Vec<DatasetBook>> {
+        let url = format!("{}/d/{}/books.json", self.base_url, dataset_id);
+        tracing::debug!("Fetching dataset books from: {}", url);
+
+        // The API returns an object with dataset and books fields
+        let json = self
+            .fetch_json(CacheEndpoint::Datasets, &url, "dataset books")
+            .await?;
+
+        let books_array = json.get("books").ok_or_else(|| {
+            AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for dataset books".to_string(),
+            )
+        })?;
+        let books =
+            serde_json::from_value::<Vec<DatasetBook>>(books_array.clone()).map_err(|e| {
+                tracing::error!("Failed to deserialize dataset books: {}", e);
+                AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+            })?;
+
+        tracing::debug!("Successfully loaded {} dataset books", books.len());
+        Ok(books)
+    }
+
+    /// Get a dataset's entries for one chapter, e.g. cross references per verse
+    pub async fn get_dataset_chapter(
+        &self,
+        dataset_id: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<DatasetChapter> {
+        let url = format!(
+            "{}/d/{}/{}/{}.json",
+            self.base_url, dataset_id, book, chapter
+        );
+        tracing::debug!("Fetching dataset chapter from: {}", url);
+
+        // The API returns a wrapped object with dataset, book, and chapter fields
+        let json = self
+            .fetch_json(CacheEndpoint::DatasetChapter, &url, "dataset chapter")
+            .await?;
+
+        let chapter_obj = json.get("chapter").ok_or_else(|| {
+            AppError::UpstreamSchemaChanged(
+                "Unexpected API response format for dataset chapter".to_string(),
+            )
+        })?;
+        let content = chapter_obj.get("content").cloned().unwrap_or_default();
+        let verses = serde_json::from_value::<Vec<DatasetVerse>>(content).map_err(|e| {
+            tracing::error!("Failed to deserialize dataset chapter: {}", e);
+            AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+        })?;
+
+        Ok(DatasetChapter {
+            dataset_id: dataset_id.to_string(),
+            book: book.to_string(),
+            chapter: chapter_obj
+                .get("number")
+                .and_then(|n| n.as_u64())
+                .unwrap_or(chapter as u64) as u32,
+            verses,
+        })
+    }
 }
 
-// API Response Types
+/// A book list, sent either as a bare array or in a "books" field
+fn parse_books(json: serde_json::Value) -> Result<Vec<Book>> {
+    if json.is_array() {
+        serde_json::from_value::<Vec<Book>>(json).map_err(|e| {
+            tracing::error!("Failed to deserialize books array: {}", e);
+            AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+        })
+    } else if let Some(books_array) = json.get("books") {
+        serde_json::from_value::<Vec<Book>>(books_array.clone()).map_err(|e| {
+            tracing::error!("Failed to deserialize books from wrapper: {}", e);
+            AppError::UpstreamSchemaChanged(format!("Failed to deserialize response: {}", e))
+        })
+    } else {
+        Err(AppError::UpstreamSchemaChanged(
+            "Unexpected API response format for books".to_string(),
+        ))
+    }
+}
+
+/// Footnote IDs are numbers upstream; keep them as plain strings
+fn note_id_string(value: &serde_json::Value) -> String {
+    match value.as_str() {
+        Some(id) => id.to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// Join strings in a HelloAO content array (used by headings)
+fn join_strings(items: &[serde_json::Value]) -> String {
+    items
+        .iter()
+        .filter_map(|v| v.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse the inline items of a verse: plain strings, formatted text
+/// (poetry, words of Jesus), footnote references, headings and line breaks
+fn parse_verse_segments(items: &[serde_json::Value]) -> Vec<VerseSegment> {
+    let mut segments = Vec::new();
+    for item in items {
+        if let Some(text) = item.as_str() {
+            segments.push(VerseSegment::Text {
+                text: text.to_string(),
+                poem: None,
+            });
+            continue;
+        }
+
+        let Some(obj) = item.as_object() else {
+            continue;
+        };
+        if let Some(note_id) = obj.get("noteId") {
+            segments.push(VerseSegment::FootnoteMarker {
+                id: note_id_string(note_id),
+            });
+        } else if let Some(heading) = obj.get("heading").and_then(|h| h.as_str()) {
+            segments.push(VerseSegment::Heading {
+                text: heading.to_string(),
+            });
+        } else if obj.get("lineBreak").and_then(|b| b.as_bool()) == Some(true) {
+            segments.push(VerseSegment::LineBreak);
+        } else if let Some(text) = obj.get("text").and_then(|t| t.as_str()) {
+            let text = text.to_string();
+            let poem = obj.get("poem").and_then(|p| p.as_u64()).map(|p| p as u32);
+            if obj.get("wordsOfJesus").and_then(|w| w.as_bool()) == Some(true) {
+                segments.push(VerseSegment::WordsOfJesus { text, poem });
+            } else {
+                segments.push(VerseSegment::Text { text, poem });
+            }
+        }
+    }
+    segments
+}
+
+/// Parse a chapter's content array into structured nodes plus the flat
+/// per-verse text view
+fn parse_chapter_content(content: &[serde_json::Value]) -> (Vec<ContentNode>, Vec<Verse>) {
+    let mut nodes = Vec::new();
+    let mut verses = Vec::new();
+
+    for item in content {
+        let Some(obj) = item.as_object() else {
+            continue;
+        };
+        let items = obj
+            .get("content")
+            .and_then(|c| c.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        match obj.get("type").and_then(|t| t.as_str()) {
+            Some("heading") => nodes.push(ContentNode::Heading {
+                text: join_strings(items),
+            }),
+            Some("line_break") => nodes.push(ContentNode::LineBreak),
+            Some("hebrew_subtitle") => nodes.push(ContentNode::HebrewSubtitle {
+                content: parse_verse_segments(items),
+            }),
+            Some("verse") => {
+                let Some(number) = obj.get("number").and_then(|n| n.as_u64()) else {
+                    continue;
+                };
+                let segments = parse_verse_segments(items);
+
+                // Flat text keeps only the words, joined with single spaces
+                let text = segments
+                    .iter()
+                    .filter_map(VerseSegment::text)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let footnotes: Vec<String> = segments
+                    .iter()
+                    .filter_map(|segment| match segment {
+                        VerseSegment::FootnoteMarker { id } => Some(id.clone()),
+                        _ => None,
+                    })
+                    .collect();
+
+                verses.push(Verse {
+                    verse: number as u32,
+                    text,
+                    footnotes: (!footnotes.is_empty()).then_some(footnotes),
+                });
+                nodes.push(ContentNode::Verse {
+                    number: number as u32,
+                    content: segments,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    (nodes, verses)
+}
+
+/// Parse a chapter's `footnotes` array
+fn parse_footnotes(value: Option<&serde_json::Value>) -> Option<Vec<Footnote>> {
+    let footnotes: Vec<Footnote> = value?
+        .as_array()?
+        .iter()
+        .filter_map(|note| {
+            Some(Footnote {
+                id: note_id_string(note.get("noteId")?),
+                text: note.get("text")?.as_str()?.to_string(),
+                caller: note
+                    .get("caller")
+                    .and_then(|c| c.as_str())
+                    .map(str::to_string),
+                verse: note
+                    .get("reference")
+                    .and_then(|r| r.get("verse"))
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as u32),
+            })
+        })
+        .collect();
+
+    (!footnotes.is_empty()).then_some(footnotes)
+}
+
+/// Split commentary text into paragraphs, detecting the scripture
+/// references each one cites. Accepts a string or an array of strings.
+fn parse_paragraphs(value: &serde_json::Value) -> Vec<CommentaryParagraph> {
+    let texts: Vec<&str> = match value {
+        serde_json::Value::String(text) => vec![text.as_str()],
+        serde_json::Value::Array(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+        _ => Vec::new(),
+    };
+
+    texts
+        .iter()
+        .flat_map(|text| text.split("\n\n"))
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(|text| CommentaryParagraph {
+            text: text.to_string(),
+            references: reference::find_references(text),
+        })
+        .collect()
+}
+
+/// Parse a commentary chapter's content array into per-verse commentary
+/// and the non-verse blocks (headings, section notes) between them
+fn parse_commentary_content(
+    content: &[serde_json::Value],
+) -> (Vec<CommentaryVerse>, Vec<CommentaryNote>) {
+    let mut verses = Vec::new();
+    let mut notes = Vec::new();
+    let mut last_verse = None;
+
+    for item in content {
+        let Some(obj) = item.as_object() else {
+            continue;
+        };
+        let kind = obj.get("type").and_then(|t| t.as_str()).unwrap_or("note");
+        let paragraphs = obj.get("content").map(parse_paragraphs).unwrap_or_default();
+
+        if kind == "verse" {
+            let Some(number) = obj.get("number").and_then(|n| n.as_u64()) else {
+                continue;
+            };
+            last_verse = Some(number as u32);
+            verses.push(CommentaryVerse {
+                verse: number as u32,
+                // Flat text view kept for existing clients
+                content: paragraphs
+                    .iter()
+                    .map(|p| p.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                paragraphs,
+            });
+        } else if !paragraphs.is_empty() {
+            notes.push(CommentaryNote {
+                kind: kind.to_string(),
+                after_verse: last_verse,
+                paragraphs,
+            });
+        }
+    }
+
+    (verses, notes)
+}
+
+// Commentary Types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Translation {
+pub struct Commentary {
     pub id: String,
     pub name: String,
-    #[serde(default)]
-    pub language: Option<String>,
     #[serde(default, rename = "englishName")]
     pub english_name: Option<String>,
-    #[serde(default, rename = "languageName")]
-    pub language_name: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
     #[serde(default, rename = "languageEnglishName")]
     pub language_english_name: Option<String>,
-    #[serde(default, rename = "shortName")]
-    pub short_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentaryChapter {
+    pub commentary_id: String,
+    pub book: String,
+    pub chapter: u32,
+    /// Introduction to the chapter, when the commentary has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introduction: Option<Vec<CommentaryParagraph>>,
+    pub verses: Vec<CommentaryVerse>,
+    /// Headings and section notes that are not tied to a single verse
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<CommentaryNote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentaryVerse {
+    pub verse: u32,
+    pub content: String,
     #[serde(default)]
-    pub description: Option<String>,
+    pub paragraphs: Vec<CommentaryParagraph>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentaryParagraph {
+    pub text: String,
+    /// Scripture references cited in the paragraph
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<FoundReference>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentaryNote {
+    /// Upstream content type, e.g. "heading"
+    pub kind: String,
+    /// Verse the note follows; `None` when it precedes the first verse
+    pub after_verse: Option<u32>,
+    pub paragraphs: Vec<CommentaryParagraph>,
 }
 
+/// A book entry from a commentary's `books.json`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Book {
+pub struct CommentaryBook {
     pub id: String,
     pub name: String,
-    #[serde(default, rename = "commonName")]
+    #[serde(default)]
     pub common_name: Option<String>,
     #[serde(default)]
     pub order: Option<u32>,
-    #[serde(default, rename = "numberOfChapters")]
+    #[serde(default)]
     pub number_of_chapters: Option<u32>,
+    #[serde(default)]
+    pub introduction: Option<String>,
+    #[serde(default)]
+    pub introduction_summary: Option<String>,
 }
 
+/// A commentary's introduction to a whole book
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Chapter {
-    pub translation: String,
+pub struct CommentaryBookIntroduction {
+    pub commentary_id: String,
     pub book: String,
-    pub chapter: u32,
-    pub verses: Vec<Verse>,
+    pub name: String,
     #[serde(default)]
-    pub footnotes: Option<Vec<Footnote>>,
+    pub summary: Option<String>,
+    pub introduction: Vec<CommentaryParagraph>,
 }
 
+/// A topical or person article in a commentary
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Verse {
-    pub verse: u32,
-    pub text: String,
+#[serde(rename_all = "camelCase")]
+pub struct CommentaryProfile {
+    pub id: String,
+    pub subject: String,
+    /// Passage the profile is primarily associated with
+    #[serde(default)]
+    pub reference: Option<DatasetReference>,
     #[serde(default)]
-    pub footnotes: Option<Vec<String>>,
+    pub thumbnail_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Footnote {
-    pub id: String,
-    pub text: String,
+pub struct CommentaryProfileArticle {
+    pub commentary_id: String,
+    pub profile: CommentaryProfile,
+    pub content: Vec<CommentaryParagraph>,
 }
 
-// Commentary Types
+// Dataset Types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Commentary {
+pub struct Dataset {
     pub id: String,
     pub name: String,
-    #[serde(default, rename = "englishName")]
+    #[serde(default)]
     pub english_name: Option<String>,
     #[serde(default)]
+    pub website: Option<String>,
+    #[serde(default)]
+    pub license_url: Option<String>,
+    #[serde(default)]
     pub language: Option<String>,
-    #[serde(default, rename = "languageEnglishName")]
-    pub language_english_name: Option<String>,
+    #[serde(default)]
+    pub number_of_books: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CommentaryChapter {
-    pub commentary_id: String,
+#[serde(rename_all = "camelCase")]
+pub struct DatasetBook {
+    pub id: String,
+    #[serde(default)]
+    pub order: Option<u32>,
+    #[serde(default)]
+    pub number_of_chapters: Option<u32>,
+    #[serde(default)]
+    pub total_number_of_references: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetChapter {
+    pub dataset_id: String,
     pub book: String,
     pub chapter: u32,
-    pub verses: Vec<CommentaryVerse>,
+    pub verses: Vec<DatasetVerse>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CommentaryVerse {
+pub struct DatasetVerse {
     pub verse: u32,
-    pub content: String,
+    #[serde(default)]
+    pub references: Vec<DatasetReference>,
 }
 
+/// A verse or verse range pointed to by a dataset entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetReference {
+    pub book: String,
+    pub chapter: u32,
+    #[serde(default)]
+    pub verse: Option<u32>,
+    #[serde(default)]
+    pub end_chapter: Option<u32>,
+    #[serde(default)]
+    pub end_verse: Option<u32>,
+    /// Relevance score, e.g. cross-reference votes
+    #[serde(default)]
+    pub score: Option<f64>,
+}
+
+#[async_trait]
+impl BibleProvider for HelloAOBibleClient {
+    fn name(&self) -> &str {
+        "helloao"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        HelloAOBibleClient::get_translations(self).await
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        HelloAOBibleClient::get_books(self, translation).await
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        HelloAOBibleClient::get_chapter(self, translation, book, chapter).await
+    }
+}
@@ -0,0 +1,414 @@
+use futures::future::{BoxFuture, FutureExt, Shared};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::HttpConfig;
+use crate::error::{AppError, Result};
+
+/// A completed upstream response; non-2xx statuses are left to the caller
+#[derive(Debug, Clone)]
+pub struct UpstreamResponse {
+    pub status: StatusCode,
+    pub body: String,
+    /// The upstream's `Retry-After` header, in seconds
+    pub retry_after_secs: Option<u64>,
+}
+
+/// Why one attempt failed, and whether another attempt may help
+enum AttemptError {
+    Retryable(AppError),
+    Fatal(AppError),
+}
+
+/// One upstream fetch that identical concurrent requests all wait on
+type InFlight = Shared<BoxFuture<'static, std::result::Result<UpstreamResponse, Arc<AppError>>>>;
+
+/// How many upstream GETs were shared with an identical in-flight request
+#[derive(Debug, Clone, Serialize)]
+pub struct CoalescingStats {
+    /// Calls to `UpstreamHttp::get`
+    pub requests: u64,
+    /// Calls that started their own upstream fetch
+    pub upstream_fetches: u64,
+    /// Calls that joined a fetch already in flight
+    pub deduplicated: u64,
+    /// Distinct fetches currently in flight
+    pub in_flight: usize,
+}
+
+#[derive(Default)]
+struct Coalescer {
+    in_flight: Mutex<HashMap<String, InFlight>>,
+    requests: AtomicU64,
+    upstream_fetches: AtomicU64,
+    deduplicated: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// A trial request is in flight after the cooldown
+    half_open: bool,
+}
+
+/// HTTP client for idempotent upstream GETs.
+///
+/// Each attempt is bounded by connect and read timeouts. Connection
+/// errors, timeouts and 5xx responses are retried with jittered
+/// exponential backoff, and a per-host circuit breaker stops calling a
+/// host that keeps failing until its cooldown has passed.
+///
+/// Identical concurrent requests are coalesced: the first one fetches,
+/// and the rest share its response or error.
+#[derive(Clone)]
+pub struct UpstreamHttp {
+    client: reqwest::Client,
+    config: HttpConfig,
+    breakers: Arc<Mutex<HashMap<String, Breaker>>>,
+    jitter: RandomState,
+    coalescer: Arc<Coalescer>,
+}
+
+impl std::fmt::Debug for UpstreamHttp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpstreamHttp")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl UpstreamHttp {
+    pub fn new(config: HttpConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_millis(config.connect_timeout_ms))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            config,
+            breakers: Arc::new(Mutex::new(HashMap::new())),
+            jitter: RandomState::new(),
+            coalescer: Arc::new(Coalescer::default()),
+        }
+    }
+
+    /// Counters for request coalescing
+    pub fn coalescing_stats(&self) -> CoalescingStats {
+        let coalescer = &self.coalescer;
+        CoalescingStats {
+            requests: coalescer.requests.load(Ordering::Relaxed),
+            upstream_fetches: coalescer.upstream_fetches.load(Ordering::Relaxed),
+            deduplicated: coalescer.deduplicated.load(Ordering::Relaxed),
+            in_flight: coalescer.in_flight.lock().unwrap().len(),
+        }
+    }
+
+    /// GET `url` with the given headers and query parameters, sharing the
+    /// result with any identical request already in flight
+    pub async fn get(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        query: &[(&str, String)],
+    ) -> Result<UpstreamResponse> {
+        let key = format!("{} {:?} {:?}", url, query, headers);
+        self.coalescer.requests.fetch_add(1, Ordering::Relaxed);
+
+        let fetch = {
+            let mut in_flight = self.coalescer.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(fetch) => {
+                    self.coalescer.deduplicated.fetch_add(1, Ordering::Relaxed);
+                    tracing::debug!("Joining in-flight request for: {}", url);
+                    fetch.clone()
+                }
+                None => {
+                    self.coalescer
+                        .upstream_fetches
+                        .fetch_add(1, Ordering::Relaxed);
+                    let fetch = self.start_fetch(key.clone(), url, headers, query);
+                    in_flight.insert(key, fetch.clone());
+                    fetch
+                }
+            }
+        };
+
+        fetch.await.map_err(|e| shared_error(&e))
+    }
+
+    /// Start one upstream fetch and return the future its waiters share.
+    ///
+    /// The fetch runs as its own task, so it finishes, records its outcome
+    /// with the circuit breaker and leaves the in-flight map even when every
+    /// waiter has given up on it.
+    fn start_fetch(
+        &self,
+        key: String,
+        url: &str,
+        headers: &[(&str, &str)],
+        query: &[(&str, String)],
+    ) -> InFlight {
+        let this = self.clone();
+        let url = url.to_string();
+        let headers: Vec<(String, String)> = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let query: Vec<(String, String)> = query
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+
+        let task = tokio::spawn(async move {
+            let headers: Vec<(&str, &str)> = headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+            let query: Vec<(&str, String)> = query
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone()))
+                .collect();
+
+            let result = this.fetch(&url, &headers, &query).await.map_err(Arc::new);
+            this.coalescer.in_flight.lock().unwrap().remove(&key);
+            result
+        });
+
+        async move {
+            task.await.unwrap_or_else(|e| {
+                Err(Arc::new(AppError::Internal(anyhow::anyhow!(
+                    "Upstream fetch task failed: {}",
+                    e
+                ))))
+            })
+        }
+        .boxed()
+        .shared()
+    }
+
+    /// GET with retries and circuit breaking
+    async fn fetch(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        query: &[(&str, String)],
+    ) -> Result<UpstreamResponse> {
+        let host = host_of(url);
+        let mut trial = TrialGuard {
+            http: self,
+            host: &host,
+            armed: self.check_breaker(&host)?,
+        };
+
+        let mut attempt = 0;
+        loop {
+            let error = match self.attempt(url, headers, query).await {
+                Ok(response) if !response.status.is_server_error() => {
+                    self.record(&host, true);
+                    return Ok(response);
+                }
+                Ok(response) => AttemptError::Retryable(AppError::UpstreamUnavailable(format!(
+                    "{} - {}",
+                    response.status, response.body
+                ))),
+                Err(error) => error,
+            };
+
+            let error = match error {
+                AttemptError::Fatal(e) => {
+                    self.record(&host, true);
+                    return Err(e);
+                }
+                AttemptError::Retryable(e) => e,
+            };
+
+            self.record(&host, false);
+            let retry = attempt < self.config.max_retries
+                && match self.check_breaker(&host) {
+                    Ok(started_trial) => {
+                        trial.armed |= started_trial;
+                        true
+                    }
+                    Err(_) => false,
+                };
+            if !retry {
+                tracing::error!("Upstream request to {} failed: {}", url, error);
+                return Err(error.with_message(format!(
+                    "Upstream request failed after {} attempt(s): {}",
+                    attempt + 1,
+                    error.detail()
+                )));
+            }
+
+            let delay = self.backoff(attempt);
+            tracing::warn!(
+                "Upstream request to {} failed ({}); retrying in {:?}",
+                url,
+                error,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn attempt(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        query: &[(&str, String)],
+    ) -> std::result::Result<UpstreamResponse, AttemptError> {
+        let mut request = self.client.get(url).query(query);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let read_timeout = Duration::from_millis(self.config.read_timeout_ms);
+        let exchange = async {
+            let response = request.send().await?;
+            let status = response.status();
+            let retry_after_secs = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok());
+            let body = response.text().await?;
+            Ok::<_, reqwest::Error>(UpstreamResponse {
+                status,
+                body,
+                retry_after_secs,
+            })
+        };
+
+        match tokio::time::timeout(read_timeout, exchange).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(e)) if e.is_timeout() => Err(AttemptError::Retryable(
+                AppError::UpstreamTimeout(format!("Request timed out: {}", e)),
+            )),
+            Ok(Err(e)) if e.is_connect() || e.is_request() => Err(AttemptError::Retryable(
+                AppError::UpstreamUnavailable(format!("Failed to connect to API: {}", e)),
+            )),
+            Ok(Err(e)) => Err(AttemptError::Fatal(AppError::HttpClient(e))),
+            Err(_) => Err(AttemptError::Retryable(AppError::UpstreamTimeout(format!(
+                "No response within {:?}",
+                read_timeout
+            )))),
+        }
+    }
+
+    /// Full-jitter exponential backoff: a random delay up to base * 2^attempt
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self.config.backoff_ceiling_ms(attempt);
+
+        let mut hasher = self.jitter.build_hasher();
+        hasher.write_u32(attempt);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        hasher.write_u128(now.as_nanos());
+        let random = hasher.finish();
+
+        Duration::from_millis(random % (ceiling + 1))
+    }
+
+    /// Fail fast while `host`'s circuit is open; `Ok(true)` means this
+    /// request is the trial let through after the cooldown
+    fn check_breaker(&self, host: &str) -> Result<bool> {
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(host.to_string()).or_default();
+
+        match breaker.open_until {
+            Some(until) if Instant::now() < until || breaker.half_open => {
+                let retry_after = until.saturating_duration_since(Instant::now());
+                tracing::debug!("Circuit for {} is open; skipping request", host);
+                Err(AppError::CircuitOpen {
+                    host: host.to_string(),
+                    retry_after_secs: retry_after.as_secs().max(1),
+                })
+            }
+            Some(_) => {
+                // Cooldown over: let one trial request through
+                breaker.half_open = true;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn record(&self, host: &str, success: bool) {
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(host.to_string()).or_default();
+
+        if success {
+            if breaker.open_until.is_some() {
+                tracing::info!("Circuit for {} closed", host);
+            }
+            *breaker = Breaker::default();
+            return;
+        }
+
+        breaker.consecutive_failures += 1;
+        if breaker.half_open || breaker.consecutive_failures >= self.config.breaker_threshold {
+            let cooldown = Duration::from_secs(self.config.breaker_cooldown_secs);
+            tracing::warn!(
+                "Circuit for {} opened for {:?} after {} consecutive failures",
+                host,
+                cooldown,
+                breaker.consecutive_failures
+            );
+            breaker.open_until = Some(Instant::now() + cooldown);
+            breaker.half_open = false;
+        }
+    }
+}
+
+/// Ends a half-open trial whose fetch stops (its task panics or is
+/// cancelled) before recording an outcome, so the next request can try
+/// again instead of the circuit staying open for good
+struct TrialGuard<'a> {
+    http: &'a UpstreamHttp,
+    host: &'a str,
+    armed: bool,
+}
+
+impl Drop for TrialGuard<'_> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let mut breakers = self.http.breakers.lock().unwrap();
+        if let Some(breaker) = breakers.get_mut(self.host) {
+            if breaker.half_open {
+                tracing::debug!("Trial request to {} was abandoned", self.host);
+                breaker.half_open = false;
+            }
+        }
+    }
+}
+
+/// Give each waiter on a shared fetch its own copy of the error
+fn shared_error(error: &AppError) -> AppError {
+    error.with_message(error.detail())
+}
+
+/// The `host:port` a URL points at, used to key circuit breakers
+fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            let host = url.host_str()?.to_string();
+            Some(match url.port_or_known_default() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
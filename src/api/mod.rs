@@ -1,4 +1,13 @@
+pub mod bible_brain;
+pub mod cache;
+pub mod chain;
+pub mod files;
 pub mod helloao;
+pub mod http;
+pub mod models;
+pub mod provider;
+pub mod video;
 
+pub use bible_brain::BibleBrainClient;
 pub use helloao::HelloAOBibleClient;
-
+pub use provider::BibleProvider;
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+// Provider-neutral scripture types shared by every `BibleProvider`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Translation {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default, rename = "englishName")]
+    pub english_name: Option<String>,
+    #[serde(default, rename = "languageName")]
+    pub language_name: Option<String>,
+    #[serde(default, rename = "languageEnglishName")]
+    pub language_english_name: Option<String>,
+    #[serde(default, rename = "shortName")]
+    pub short_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(
+        default,
+        rename = "numberOfBooks",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub number_of_books: Option<u32>,
+    /// ID of the canon the translation follows (see `/api/canons`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Book {
+    pub id: String,
+    pub name: String,
+    #[serde(default, rename = "commonName")]
+    pub common_name: Option<String>,
+    #[serde(default)]
+    pub order: Option<u32>,
+    #[serde(default, rename = "numberOfChapters")]
+    pub number_of_chapters: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub translation: String,
+    pub book: String,
+    pub chapter: u32,
+    pub verses: Vec<Verse>,
+    #[serde(default)]
+    pub footnotes: Option<Vec<Footnote>>,
+    /// Structured chapter content: headings, line breaks, poetry and
+    /// footnote markers. Empty when the provider only has plain verse text.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<ContentNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verse {
+    pub verse: u32,
+    pub text: String,
+    #[serde(default)]
+    pub footnotes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Footnote {
+    pub id: String,
+    pub text: String,
+    /// Marker shown in the text, e.g. "+" or "a"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caller: Option<String>,
+    /// Verse the footnote is attached to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verse: Option<u32>,
+}
+
+/// A block-level item of a chapter, in reading order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentNode {
+    Heading {
+        text: String,
+    },
+    LineBreak,
+    Verse {
+        number: u32,
+        content: Vec<VerseSegment>,
+    },
+    /// Psalm superscription ("A Psalm of David") that precedes verse 1
+    HebrewSubtitle {
+        content: Vec<VerseSegment>,
+    },
+}
+
+/// An inline piece of a verse
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VerseSegment {
+    Text {
+        text: String,
+        /// Poetry indentation level
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        poem: Option<u32>,
+    },
+    /// Red-letter text spoken by Jesus
+    WordsOfJesus {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        poem: Option<u32>,
+    },
+    FootnoteMarker {
+        /// `Footnote.id` of the note this marker refers to
+        id: String,
+    },
+    Heading {
+        text: String,
+    },
+    LineBreak,
+}
+
+impl VerseSegment {
+    /// Plain text carried by this segment, if any
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            VerseSegment::Text { text, .. } | VerseSegment::WordsOfJesus { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+}
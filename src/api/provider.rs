@@ -0,0 +1,174 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::bible_brain::BibleBrainClient;
+use super::chain::{MappedProvider, OverlayProvider, ProviderChain};
+use super::files::FileProvider;
+use super::helloao::HelloAOBibleClient;
+use super::models::{Book, Chapter, Translation};
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::store::{DiskStore, StoreBackedProvider};
+
+/// A provider response, with the name of the provider that produced it
+#[derive(Debug, Clone)]
+pub struct Served<T> {
+    pub value: T,
+    pub provider: String,
+}
+
+impl<T> Served<T> {
+    pub fn new(provider: &str, value: T) -> Self {
+        Self {
+            value,
+            provider: provider.to_string(),
+        }
+    }
+}
+
+/// A source of scripture text.
+///
+/// Handlers only talk to this trait, so the server can switch between
+/// upstream APIs (or combine them) through configuration alone.
+#[async_trait]
+pub trait BibleProvider: Send + Sync {
+    /// Short identifier used in configuration and logs
+    fn name(&self) -> &str;
+
+    /// Get list of available translations
+    async fn get_translations(&self) -> Result<Vec<Translation>>;
+
+    /// Get list of books for a translation
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>>;
+
+    /// Get a chapter from a translation
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter>;
+
+    /// Whether the translation and book lists are a stored snapshot that
+    /// may lag behind upstream, rather than a live source
+    fn is_snapshot(&self) -> bool {
+        false
+    }
+
+    /// Like `get_translations`, also naming the provider that answered.
+    ///
+    /// Composite providers override the `serve_*` methods to report which
+    /// of their members actually served the request.
+    async fn serve_translations(&self) -> Result<Served<Vec<Translation>>> {
+        Ok(Served::new(self.name(), self.get_translations().await?))
+    }
+
+    /// Like `get_books`, also naming the provider that answered
+    async fn serve_books(&self, translation: &str) -> Result<Served<Vec<Book>>> {
+        Ok(Served::new(self.name(), self.get_books(translation).await?))
+    }
+
+    /// Like `get_chapter`, also naming the provider that answered
+    async fn serve_chapter(
+        &self,
+        translation: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<Served<Chapter>> {
+        let data = self.get_chapter(translation, book, chapter).await?;
+        Ok(Served::new(self.name(), data))
+    }
+}
+
+/// Build the provider chain listed in `bible_api.provider`.
+///
+/// Upstream providers write through to `store` when it is set (only the
+/// first one writes translation and book lists), and the mirror is asked
+/// for those lists only after the upstreams. Providers that are listed but
+/// unavailable (no store for "mirror", no
+/// API key for "bible_brain") are skipped with a warning. The HelloAO
+/// client is passed in so its response cache is shared with the
+/// commentary routes.
+///
+/// Translations imported from `files.path` are served ahead of the chain,
+/// and are all that is served when no chain provider is usable.
+pub fn from_config(
+    config: &Config,
+    helloao: &HelloAOBibleClient,
+    brain: Option<&BibleBrainClient>,
+    store: Option<&DiskStore>,
+) -> Result<Arc<dyn BibleProvider>> {
+    let mut links: Vec<Arc<dyn BibleProvider>> = Vec::new();
+    let mut catalogs_stored = false;
+
+    for name in config.bible_api.provider_chain() {
+        let upstream: Arc<dyn BibleProvider> = match name {
+            "mirror" => match store {
+                Some(store) => {
+                    links.push(Arc::new(store.clone()));
+                    continue;
+                }
+                None => {
+                    tracing::warn!("Skipping mirror provider: STORE_PATH is empty");
+                    continue;
+                }
+            },
+            "helloao" => Arc::new(helloao.clone()),
+            "bible_brain" => match brain {
+                Some(brain) => Arc::new(brain.clone()),
+                None => {
+                    tracing::warn!("Skipping bible_brain provider: BIBLE_BRAIN_API_KEY is not set");
+                    continue;
+                }
+            },
+            other => {
+                return Err(AppError::Config(config::ConfigError::Message(format!(
+                    "Unknown bible provider: {}",
+                    other
+                ))))
+            }
+        };
+
+        let mut link = match config.bible_api.translation_ids.get(name) {
+            Some(ids) if !ids.is_empty() => Arc::new(MappedProvider::new(upstream, ids.clone())),
+            _ => upstream,
+        };
+        if let Some(store) = store {
+            // The first upstream owns the stored catalogs
+            let backed = StoreBackedProvider::new(link, store.clone());
+            link = if catalogs_stored {
+                Arc::new(backed.without_catalogs())
+            } else {
+                Arc::new(backed)
+            };
+            catalogs_stored = true;
+        }
+        links.push(link);
+    }
+
+    let files = match config.files.path.as_str() {
+        "" => None,
+        path => Some(FileProvider::load(path)?).filter(|files| {
+            if files.is_empty() {
+                tracing::warn!("No translations to import in {}", path);
+            }
+            !files.is_empty()
+        }),
+    };
+
+    if links.is_empty() {
+        return match files {
+            Some(files) => Ok(Arc::new(files)),
+            None => Err(AppError::Config(config::ConfigError::Message(format!(
+                "No usable bible provider in: {}",
+                config.bible_api.provider
+            )))),
+        };
+    }
+
+    let timeout = Duration::from_secs(config.bible_api.provider_timeout_secs);
+    let chain: Arc<dyn BibleProvider> = Arc::new(ProviderChain::new(links, timeout));
+    match files {
+        Some(files) => {
+            let ids = files.translation_ids().map(str::to_string).collect();
+            Ok(Arc::new(OverlayProvider::new(Arc::new(files), ids, chain)))
+        }
+        None => Ok(chain),
+    }
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long a video listing is reused when its links carry no expiry
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Links this close to expiring are treated as expired, so a client
+/// always has time to start playback
+const REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// A video as DBP sends it; field names and types vary between endpoints
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct VideoRecord {
+    #[serde(deserialize_with = "super::bible_brain::string_or_number")]
+    id: String,
+    #[serde(default)]
+    bible_id: Option<String>,
+    #[serde(default)]
+    book_id: Option<String>,
+    #[serde(
+        default,
+        alias = "chapter",
+        deserialize_with = "optional_string_or_number"
+    )]
+    chapter_id: Option<String>,
+    #[serde(
+        default,
+        alias = "verse_start",
+        deserialize_with = "optional_string_or_number"
+    )]
+    verse_id: Option<String>,
+    #[serde(default, alias = "path")]
+    url: Option<String>,
+    #[serde(default, alias = "thumbnail")]
+    thumbnail_url: Option<String>,
+    #[serde(default)]
+    duration: Option<u32>,
+    #[serde(default, alias = "name")]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default, alias = "iso")]
+    language_code: Option<String>,
+    #[serde(default, deserialize_with = "optional_string_or_number")]
+    resolution: Option<String>,
+    #[serde(default, alias = "filesize_in_bytes")]
+    size: Option<u64>,
+    #[serde(default, rename = "type")]
+    video_type: Option<String>,
+}
+
+fn optional_string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(text) => Ok(Some(text)),
+        serde_json::Value::Number(number) => Ok(Some(number.to_string())),
+        other => Err(serde::de::Error::custom(format!(
+            "expected string or number, got {}",
+            other
+        ))),
+    }
+}
+
+/// A video with consistent field types, whichever DBP endpoint it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Video {
+    pub id: String,
+    pub bible_id: Option<String>,
+    pub book_id: Option<String>,
+    pub chapter: Option<u32>,
+    pub verse: Option<u32>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Signed stream URL; see `expires_at`
+    pub url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    /// Length in seconds
+    pub duration: Option<u32>,
+    /// Lowercase ISO 639-3 code, e.g. "eng"
+    pub language: Option<String>,
+    /// Frame height, e.g. "720p"
+    pub resolution: Option<String>,
+    pub size: Option<u64>,
+    pub kind: Option<String>,
+    /// Unix time at which the signed `url` stops working, when known
+    pub expires_at: Option<u64>,
+}
+
+impl Video {
+    pub(crate) fn from_record(record: VideoRecord, fetched_at: SystemTime) -> Self {
+        let expires_at = record
+            .url
+            .as_deref()
+            .and_then(|url| signed_url_expiry(url, fetched_at));
+
+        Self {
+            id: record.id,
+            bible_id: record.bible_id,
+            book_id: record.book_id.map(|book| book.to_uppercase()),
+            chapter: record
+                .chapter_id
+                .and_then(|chapter| chapter.trim().parse().ok()),
+            verse: record.verse_id.and_then(|verse| verse.trim().parse().ok()),
+            title: record.title,
+            description: record.description,
+            url: record.url,
+            thumbnail_url: record.thumbnail_url,
+            duration: record.duration,
+            language: record.language_code.map(|code| code.trim().to_lowercase()),
+            resolution: record.resolution.as_deref().and_then(normalize_resolution),
+            size: record.size,
+            kind: record.video_type,
+            expires_at,
+        }
+    }
+
+    /// Whether the signed link has expired, or will within `REFRESH_MARGIN`
+    pub fn link_expired(&self, now: SystemTime) -> bool {
+        let Some(expires_at) = self.expires_at else {
+            return false;
+        };
+        let deadline = UNIX_EPOCH + Duration::from_secs(expires_at);
+        now + REFRESH_MARGIN >= deadline
+    }
+}
+
+/// Normalize "1280x720", "720", "720P" or "hd720" to "720p"
+pub fn normalize_resolution(resolution: &str) -> Option<String> {
+    let resolution = resolution.trim().to_lowercase();
+    let height = match resolution.split_once('x') {
+        Some((_, height)) => height,
+        None => resolution.as_str(),
+    };
+    let digits: String = height.chars().filter(char::is_ascii_digit).collect();
+    let height: u32 = digits.parse().ok()?;
+    Some(format!("{}p", height))
+}
+
+/// Expiry of a CloudFront (`Expires=`) or S3 (`X-Amz-Expires=`) signed URL
+fn signed_url_expiry(url: &str, fetched_at: SystemTime) -> Option<u64> {
+    let (_, query) = url.split_once('?')?;
+    let params: HashMap<&str, &str> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+
+    if let Some(expires) = params.get("Expires").and_then(|v| v.parse().ok()) {
+        return Some(expires);
+    }
+
+    // S3 links are valid for a number of seconds from signing, which
+    // happened no later than the fetch
+    let lifetime: u64 = params.get("X-Amz-Expires")?.parse().ok()?;
+    let fetched = fetched_at.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(fetched + lifetime)
+}
+
+/// Language and resolution filters for video listings
+#[derive(Debug, Clone, Default)]
+pub struct VideoFilter {
+    pub language: Option<String>,
+    pub resolution: Option<String>,
+}
+
+impl VideoFilter {
+    pub fn matches(&self, video: &Video) -> bool {
+        let language_ok = self.language.as_deref().is_none_or(|language| {
+            video
+                .language
+                .as_deref()
+                .is_some_and(|code| code.eq_ignore_ascii_case(language.trim()))
+        });
+        let resolution_ok = self.resolution.as_deref().is_none_or(|resolution| {
+            video.resolution.is_some() && video.resolution == normalize_resolution(resolution)
+        });
+        language_ok && resolution_ok
+    }
+
+    pub fn apply(&self, videos: &[Video]) -> Vec<Video> {
+        videos
+            .iter()
+            .filter(|video| self.matches(video))
+            .cloned()
+            .collect()
+    }
+}
+
+struct CachedListing {
+    videos: Arc<Vec<Video>>,
+    refresh_at: Instant,
+}
+
+/// Video listings keyed by upstream path.
+///
+/// A listing is reused until its earliest signed link is about to expire,
+/// then fetched again so clients never receive a dead link.
+#[derive(Clone, Default)]
+pub(crate) struct VideoCache {
+    listings: Arc<Mutex<HashMap<String, CachedListing>>>,
+}
+
+impl std::fmt::Debug for VideoCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries = self.listings.lock().map(|l| l.len()).unwrap_or_default();
+        f.debug_struct("VideoCache")
+            .field("entries", &entries)
+            .finish()
+    }
+}
+
+impl VideoCache {
+    pub(crate) fn get(&self, path: &str) -> Option<Arc<Vec<Video>>> {
+        let listings = self.listings.lock().unwrap();
+        listings
+            .get(path)
+            .filter(|listing| Instant::now() < listing.refresh_at)
+            .map(|listing| listing.videos.clone())
+    }
+
+    pub(crate) fn insert(&self, path: &str, videos: Arc<Vec<Video>>) {
+        let now = SystemTime::now();
+        let until_expiry = videos
+            .iter()
+            .filter_map(|video| video.expires_at)
+            .min()
+            .map(|expires_at| {
+                (UNIX_EPOCH + Duration::from_secs(expires_at))
+                    .duration_since(now + REFRESH_MARGIN)
+                    .unwrap_or_default()
+            });
+        let ttl = until_expiry.map_or(DEFAULT_TTL, |ttl| ttl.min(DEFAULT_TTL));
+
+        self.listings.lock().unwrap().insert(
+            path.to_string(),
+            CachedListing {
+                videos,
+                refresh_at: Instant::now() + ttl,
+            },
+        );
+    }
+
+    /// Drop a listing so the next lookup refetches it
+    pub(crate) fn invalidate(&self, path: &str) {
+        self.listings.lock().unwrap().remove(path);
+    }
+}
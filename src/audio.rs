@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::api::bible_brain::{AudioChapter, BibleBrainClient};
+use crate::api::models::Chapter;
+use crate::api::provider::BibleProvider;
+use crate::error::Result;
+
+/// A chapter's audio with every text verse placed on its timeline
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncedChapter {
+    pub translation: String,
+    pub fileset_id: String,
+    pub book: String,
+    pub chapter: u32,
+    /// Signed audio URL; it expires, so clients should not store it
+    pub url: String,
+    /// Length of the audio in seconds, when DBP reports it
+    pub duration: Option<u32>,
+    /// Whether the fileset has any timing data for this chapter
+    pub timed: bool,
+    pub verses: Vec<SyncedVerse>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncedVerse {
+    pub verse: u32,
+    pub text: String,
+    /// Seconds from the start of the audio; `None` when `timed` is false
+    pub start: Option<f64>,
+    /// Start of the next timed verse, or the end of the audio
+    pub end: Option<f64>,
+    /// False when DBP has no timestamp for this verse
+    pub timed: bool,
+}
+
+/// Fetch `translation`'s text and `fileset_id`'s audio for a chapter and
+/// line them up verse by verse.
+pub async fn fetch_synced_chapter(
+    provider: &dyn BibleProvider,
+    brain: &BibleBrainClient,
+    translation: &str,
+    fileset_id: &str,
+    book: &str,
+    chapter: u32,
+) -> Result<SyncedChapter> {
+    let (text, audio) = futures::join!(
+        provider.get_chapter(translation, book, chapter),
+        brain.get_audio_chapter(fileset_id, book, chapter),
+    );
+    let (text, audio) = (text?, audio?);
+
+    Ok(SyncedChapter {
+        translation: translation.to_string(),
+        fileset_id: fileset_id.to_string(),
+        book: book.to_string(),
+        chapter,
+        timed: !audio.timestamps.is_empty(),
+        verses: synchronize(&text, &audio),
+        url: audio.url,
+        duration: audio.duration,
+    })
+}
+
+/// Merge DBP verse timestamps into the text's verse numbering.
+///
+/// Timestamps for partial verses ("3a", "3b") count from the first part;
+/// timestamps for verses the text lacks (and the verse 0 intro) only
+/// bound the end of the verse before them.
+pub fn synchronize(text: &Chapter, audio: &AudioChapter) -> Vec<SyncedVerse> {
+    let mut timeline: Vec<(u32, f64)> = audio
+        .timestamps
+        .iter()
+        .map(|timestamp| (timestamp.verse, timestamp.start))
+        .collect();
+    timeline.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut starts: HashMap<u32, f64> = HashMap::new();
+    for (verse, start) in &timeline {
+        starts.entry(*verse).or_insert(*start);
+    }
+
+    let audio_end = audio.duration.map(f64::from);
+    let end_of = |verse: u32, start: f64| {
+        timeline
+            .iter()
+            .find(|(other, next)| *other != verse && *next > start)
+            .map(|(_, next)| *next)
+            .or(audio_end)
+    };
+
+    text.verses
+        .iter()
+        .map(|verse| {
+            let start = starts.get(&verse.verse).copied();
+            SyncedVerse {
+                verse: verse.verse,
+                text: verse.text.clone(),
+                start,
+                end: start.and_then(|start| end_of(verse.verse, start)),
+                timed: start.is_some(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,76 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use thebible::{api::helloao::HelloAOBibleClient, config::Config, store::DiskStore};
+
+const USAGE: &str = "Usage: mirror [--concurrency N] <translation> [<translation>...]";
+
+/// Download whole translations from HelloAO into the on-disk store so the
+/// server can keep serving them while the upstream API is unreachable.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Load environment variables
+    dotenv::dotenv().ok();
+
+    // Initialize tracing
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "thebible=info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let mut concurrency = 8;
+    let mut translations = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--concurrency" => {
+                concurrency = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or_else(|| anyhow::anyhow!(USAGE))?;
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => translations.push(arg),
+        }
+    }
+
+    if translations.is_empty() {
+        anyhow::bail!(USAGE);
+    }
+
+    let config = Config::from_env()?;
+    if config.store.path.is_empty() {
+        anyhow::bail!("STORE_PATH must be set to mirror translations");
+    }
+
+    let client = HelloAOBibleClient::with_options(
+        config.bible_api.base_url.clone(),
+        config.cache.clone(),
+        config.http.clone(),
+    );
+    let store = DiskStore::new(&config.store.path);
+
+    // Keep the translation list so the mirror can be browsed offline
+    let available = client.get_translations().await?;
+    store.save_translations(&available).await?;
+
+    for translation in &translations {
+        tracing::info!("Mirroring {} into {}", translation, store.root().display());
+        let summary = store
+            .mirror_translation(&client, translation, concurrency)
+            .await?;
+        tracing::info!(
+            "Mirrored {}: {} books, {} chapters",
+            summary.translation,
+            summary.books,
+            summary.chapters
+        );
+    }
+
+    Ok(())
+}
@@ -1,20 +1,10 @@
-use axum::{
-    routing::get,
-    Router,
-};
+use axum::{middleware, routing::get, Router};
 use std::net::SocketAddr;
 use tower::ServiceBuilder;
-use tower_http::{
-    cors::CorsLayer,
-    trace::TraceLayer,
-};
+use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use thebible::{
-    api::helloao::HelloAOBibleClient,
-    config::Config,
-    handlers,
-};
+use thebible::{config::Config, handlers, problem, state::AppState};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -34,8 +24,9 @@ async fn main() -> anyhow::Result<()> {
     let config = Config::from_env()?;
     tracing::info!("Loaded configuration: {:?}", config);
 
-    // Create HelloAO Bible API client (no API key needed!)
-    let bible_client = HelloAOBibleClient::new(config.bible_api.base_url.clone());
+    // Create the configured Bible provider (HelloAO needs no API key!)
+    let state = AppState::from_config(&config)?;
+    tracing::info!("Serving scripture from provider: {}", state.provider.name());
 
     // Build application routes
     let app = Router::new()
@@ -43,17 +34,94 @@ async fn main() -> anyhow::Result<()> {
         .route("/health", get(handlers::health))
         // HelloAO API routes
         .route("/api/translations", get(handlers::get_translations))
-        .route("/api/translations/:translation/books", get(handlers::get_books))
+        .route(
+            "/api/translations/:translation/books",
+            get(handlers::get_books),
+        )
+        .route("/api/canons", get(handlers::get_canons))
         .route(
             "/api/translations/:translation/books/:book/chapters/:chapter",
             get(handlers::get_chapter),
         )
+        .route(
+            "/api/translations/:translation/books/:book/chapters/:chapter/audio",
+            get(handlers::get_synced_audio),
+        )
+        .route(
+            "/api/translations/:translation/search",
+            get(handlers::search_translation),
+        )
+        // Passage lookup by reference
+        .route("/api/passage", get(handlers::get_passage))
+        .route("/api/parallel", get(handlers::get_parallel))
+        .route("/api/crossrefs", get(handlers::get_crossrefs))
+        .route("/api/interlinear", get(handlers::get_interlinear))
+        .route("/api/lexicon/:strongs", get(handlers::get_lexicon_entry))
         // Commentary routes
         .route("/api/commentaries", get(handlers::get_commentaries))
         .route(
             "/api/commentaries/:commentary_id/books/:book/chapters/:chapter",
             get(handlers::get_commentary),
         )
+        .route(
+            "/api/commentaries/:commentary_id/books/:book/introduction",
+            get(handlers::get_commentary_book_introduction),
+        )
+        .route(
+            "/api/commentaries/:commentary_id/books",
+            get(handlers::get_commentary_books),
+        )
+        .route(
+            "/api/commentaries/:commentary_id/profiles",
+            get(handlers::get_commentary_profiles),
+        )
+        .route(
+            "/api/commentaries/:commentary_id/profiles/:profile_id",
+            get(handlers::get_commentary_profile),
+        )
+        .route("/api/brain/languages", get(handlers::get_brain_languages))
+        .route("/api/brain/bibles", get(handlers::get_brain_bibles))
+        .route(
+            "/api/brain/bibles/:bible_id/books",
+            get(handlers::get_brain_books),
+        )
+        .route(
+            "/api/brain/bibles/:bible_id/filesets",
+            get(handlers::get_brain_filesets),
+        )
+        .route(
+            "/api/brain/filesets/:fileset_id/books/:book/chapters/:chapter/audio",
+            get(handlers::get_brain_audio_chapter),
+        )
+        .route("/api/brain/videos", get(handlers::get_brain_videos))
+        .route(
+            "/api/brain/bibles/:bible_id/videos",
+            get(handlers::get_brain_bible_videos),
+        )
+        .route(
+            "/api/brain/bibles/:bible_id/videos/:video_id",
+            get(handlers::get_brain_video),
+        )
+        .route(
+            "/api/brain/bibles/:bible_id/books/:book/videos",
+            get(handlers::get_brain_book_videos),
+        )
+        .route(
+            "/api/brain/bibles/:bible_id/books/:book/chapters/:chapter/videos",
+            get(handlers::get_brain_chapter_videos),
+        )
+        .route("/api/datasets", get(handlers::get_datasets))
+        .route(
+            "/api/datasets/:dataset_id/books",
+            get(handlers::get_dataset_books),
+        )
+        .route(
+            "/api/datasets/:dataset_id/books/:book/chapters/:chapter",
+            get(handlers::get_dataset_chapter),
+        )
+        // Cache and upstream statistics
+        .route("/api/cache/stats", get(handlers::get_cache_stats))
+        .route("/api/upstream/stats", get(handlers::get_upstream_stats))
         // Compatibility routes for frontend
         .route("/api/languages", get(handlers::get_languages))
         .route("/api/bibles", get(handlers::get_bibles))
@@ -69,9 +137,10 @@ async fn main() -> anyhow::Result<()> {
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
                 .layer(CorsLayer::permissive())
+                .layer(middleware::from_fn(problem::problem_details))
                 .into_inner(),
         )
-        .with_state(bible_client);
+        .with_state(state);
 
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], config.server.port));
@@ -82,4 +151,3 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
-
@@ -0,0 +1,459 @@
+//! Catalog of the books of the Bible.
+//!
+//! Every book path parameter and book name in a reference resolves through
+//! this catalog to its USFM code, which is what the upstreams and the store
+//! use. Names and abbreviations are known in English, Afrikaans, Dutch,
+//! German, French, Spanish and Portuguese.
+
+use serde::Serialize;
+
+use crate::reference::ReferenceError;
+
+/// Languages with localized book names, in the order of `Book::names`
+pub const LANGUAGES: [&str; 7] = ["en", "af", "nl", "de", "fr", "es", "pt"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Testament {
+    Old,
+    New,
+    /// Books outside the Protestant canon, kept by the Catholic, Orthodox
+    /// or Ethiopian churches (see `canons`)
+    Deuterocanon,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Book {
+    /// USFM code, e.g. "1CO"
+    pub usfm: &'static str,
+    /// OSIS code, e.g. "1Cor"
+    pub osis: &'static str,
+    /// Position in canonical order: 1-66 for the Protestant canon, with the
+    /// deuterocanonical books numbered after Revelation
+    pub order: u16,
+    pub testament: Testament,
+    /// Names in the order of `LANGUAGES`
+    #[serde(skip)]
+    pub names: [&'static str; 7],
+    /// Abbreviations in any of the languages, lowercase without spaces or
+    /// periods
+    #[serde(skip)]
+    pub abbreviations: &'static [&'static str],
+}
+
+impl Book {
+    /// English name, e.g. "1 Corinthians"
+    pub fn name(&self) -> &'static str {
+        self.names[0]
+    }
+
+    /// Name in `language` (an ISO 639-1 code), if the catalog has it
+    pub fn localized_name(&self, language: &str) -> Option<&'static str> {
+        LANGUAGES
+            .iter()
+            .position(|code| code.eq_ignore_ascii_case(language))
+            .map(|index| self.names[index])
+    }
+
+    pub fn is_new_testament(&self) -> bool {
+        self.testament == Testament::New
+    }
+}
+
+macro_rules! book {
+    ($usfm:literal, $osis:literal, $order:literal, $testament:ident,
+     [$($name:literal),* $(,)?], [$($abbreviation:literal),* $(,)?]) => {
+        Book {
+            usfm: $usfm,
+            osis: $osis,
+            order: $order,
+            testament: Testament::$testament,
+            names: [$($name),*],
+            abbreviations: &[$($abbreviation),*],
+        }
+    };
+}
+
+//                                                 en, af, nl, de, fr, es, pt
+#[rustfmt::skip]
+const BOOKS: &[Book] = &[
+    book!("GEN", "Gen", 1, Old,
+        ["Genesis", "Genesis", "Genesis", "Genesis", "Genèse", "Génesis", "Gênesis"],
+        ["gn", "ge", "1mose", "1mo"]),
+    book!("EXO", "Exod", 2, Old,
+        ["Exodus", "Eksodus", "Exodus", "Exodus", "Exode", "Éxodo", "Êxodo"],
+        ["ex", "exod", "eks", "2mose", "2mo"]),
+    book!("LEV", "Lev", 3, Old,
+        ["Leviticus", "Levitikus", "Leviticus", "Levitikus", "Lévitique", "Levítico", "Levítico"],
+        ["lv", "le", "3mose", "3mo"]),
+    book!("NUM", "Num", 4, Old,
+        ["Numbers", "Numeri", "Numeri", "Numeri", "Nombres", "Números", "Números"],
+        ["nm", "nu", "nb", "4mose", "4mo"]),
+    book!("DEU", "Deut", 5, Old,
+        ["Deuteronomy", "Deuteronomium", "Deuteronomium", "Deuteronomium", "Deutéronome", "Deuteronomio", "Deuteronômio"],
+        ["dt", "dtn", "5mose", "5mo"]),
+    book!("JOS", "Josh", 6, Old,
+        ["Joshua", "Josua", "Jozua", "Josua", "Josué", "Josué", "Josué"],
+        ["jsh", "josh", "joz", "js"]),
+    book!("JDG", "Judg", 7, Old,
+        ["Judges", "Rigters", "Rechters", "Richter", "Juges", "Jueces", "Juízes"],
+        ["jg", "jdgs", "ri", "rig", "richt", "jue", "jz"]),
+    book!("RUT", "Ruth", 8, Old,
+        ["Ruth", "Rut", "Ruth", "Rut", "Ruth", "Rut", "Rute"],
+        ["rth", "ru", "rt"]),
+    book!("1SA", "1Sam", 9, Old,
+        ["1 Samuel", "1 Samuel", "1 Samuël", "1 Samuel", "1 Samuel", "1 Samuel", "1 Samuel"],
+        ["1sm", "1s"]),
+    book!("2SA", "2Sam", 10, Old,
+        ["2 Samuel", "2 Samuel", "2 Samuël", "2 Samuel", "2 Samuel", "2 Samuel", "2 Samuel"],
+        ["2sm", "2s"]),
+    book!("1KI", "1Kgs", 11, Old,
+        ["1 Kings", "1 Konings", "1 Koningen", "1 Könige", "1 Rois", "1 Reyes", "1 Reis"],
+        ["1kgs", "1kg", "1k", "1kon", "1kön", "1r", "1rs"]),
+    book!("2KI", "2Kgs", 12, Old,
+        ["2 Kings", "2 Konings", "2 Koningen", "2 Könige", "2 Rois", "2 Reyes", "2 Reis"],
+        ["2kgs", "2kg", "2k", "2kon", "2kön", "2r", "2rs"]),
+    book!("1CH", "1Chr", 13, Old,
+        ["1 Chronicles", "1 Kronieke", "1 Kronieken", "1 Chronik", "1 Chroniques", "1 Crónicas", "1 Crônicas"],
+        ["1chr", "1chron", "1kron", "1cr"]),
+    book!("2CH", "2Chr", 14, Old,
+        ["2 Chronicles", "2 Kronieke", "2 Kronieken", "2 Chronik", "2 Chroniques", "2 Crónicas", "2 Crônicas"],
+        ["2chr", "2chron", "2kron", "2cr"]),
+    book!("EZR", "Ezra", 15, Old,
+        ["Ezra", "Esra", "Ezra", "Esra", "Esdras", "Esdras", "Esdras"],
+        ["esr"]),
+    book!("NEH", "Neh", 16, Old,
+        ["Nehemiah", "Nehemia", "Nehemia", "Nehemia", "Néhémie", "Nehemías", "Neemias"],
+        ["ne", "né"]),
+    book!("EST", "Esth", 17, Old,
+        ["Esther", "Ester", "Ester", "Ester", "Esther", "Ester", "Ester"],
+        ["es", "esth"]),
+    book!("JOB", "Job", 18, Old,
+        ["Job", "Job", "Job", "Hiob", "Job", "Job", "Jó"],
+        ["jb", "hi"]),
+    book!("PSA", "Ps", 19, Old,
+        ["Psalms", "Psalms", "Psalmen", "Psalmen", "Psaumes", "Salmos", "Salmos"],
+        ["ps", "psalm", "pss", "psm", "sal", "sl"]),
+    book!("PRO", "Prov", 20, Old,
+        ["Proverbs", "Spreuke", "Spreuken", "Sprüche", "Proverbes", "Proverbios", "Provérbios"],
+        ["pr", "prv", "prov", "spr", "pv"]),
+    book!("ECC", "Eccl", 21, Old,
+        ["Ecclesiastes", "Prediker", "Prediker", "Prediger", "Ecclésiaste", "Eclesiastés", "Eclesiastes"],
+        ["ec", "eccl", "qoh", "qoheleth", "pred", "koh", "kohelet"]),
+    book!("SNG", "Song", 22, Old,
+        ["Song of Songs", "Hooglied", "Hooglied", "Hoheslied", "Cantique des cantiques", "Cantares", "Cânticos"],
+        ["song", "sos", "songofsolomon", "canticles", "cant", "hoogl", "hld", "cnt", "ct"]),
+    book!("ISA", "Isa", 23, Old,
+        ["Isaiah", "Jesaja", "Jesaja", "Jesaja", "Ésaïe", "Isaías", "Isaías"],
+        ["is", "jes", "esaie"]),
+    book!("JER", "Jer", 24, Old,
+        ["Jeremiah", "Jeremia", "Jeremia", "Jeremia", "Jérémie", "Jeremías", "Jeremias"],
+        ["jr"]),
+    book!("LAM", "Lam", 25, Old,
+        ["Lamentations", "Klaagliedere", "Klaagliederen", "Klagelieder", "Lamentations", "Lamentaciones", "Lamentações"],
+        ["la", "lm", "klaagl", "klgl"]),
+    book!("EZK", "Ezek", 26, Old,
+        ["Ezekiel", "Esegiël", "Ezechiël", "Hesekiel", "Ézéchiel", "Ezequiel", "Ezequiel"],
+        ["ezek", "eze", "eseg", "hes", "ezechiel"]),
+    book!("DAN", "Dan", 27, Old,
+        ["Daniel", "Daniël", "Daniël", "Daniel", "Daniel", "Daniel", "Daniel"],
+        ["dn", "da"]),
+    book!("HOS", "Hos", 28, Old,
+        ["Hosea", "Hosea", "Hosea", "Hosea", "Osée", "Oseas", "Oseias"],
+        ["ho", "os"]),
+    book!("JOL", "Joel", 29, Old,
+        ["Joel", "Joël", "Joël", "Joel", "Joël", "Joel", "Joel"],
+        ["jl"]),
+    book!("AMO", "Amos", 30, Old,
+        ["Amos", "Amos", "Amos", "Amos", "Amos", "Amós", "Amós"],
+        ["am"]),
+    book!("OBA", "Obad", 31, Old,
+        ["Obadiah", "Obadja", "Obadja", "Obadja", "Abdias", "Abdías", "Obadias"],
+        ["ob", "obad", "obd", "abd"]),
+    book!("JON", "Jonah", 32, Old,
+        ["Jonah", "Jona", "Jona", "Jona", "Jonas", "Jonás", "Jonas"],
+        ["jnh"]),
+    book!("MIC", "Mic", 33, Old,
+        ["Micah", "Miga", "Micha", "Micha", "Michée", "Miqueas", "Miqueias"],
+        ["mc", "mi", "mq"]),
+    book!("NAM", "Nah", 34, Old,
+        ["Nahum", "Nahum", "Nahum", "Nahum", "Nahum", "Nahúm", "Naum"],
+        ["na"]),
+    book!("HAB", "Hab", 35, Old,
+        ["Habakkuk", "Habakuk", "Habakuk", "Habakuk", "Habacuc", "Habacuc", "Habacuque"],
+        ["hb", "hc"]),
+    book!("ZEP", "Zeph", 36, Old,
+        ["Zephaniah", "Sefanja", "Sefanja", "Zefanja", "Sophonie", "Sofonías", "Sofonias"],
+        ["zp", "zeph", "zef", "sef", "sof", "sf"]),
+    book!("HAG", "Hag", 37, Old,
+        ["Haggai", "Haggai", "Haggai", "Haggai", "Aggée", "Hageo", "Ageu"],
+        ["hg", "ag"]),
+    book!("ZEC", "Zech", 38, Old,
+        ["Zechariah", "Sagaria", "Zacharia", "Sacharja", "Zacharie", "Zacarías", "Zacarias"],
+        ["zc", "zech", "sag", "sach", "zach", "zac", "za"]),
+    book!("MAL", "Mal", 39, Old,
+        ["Malachi", "Maleagi", "Maleachi", "Maleachi", "Malachie", "Malaquías", "Malaquias"],
+        ["ml"]),
+    book!("MAT", "Matt", 40, New,
+        ["Matthew", "Matteus", "Matteüs", "Matthäus", "Matthieu", "Mateo", "Mateus"],
+        ["mt", "matt"]),
+    book!("MRK", "Mark", 41, New,
+        ["Mark", "Markus", "Marcus", "Markus", "Marc", "Marcos", "Marcos"],
+        ["mk", "mr", "mrk"]),
+    book!("LUK", "Luke", 42, New,
+        ["Luke", "Lukas", "Lucas", "Lukas", "Luc", "Lucas", "Lucas"],
+        ["lk", "lu", "lc"]),
+    book!("JHN", "John", 43, New,
+        ["John", "Johannes", "Johannes", "Johannes", "Jean", "Juan", "João"],
+        ["jn", "joh"]),
+    book!("ACT", "Acts", 44, New,
+        ["Acts", "Handelinge", "Handelingen", "Apostelgeschichte", "Actes", "Hechos", "Atos"],
+        ["ac", "hand", "apg", "hch"]),
+    book!("ROM", "Rom", 45, New,
+        ["Romans", "Romeine", "Romeinen", "Römer", "Romains", "Romanos", "Romanos"],
+        ["rm", "ro", "röm"]),
+    book!("1CO", "1Cor", 46, New,
+        ["1 Corinthians", "1 Korintiërs", "1 Korintiërs", "1 Korinther", "1 Corinthiens", "1 Corintios", "1 Coríntios"],
+        ["1cor", "1kor"]),
+    book!("2CO", "2Cor", 47, New,
+        ["2 Corinthians", "2 Korintiërs", "2 Korintiërs", "2 Korinther", "2 Corinthiens", "2 Corintios", "2 Coríntios"],
+        ["2cor", "2kor"]),
+    book!("GAL", "Gal", 48, New,
+        ["Galatians", "Galasiërs", "Galaten", "Galater", "Galates", "Gálatas", "Gálatas"],
+        ["ga", "gl"]),
+    book!("EPH", "Eph", 49, New,
+        ["Ephesians", "Efesiërs", "Efeziërs", "Epheser", "Éphésiens", "Efesios", "Efésios"],
+        ["ephes", "ef", "ep"]),
+    book!("PHP", "Phil", 50, New,
+        ["Philippians", "Filippense", "Filippenzen", "Philipper", "Philippiens", "Filipenses", "Filipenses"],
+        ["phil", "fil", "fp"]),
+    book!("COL", "Col", 51, New,
+        ["Colossians", "Kolossense", "Kolossenzen", "Kolosser", "Colossiens", "Colosenses", "Colossenses"],
+        ["kol", "cl"]),
+    book!("1TH", "1Thess", 52, New,
+        ["1 Thessalonians", "1 Tessalonisense", "1 Tessalonicenzen", "1 Thessalonicher", "1 Thessaloniciens", "1 Tesalonicenses", "1 Tessalonicenses"],
+        ["1thess", "1thes", "1tess", "1ts"]),
+    book!("2TH", "2Thess", 53, New,
+        ["2 Thessalonians", "2 Tessalonisense", "2 Tessalonicenzen", "2 Thessalonicher", "2 Thessaloniciens", "2 Tesalonicenses", "2 Tessalonicenses"],
+        ["2thess", "2thes", "2tess", "2ts"]),
+    book!("1TI", "1Tim", 54, New,
+        ["1 Timothy", "1 Timoteus", "1 Timoteüs", "1 Timotheus", "1 Timothée", "1 Timoteo", "1 Timóteo"],
+        ["1tim", "1tm"]),
+    book!("2TI", "2Tim", 55, New,
+        ["2 Timothy", "2 Timoteus", "2 Timoteüs", "2 Timotheus", "2 Timothée", "2 Timoteo", "2 Timóteo"],
+        ["2tim", "2tm"]),
+    book!("TIT", "Titus", 56, New,
+        ["Titus", "Titus", "Titus", "Titus", "Tite", "Tito", "Tito"],
+        ["ti", "tt"]),
+    book!("PHM", "Phlm", 57, New,
+        ["Philemon", "Filemon", "Filemon", "Philemon", "Philémon", "Filemón", "Filemom"],
+        ["phlm", "philem", "filem", "flm", "fm"]),
+    book!("HEB", "Heb", 58, New,
+        ["Hebrews", "Hebreërs", "Hebreeën", "Hebräer", "Hébreux", "Hebreos", "Hebreus"],
+        ["hebr"]),
+    book!("JAS", "Jas", 59, New,
+        ["James", "Jakobus", "Jakobus", "Jakobus", "Jacques", "Santiago", "Tiago"],
+        ["jm", "jak", "jc", "stg", "tg"]),
+    book!("1PE", "1Pet", 60, New,
+        ["1 Peter", "1 Petrus", "1 Petrus", "1 Petrus", "1 Pierre", "1 Pedro", "1 Pedro"],
+        ["1pet", "1pt", "1p", "1petr"]),
+    book!("2PE", "2Pet", 61, New,
+        ["2 Peter", "2 Petrus", "2 Petrus", "2 Petrus", "2 Pierre", "2 Pedro", "2 Pedro"],
+        ["2pet", "2pt", "2p", "2petr"]),
+    book!("1JN", "1John", 62, New,
+        ["1 John", "1 Johannes", "1 Johannes", "1 Johannes", "1 Jean", "1 Juan", "1 João"],
+        ["1jo", "1joh"]),
+    book!("2JN", "2John", 63, New,
+        ["2 John", "2 Johannes", "2 Johannes", "2 Johannes", "2 Jean", "2 Juan", "2 João"],
+        ["2jo", "2joh"]),
+    book!("3JN", "3John", 64, New,
+        ["3 John", "3 Johannes", "3 Johannes", "3 Johannes", "3 Jean", "3 Juan", "3 João"],
+        ["3jo", "3joh"]),
+    book!("JUD", "Jude", 65, New,
+        ["Jude", "Judas", "Judas", "Judas", "Jude", "Judas", "Judas"],
+        ["jd"]),
+    book!("REV", "Rev", 66, New,
+        ["Revelation", "Openbaring", "Openbaring", "Offenbarung", "Apocalypse", "Apocalipsis", "Apocalipse"],
+        ["rv", "re", "revelations", "offb", "openb", "op", "ap"]),
+    book!("TOB", "Tob", 67, Deuterocanon,
+        ["Tobit", "Tobit", "Tobit", "Tobit", "Tobie", "Tobías", "Tobias"],
+        ["tb"]),
+    book!("JDT", "Jdt", 68, Deuterocanon,
+        ["Judith", "Judit", "Judit", "Judit", "Judith", "Judit", "Judite"],
+        ["jdth"]),
+    book!("ESG", "EsthGr", 69, Deuterocanon,
+        ["Greek Esther", "Griekse Ester", "Griekse Ester", "Griechische Ester", "Esther grec", "Ester griego", "Ester grego"],
+        ["addesth", "gkesth"]),
+    book!("WIS", "Wis", 70, Deuterocanon,
+        ["Wisdom of Solomon", "Wysheid van Salomo", "Wijsheid van Salomo", "Weisheit", "Sagesse", "Sabiduría", "Sabedoria"],
+        ["wisd", "ws", "weish"]),
+    book!("SIR", "Sir", 71, Deuterocanon,
+        ["Sirach", "Sirag", "Sirach", "Jesus Sirach", "Siracide", "Eclesiástico", "Eclesiástico"],
+        ["ecclus", "ecclesiasticus", "sirach"]),
+    book!("BAR", "Bar", 72, Deuterocanon,
+        ["Baruch", "Barug", "Baruch", "Baruch", "Baruch", "Baruc", "Baruc"],
+        []),
+    book!("LJE", "EpJer", 73, Deuterocanon,
+        ["Letter of Jeremiah", "Brief van Jeremia", "Brief van Jeremia", "Brief des Jeremia", "Lettre de Jérémie", "Carta de Jeremías", "Carta de Jeremias"],
+        ["epjer", "letjer"]),
+    book!("S3Y", "PrAzar", 74, Deuterocanon,
+        ["Song of the Three Young Men", "Gebed van Asarja", "Gebed van Azarja", "Gebet Asarjas", "Cantique des trois enfants", "Cántico de los tres jóvenes", "Cântico dos três jovens"],
+        ["praz", "prazar", "sgthree"]),
+    book!("SUS", "Sus", 75, Deuterocanon,
+        ["Susanna", "Susanna", "Susanna", "Susanna", "Suzanne", "Susana", "Susana"],
+        []),
+    book!("BEL", "Bel", 76, Deuterocanon,
+        ["Bel and the Dragon", "Bel en die draak", "Bel en de draak", "Bel und der Drache", "Bel et le dragon", "Bel y el dragón", "Bel e o dragão"],
+        []),
+    book!("1MA", "1Macc", 77, Deuterocanon,
+        ["1 Maccabees", "1 Makkabeërs", "1 Makkabeeën", "1 Makkabäer", "1 Maccabées", "1 Macabeos", "1 Macabeus"],
+        ["1mac", "1macc", "1makk"]),
+    book!("2MA", "2Macc", 78, Deuterocanon,
+        ["2 Maccabees", "2 Makkabeërs", "2 Makkabeeën", "2 Makkabäer", "2 Maccabées", "2 Macabeos", "2 Macabeus"],
+        ["2mac", "2macc", "2makk"]),
+    book!("3MA", "3Macc", 79, Deuterocanon,
+        ["3 Maccabees", "3 Makkabeërs", "3 Makkabeeën", "3 Makkabäer", "3 Maccabées", "3 Macabeos", "3 Macabeus"],
+        ["3mac", "3macc", "3makk"]),
+    book!("4MA", "4Macc", 80, Deuterocanon,
+        ["4 Maccabees", "4 Makkabeërs", "4 Makkabeeën", "4 Makkabäer", "4 Maccabées", "4 Macabeos", "4 Macabeus"],
+        ["4mac", "4macc", "4makk"]),
+    book!("1ES", "1Esd", 81, Deuterocanon,
+        ["1 Esdras", "1 Esdras", "1 Esdras", "3 Esra", "1 Esdras", "1 Esdras", "1 Esdras"],
+        ["1esd"]),
+    book!("2ES", "2Esd", 82, Deuterocanon,
+        ["2 Esdras", "2 Esdras", "2 Esdras", "4 Esra", "2 Esdras", "2 Esdras", "2 Esdras"],
+        ["2esd"]),
+    book!("MAN", "PrMan", 83, Deuterocanon,
+        ["Prayer of Manasseh", "Gebed van Manasse", "Gebed van Manasse", "Gebet des Manasse", "Prière de Manassé", "Oración de Manasés", "Oração de Manassés"],
+        ["prman"]),
+    book!("PS2", "AddPs", 84, Deuterocanon,
+        ["Psalm 151", "Psalm 151", "Psalm 151", "Psalm 151", "Psaume 151", "Salmo 151", "Salmo 151"],
+        ["ps151", "addps"]),
+    book!("DAG", "AddDan", 85, Deuterocanon,
+        ["Greek Daniel", "Griekse Daniël", "Griekse Daniël", "Griechischer Daniel", "Daniel grec", "Daniel griego", "Daniel grego"],
+        ["adddan", "gkdan"]),
+    book!("ENO", "1En", 86, Deuterocanon,
+        ["1 Enoch", "1 Henog", "1 Henoch", "1 Henoch", "1 Hénoch", "1 Enoc", "1 Enoque"],
+        ["enoch", "1enoch"]),
+    book!("JUB", "Jub", 87, Deuterocanon,
+        ["Jubilees", "Jubileë", "Jubileeën", "Jubiläen", "Jubilés", "Jubileos", "Jubileus"],
+        []),
+    book!("1MQ", "1Meq", 88, Deuterocanon,
+        ["1 Meqabyan", "1 Meqabyan", "1 Meqabyan", "1 Meqabyan", "1 Meqabyan", "1 Meqabyan", "1 Meqabyan"],
+        []),
+    book!("2MQ", "2Meq", 89, Deuterocanon,
+        ["2 Meqabyan", "2 Meqabyan", "2 Meqabyan", "2 Meqabyan", "2 Meqabyan", "2 Meqabyan", "2 Meqabyan"],
+        []),
+    book!("3MQ", "3Meq", 90, Deuterocanon,
+        ["3 Meqabyan", "3 Meqabyan", "3 Meqabyan", "3 Meqabyan", "3 Meqabyan", "3 Meqabyan", "3 Meqabyan"],
+        []),
+    book!("4BA", "4Bar", 91, Deuterocanon,
+        ["4 Baruch", "4 Barug", "4 Baruch", "4 Baruch", "4 Baruch", "4 Baruc", "4 Baruc"],
+        ["parjer"]),
+];
+
+/// Every book in canonical order
+pub fn all() -> &'static [Book] {
+    BOOKS
+}
+
+/// Look up a book by its USFM or OSIS code, in any case
+pub fn by_code(code: &str) -> Option<&'static Book> {
+    BOOKS
+        .iter()
+        .find(|book| book.usfm.eq_ignore_ascii_case(code) || book.osis.eq_ignore_ascii_case(code))
+}
+
+/// Lowercase a book name and fold ordinal prefixes ("I", "First", "1st")
+/// into digits, dropping spaces and periods: "I Jn." -> "1jn"
+pub fn normalize_name(name: &str) -> String {
+    let lowered = name.to_lowercase().replace('.', " ");
+    let mut words: Vec<&str> = lowered.split_whitespace().collect();
+
+    if words.len() > 1 {
+        let ordinal = match words[0] {
+            "i" | "1st" | "first" => Some("1"),
+            "ii" | "2nd" | "second" => Some("2"),
+            "iii" | "3rd" | "third" => Some("3"),
+            _ => None,
+        };
+        if let Some(ordinal) = ordinal {
+            words[0] = ordinal;
+        }
+    }
+
+    words.concat()
+}
+
+/// Strip the accents that commonly appear in book names, so "Genese"
+/// finds "Genèse"
+fn fold_accents(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' => 'a',
+            'ç' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            other => other,
+        })
+        .collect()
+}
+
+/// Resolve a book name, abbreviation, USFM or OSIS code in any catalog
+/// language: "john", "Jn", "JHN", "Johannes" and "Jean" are all John.
+///
+/// Exact names and abbreviations are tried first, then an unambiguous
+/// prefix of a name ("Gen", "Habak"), where books of the Protestant canon
+/// win over deuterocanonical ones.
+pub fn resolve(name: &str) -> Result<&'static Book, ReferenceError> {
+    let key = normalize_name(name);
+    if key.is_empty() {
+        return Err(ReferenceError::MissingBook(name.to_string()));
+    }
+
+    let exact: Vec<&'static Book> = BOOKS
+        .iter()
+        .filter(|book| {
+            book.usfm.eq_ignore_ascii_case(&key)
+                || book.osis.eq_ignore_ascii_case(&key)
+                || book.abbreviations.contains(&key.as_str())
+                || book.names.iter().any(|n| normalize_name(n) == key)
+        })
+        .collect();
+    match exact.as_slice() {
+        [book] => return Ok(book),
+        [] => {}
+        _ => return Err(ReferenceError::AmbiguousBook(name.trim().to_string())),
+    }
+
+    let folded = fold_accents(&key);
+    let prefixed: Vec<&'static Book> = BOOKS
+        .iter()
+        .filter(|book| {
+            folded.chars().count() >= 2
+                && book
+                    .names
+                    .iter()
+                    .any(|n| fold_accents(&normalize_name(n)).starts_with(&folded))
+        })
+        .collect();
+    let canonical: Vec<&'static Book> = prefixed
+        .iter()
+        .copied()
+        .filter(|book| book.testament != Testament::Deuterocanon)
+        .collect();
+    let matches = if canonical.is_empty() {
+        prefixed
+    } else {
+        canonical
+    };
+
+    match matches.as_slice() {
+        [book] => Ok(book),
+        [] => Err(ReferenceError::UnknownBook(name.trim().to_string())),
+        _ => Err(ReferenceError::AmbiguousBook(name.trim().to_string())),
+    }
+}
@@ -0,0 +1,151 @@
+//! Canons: which books a church tradition counts as scripture, and in what
+//! order.
+//!
+//! Upstreams list whatever books a translation has, in their own order. A
+//! canon turns that into the list a reader of that tradition expects:
+//! Tobit after Nehemiah for Catholics, the Minor Prophets before Isaiah for
+//! the Orthodox, Enoch and Jubilees for the Ethiopian church. Additions to
+//! Esther and Daniel are separate books in the catalog and are listed after
+//! the book they extend.
+
+use serde::Serialize;
+
+use crate::api::models::Book;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Canon {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// USFM codes in this canon's order
+    pub books: &'static [&'static str],
+}
+
+impl Canon {
+    /// Position of a book in this canon, if it belongs to it
+    pub fn position(&self, usfm: &str) -> Option<usize> {
+        self.books
+            .iter()
+            .position(|book| book.eq_ignore_ascii_case(usfm))
+    }
+
+    pub fn contains(&self, usfm: &str) -> bool {
+        self.position(usfm).is_some()
+    }
+
+    /// Keep the books that belong to this canon, in canon order, numbered
+    /// from 1 in `order`
+    pub fn arrange(&self, books: Vec<Book>) -> Vec<Book> {
+        let mut kept: Vec<(usize, Book)> = books
+            .into_iter()
+            .filter_map(|book| Some((self.position(&book.id)?, book)))
+            .collect();
+        kept.sort_by_key(|(position, _)| *position);
+        kept.into_iter()
+            .enumerate()
+            .map(|(index, (_, mut book))| {
+                book.order = Some(index as u32 + 1);
+                book
+            })
+            .collect()
+    }
+}
+
+const NEW_TESTAMENT: [&str; 27] = [
+    "MAT", "MRK", "LUK", "JHN", "ACT", "ROM", "1CO", "2CO", "GAL", "EPH", "PHP", "COL", "1TH",
+    "2TH", "1TI", "2TI", "TIT", "PHM", "HEB", "JAS", "1PE", "2PE", "1JN", "2JN", "3JN", "JUD",
+    "REV",
+];
+
+/// An Old Testament list followed by the New Testament
+macro_rules! canon_books {
+    ($($book:literal),* $(,)?) => {{
+        const OLD: &[&str] = &[$($book),*];
+        const BOOKS: [&str; OLD.len() + 27] = {
+            let mut books = [""; OLD.len() + 27];
+            let mut i = 0;
+            while i < OLD.len() {
+                books[i] = OLD[i];
+                i += 1;
+            }
+            while i < OLD.len() + 27 {
+                books[i] = NEW_TESTAMENT[i - OLD.len()];
+                i += 1;
+            }
+            books
+        };
+        &BOOKS
+    }};
+}
+
+const CANONS: &[Canon] = &[
+    Canon {
+        id: "protestant",
+        name: "Protestant",
+        description: "The 39 books of the Hebrew Bible and the 27 of the New Testament",
+        books: canon_books!(
+            "GEN", "EXO", "LEV", "NUM", "DEU", "JOS", "JDG", "RUT", "1SA", "2SA", "1KI", "2KI",
+            "1CH", "2CH", "EZR", "NEH", "EST", "JOB", "PSA", "PRO", "ECC", "SNG", "ISA", "JER",
+            "LAM", "EZK", "DAN", "HOS", "JOL", "AMO", "OBA", "JON", "MIC", "NAM", "HAB", "ZEP",
+            "HAG", "ZEC", "MAL",
+        ),
+    },
+    Canon {
+        id: "catholic",
+        name: "Catholic",
+        description: "The Protestant books with Tobit, Judith, 1-2 Maccabees, Wisdom, Sirach, \
+                      Baruch and the Greek additions to Esther and Daniel",
+        books: canon_books!(
+            "GEN", "EXO", "LEV", "NUM", "DEU", "JOS", "JDG", "RUT", "1SA", "2SA", "1KI", "2KI",
+            "1CH", "2CH", "EZR", "NEH", "TOB", "JDT", "EST", "ESG", "1MA", "2MA", "JOB", "PSA",
+            "PRO", "ECC", "SNG", "WIS", "SIR", "ISA", "JER", "LAM", "BAR", "LJE", "EZK", "DAN",
+            "S3Y", "SUS", "BEL", "DAG", "HOS", "JOL", "AMO", "OBA", "JON", "MIC", "NAM", "HAB",
+            "ZEP", "HAG", "ZEC", "MAL",
+        ),
+    },
+    Canon {
+        id: "orthodox",
+        name: "Eastern Orthodox",
+        description: "The Septuagint books in Septuagint order, adding 1 Esdras, 3 Maccabees, \
+                      the Prayer of Manasseh and Psalm 151 to the Catholic books, with \
+                      4 Maccabees as an appendix",
+        books: canon_books!(
+            "GEN", "EXO", "LEV", "NUM", "DEU", "JOS", "JDG", "RUT", "1SA", "2SA", "1KI", "2KI",
+            "1CH", "2CH", "MAN", "1ES", "EZR", "NEH", "TOB", "JDT", "EST", "ESG", "1MA", "2MA",
+            "3MA", "PSA", "PS2", "JOB", "PRO", "ECC", "SNG", "WIS", "SIR", "HOS", "AMO", "MIC",
+            "JOL", "OBA", "JON", "NAM", "HAB", "ZEP", "HAG", "ZEC", "MAL", "ISA", "JER", "BAR",
+            "LAM", "LJE", "EZK", "DAN", "S3Y", "SUS", "BEL", "DAG", "4MA",
+        ),
+    },
+    Canon {
+        id: "ethiopian",
+        name: "Ethiopian Orthodox",
+        description: "The narrower Ethiopian canon: the Orthodox books without 1-4 Maccabees, \
+                      with 1 Enoch, Jubilees, 1-3 Meqabyan, 2 Esdras and 4 Baruch",
+        books: canon_books!(
+            "GEN", "EXO", "LEV", "NUM", "DEU", "JOS", "JDG", "RUT", "ENO", "JUB", "1SA", "2SA",
+            "1KI", "2KI", "1CH", "2CH", "MAN", "1ES", "2ES", "EZR", "NEH", "TOB", "JDT", "EST",
+            "ESG", "1MQ", "2MQ", "3MQ", "JOB", "PSA", "PS2", "PRO", "ECC", "SNG", "WIS", "SIR",
+            "ISA", "JER", "LAM", "BAR", "LJE", "4BA", "EZK", "DAN", "S3Y", "SUS", "BEL", "DAG",
+            "HOS", "AMO", "MIC", "JOL", "OBA", "JON", "NAM", "HAB", "ZEP", "HAG", "ZEC", "MAL",
+        ),
+    },
+];
+
+/// Every canon, narrowest first
+pub fn all() -> &'static [Canon] {
+    CANONS
+}
+
+pub fn by_id(id: &str) -> Option<&'static Canon> {
+    CANONS
+        .iter()
+        .find(|canon| canon.id.eq_ignore_ascii_case(id.trim()))
+}
+
+/// The narrowest canon holding every one of `books` (USFM codes)
+pub fn infer<'a>(books: impl IntoIterator<Item = &'a str> + Clone) -> Option<&'static Canon> {
+    CANONS
+        .iter()
+        .find(|canon| books.clone().into_iter().all(|book| canon.contains(book)))
+}
@@ -1,9 +1,26 @@
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::canons;
+use crate::versification::Scheme;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub bible_api: BibleApiConfig,
+    pub bible_brain: BibleBrainConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    pub store: StoreConfig,
+    #[serde(default)]
+    pub crossrefs: CrossRefsConfig,
+    #[serde(default)]
+    pub original: OriginalConfig,
+    #[serde(default)]
+    pub files: FilesConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -15,6 +32,162 @@ pub struct ServerConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct BibleApiConfig {
     pub base_url: String,
+    /// Providers to try in order, comma-separated: "mirror", "helloao", "bible_brain"
+    pub provider: String,
+    /// Seconds each provider in the chain gets before the next one is tried
+    pub provider_timeout_secs: u64,
+    /// Provider-specific translation IDs, keyed by provider and then by our ID
+    #[serde(default)]
+    pub translation_ids: HashMap<String, HashMap<String, String>>,
+    /// Versification scheme per translation ID, for translations whose
+    /// language does not tell
+    #[serde(default)]
+    pub versification: HashMap<String, Scheme>,
+    /// Canon ID per translation ID, for translations whose books do not tell
+    #[serde(default)]
+    pub canons: HashMap<String, String>,
+}
+
+impl BibleApiConfig {
+    /// Provider names in the order they should be tried
+    pub fn provider_chain(&self) -> Vec<&str> {
+        self.provider
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BibleBrainConfig {
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StoreConfig {
+    /// Directory for the on-disk mirror; an empty path disables it
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CrossRefsConfig {
+    /// OpenBible.info-style cross-reference file; an empty path disables
+    /// cross-references
+    #[serde(default)]
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FilesConfig {
+    /// Directory of USFM, USX, OSIS and Zefania translations to serve
+    /// alongside the provider chain; an empty path disables imports
+    #[serde(default)]
+    pub path: String,
+}
+
+/// Local tagged original-language texts and lexicon; empty paths disable them
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OriginalConfig {
+    /// Directory of OSHB OSIS files (`Gen.xml`, ...)
+    #[serde(default)]
+    pub hebrew_path: String,
+    /// Directory of MorphGNT files (`61-Mt-morphgnt.txt`, ...)
+    #[serde(default)]
+    pub greek_path: String,
+    /// Comma-separated Strong's dictionary files
+    #[serde(default)]
+    pub lexicon_path: String,
+}
+
+/// Timeouts, retries and circuit breaking for upstream HTTP calls
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    pub connect_timeout_ms: u64,
+    /// Limit on waiting for a complete response once connected
+    pub read_timeout_ms: u64,
+    /// Retries after the first attempt, for connection errors, timeouts and 5xx
+    pub max_retries: u32,
+    pub backoff_base_ms: u64,
+    pub backoff_max_ms: u64,
+    /// Consecutive failures that open a host's circuit
+    pub breaker_threshold: u32,
+    /// How long an open circuit fails fast before a trial request
+    pub breaker_cooldown_secs: u64,
+}
+
+impl HttpConfig {
+    /// Longest backoff, in milliseconds, before retrying after `attempt`:
+    /// base * 2^attempt, capped at the maximum
+    pub fn backoff_ceiling_ms(&self, attempt: u32) -> u64 {
+        self.backoff_base_ms
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.backoff_max_ms)
+    }
+
+    /// The longest one upstream GET can take: every attempt running into
+    /// the read timeout, plus the longest backoff before each retry
+    pub fn worst_case(&self) -> Duration {
+        let backoff: u64 = (0..self.max_retries)
+            .map(|attempt| self.backoff_ceiling_ms(attempt))
+            .sum();
+        let attempts = u64::from(self.max_retries) + 1;
+        Duration::from_millis(self.read_timeout_ms.saturating_mul(attempts) + backoff)
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: 3_000,
+            read_timeout_ms: 10_000,
+            max_retries: 2,
+            backoff_base_ms: 200,
+            backoff_max_ms: 2_000,
+            breaker_threshold: 5,
+            breaker_cooldown_secs: 30,
+        }
+    }
+}
+
+/// Limits and per-endpoint TTLs for the in-process HelloAO response cache
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Total size budget for cached response bodies
+    pub max_bytes: usize,
+    /// Responses larger than this are never cached
+    pub max_entry_bytes: usize,
+    pub translations_ttl_secs: u64,
+    pub books_ttl_secs: u64,
+    pub chapter_ttl_secs: u64,
+    pub commentaries_ttl_secs: u64,
+    pub commentary_ttl_secs: u64,
+    pub commentary_books_ttl_secs: u64,
+    pub profiles_ttl_secs: u64,
+    pub datasets_ttl_secs: u64,
+    pub dataset_chapter_ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024 * 1024,
+            max_entry_bytes: 2 * 1024 * 1024,
+            translations_ttl_secs: 6 * 60 * 60,
+            books_ttl_secs: 24 * 60 * 60,
+            chapter_ttl_secs: 7 * 24 * 60 * 60,
+            commentaries_ttl_secs: 6 * 60 * 60,
+            commentary_ttl_secs: 7 * 24 * 60 * 60,
+            commentary_books_ttl_secs: 24 * 60 * 60,
+            profiles_ttl_secs: 24 * 60 * 60,
+            datasets_ttl_secs: 24 * 60 * 60,
+            dataset_chapter_ttl_secs: 7 * 24 * 60 * 60,
+        }
+    }
 }
 
 impl Config {
@@ -22,16 +195,20 @@ impl Config {
         let mut builder = config::Config::builder()
             .set_default("server.host", "0.0.0.0")?
             .set_default("server.port", 3000)?
-            .set_default("bible_api.base_url", "https://bible.helloao.org/api")?;
+            .set_default("bible_api.base_url", "https://bible.helloao.org/api")?
+            .set_default("bible_api.provider", "mirror,helloao,bible_brain")?
+            .set_default("bible_api.provider_timeout_secs", 35)?
+            .set_default("bible_brain.base_url", "https://4.dbt.io/api")?
+            .set_default("store.path", "data")?;
 
         if let Ok(host) = std::env::var("HOST") {
             builder = builder.set_override("server.host", host)?;
         }
 
         if let Ok(port) = std::env::var("PORT") {
-            let port: u16 = port.parse().map_err(|_| {
-                config::ConfigError::Message("Invalid PORT value".to_string())
-            })?;
+            let port: u16 = port
+                .parse()
+                .map_err(|_| config::ConfigError::Message("Invalid PORT value".to_string()))?;
             builder = builder.set_override("server.port", port)?;
         }
 
@@ -39,7 +216,158 @@ impl Config {
             builder = builder.set_override("bible_api.base_url", base_url)?;
         }
 
-        builder.build()?.try_deserialize()
+        if let Ok(provider) = std::env::var("BIBLE_PROVIDER") {
+            builder = builder.set_override("bible_api.provider", provider)?;
+        }
+
+        if let Ok(base_url) = std::env::var("BIBLE_BRAIN_BASE_URL") {
+            builder = builder.set_override("bible_brain.base_url", base_url)?;
+        }
+
+        if let Ok(api_key) = std::env::var("BIBLE_BRAIN_API_KEY") {
+            builder = builder.set_override("bible_brain.api_key", api_key)?;
+        }
+
+        if let Ok(path) = std::env::var("STORE_PATH") {
+            builder = builder.set_override("store.path", path)?;
+        }
+
+        if let Ok(path) = std::env::var("FILES_PATH") {
+            builder = builder.set_override("files.path", path)?;
+        }
+
+        if let Ok(path) = std::env::var("CROSSREFS_PATH") {
+            builder = builder.set_override("crossrefs.path", path)?;
+        }
+
+        for (var, key) in [
+            ("OSHB_PATH", "original.hebrew_path"),
+            ("MORPHGNT_PATH", "original.greek_path"),
+            ("LEXICON_PATH", "original.lexicon_path"),
+        ] {
+            if let Ok(path) = std::env::var(var) {
+                builder = builder.set_override(key, path)?;
+            }
+        }
+
+        if let Ok(secs) = std::env::var("PROVIDER_TIMEOUT_SECS") {
+            let secs: u64 = secs.parse().map_err(|_| {
+                config::ConfigError::Message("Invalid PROVIDER_TIMEOUT_SECS value".to_string())
+            })?;
+            builder = builder.set_override("bible_api.provider_timeout_secs", secs)?;
+        }
+
+        for (var, key) in [
+            ("CACHE_MAX_BYTES", "cache.max_bytes"),
+            ("CACHE_MAX_ENTRY_BYTES", "cache.max_entry_bytes"),
+            ("CACHE_TRANSLATIONS_TTL_SECS", "cache.translations_ttl_secs"),
+            ("CACHE_BOOKS_TTL_SECS", "cache.books_ttl_secs"),
+            ("CACHE_CHAPTER_TTL_SECS", "cache.chapter_ttl_secs"),
+            ("CACHE_COMMENTARIES_TTL_SECS", "cache.commentaries_ttl_secs"),
+            ("CACHE_COMMENTARY_TTL_SECS", "cache.commentary_ttl_secs"),
+            (
+                "CACHE_COMMENTARY_BOOKS_TTL_SECS",
+                "cache.commentary_books_ttl_secs",
+            ),
+            ("CACHE_PROFILES_TTL_SECS", "cache.profiles_ttl_secs"),
+            ("CACHE_DATASETS_TTL_SECS", "cache.datasets_ttl_secs"),
+            (
+                "CACHE_DATASET_CHAPTER_TTL_SECS",
+                "cache.dataset_chapter_ttl_secs",
+            ),
+            ("HTTP_CONNECT_TIMEOUT_MS", "http.connect_timeout_ms"),
+            ("HTTP_READ_TIMEOUT_MS", "http.read_timeout_ms"),
+            ("HTTP_MAX_RETRIES", "http.max_retries"),
+            ("HTTP_BACKOFF_BASE_MS", "http.backoff_base_ms"),
+            ("HTTP_BACKOFF_MAX_MS", "http.backoff_max_ms"),
+            ("HTTP_BREAKER_THRESHOLD", "http.breaker_threshold"),
+            ("HTTP_BREAKER_COOLDOWN_SECS", "http.breaker_cooldown_secs"),
+        ] {
+            if let Ok(value) = std::env::var(var) {
+                let value: u64 = value
+                    .parse()
+                    .map_err(|_| config::ConfigError::Message(format!("Invalid {} value", var)))?;
+                builder = builder.set_override(key, value)?;
+            }
+        }
+
+        let mut config: Self = builder.build()?.try_deserialize()?;
+
+        // "bible_brain:eng_kjv=ENGKJV,bible_brain:BSB=ENGBSB"
+        if let Ok(mappings) = std::env::var("TRANSLATION_IDS") {
+            for mapping in mappings.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+                let parsed = mapping.split_once(':').and_then(|(provider, ids)| {
+                    let (ours, theirs) = ids.split_once('=')?;
+                    Some((provider.trim(), ours.trim(), theirs.trim()))
+                });
+                let Some((provider, ours, theirs)) = parsed else {
+                    return Err(config::ConfigError::Message(format!(
+                        "Invalid TRANSLATION_IDS entry: {}",
+                        mapping
+                    )));
+                };
+                config
+                    .bible_api
+                    .translation_ids
+                    .entry(provider.to_string())
+                    .or_default()
+                    .insert(ours.to_string(), theirs.to_string());
+            }
+        }
+
+        // "heb_wlc=hebrew,rus_syn=synodal"
+        if let Ok(schemes) = std::env::var("VERSIFICATION") {
+            for entry in schemes.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let parsed = entry.split_once('=').and_then(|(translation, scheme)| {
+                    Some((translation.trim(), scheme.parse::<Scheme>().ok()?))
+                });
+                let Some((translation, scheme)) = parsed else {
+                    return Err(config::ConfigError::Message(format!(
+                        "Invalid VERSIFICATION entry: {}",
+                        entry
+                    )));
+                };
+                config
+                    .bible_api
+                    .versification
+                    .insert(translation.to_string(), scheme);
+            }
+        }
+
+        // "eng_dra=catholic,BSB=protestant"
+        if let Ok(entries) = std::env::var("CANONS") {
+            for entry in entries.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let Some((translation, canon)) = entry.split_once('=') else {
+                    return Err(config::ConfigError::Message(format!(
+                        "Invalid CANONS entry: {}",
+                        entry
+                    )));
+                };
+                config
+                    .bible_api
+                    .canons
+                    .insert(translation.trim().to_string(), canon.trim().to_string());
+            }
+        }
+        for (translation, canon) in &config.bible_api.canons {
+            if canons::by_id(canon).is_none() {
+                return Err(config::ConfigError::Message(format!(
+                    "Unknown canon for {}: {}",
+                    translation, canon
+                )));
+            }
+        }
+
+        // A provider cut off mid-retry never gets to use its later attempts
+        let worst_case = config.http.worst_case();
+        if Duration::from_secs(config.bible_api.provider_timeout_secs) <= worst_case {
+            return Err(config::ConfigError::Message(format!(
+                "PROVIDER_TIMEOUT_SECS ({}) must exceed the {:?} an upstream request can take \
+                 (HTTP_READ_TIMEOUT_MS * (HTTP_MAX_RETRIES + 1) plus backoff)",
+                config.bible_api.provider_timeout_secs, worst_case
+            )));
+        }
+
+        Ok(config)
     }
 }
-
@@ -0,0 +1,242 @@
+//! Cross-references loaded from a local dataset.
+//!
+//! The expected format is the tab-separated file published by
+//! OpenBible.info (which includes the Treasury of Scripture Knowledge
+//! references): one reference per line as `From Verse`, `To Verse` and
+//! `Votes`, with OSIS references such as `Gen.1.1` or `Job.38.4-Job.38.7`.
+//! The votes column may be left out, in which case every reference weighs
+//! 1. References use English (KJV) verse numbering.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use crate::books;
+use crate::error::{AppError, Result};
+use crate::passage::{self, PassageVerse};
+use crate::reference::{ScriptureRange, VersePoint};
+
+/// One verse's reference to a related passage
+#[derive(Debug, Clone)]
+struct Link {
+    chapter: u32,
+    verse: u32,
+    target: ScriptureRange,
+    votes: i64,
+}
+
+/// A passage related to the requested reference
+#[derive(Debug, Clone, Serialize)]
+pub struct CrossReference {
+    /// Display form of the related passage, e.g. "Hebrews 11:3"
+    pub reference: String,
+    pub range: ScriptureRange,
+    /// Summed votes (or weights) of every link to this passage
+    pub votes: i64,
+    /// Verses of the requested reference that link here
+    pub from: Vec<VersePoint>,
+    /// Passage text, when a translation was requested and could serve it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verses: Option<Vec<PassageVerse>>,
+}
+
+/// Cross-references keyed by the book they start from
+pub struct CrossRefIndex {
+    by_book: HashMap<String, Vec<Link>>,
+    len: usize,
+}
+
+/// Parse an OSIS verse such as `Gen.1.1` into its USFM book, chapter and verse
+fn osis_verse(osis: &str) -> std::result::Result<(&'static str, u32, u32), String> {
+    let mut parts = osis.split('.');
+    let (Some(book), Some(chapter), Some(verse), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!("expected Book.Chapter.Verse, found {:?}", osis));
+    };
+    let book = books::by_code(book).ok_or_else(|| format!("unknown book {:?}", book))?;
+    let number = |part: &str| {
+        part.parse::<u32>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("invalid number in {:?}", osis))
+    };
+    Ok((book.usfm, number(chapter)?, number(verse)?))
+}
+
+/// Parse an OSIS verse or verse range (`Job.38.4-Job.38.7`)
+fn osis_range(osis: &str) -> std::result::Result<ScriptureRange, String> {
+    let (first, last) = osis.split_once('-').unwrap_or((osis, osis));
+    let (book, chapter, verse) = osis_verse(first)?;
+    let (end_book, end_chapter, end_verse) = osis_verse(last)?;
+    if end_book != book || (end_chapter, end_verse) < (chapter, verse) {
+        return Err(format!("invalid range {:?}", osis));
+    }
+    Ok(ScriptureRange::new(
+        book,
+        VersePoint::verse(chapter, verse),
+        VersePoint::verse(end_chapter, end_verse),
+    ))
+}
+
+impl CrossRefIndex {
+    /// Parse a dataset; `source` names it in error messages, which give the
+    /// line of the first malformed entry
+    pub fn parse(text: &str, source: &str) -> Result<Self> {
+        let mut by_book: HashMap<String, Vec<Link>> = HashMap::new();
+        let mut len = 0;
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("From Verse") {
+                continue;
+            }
+
+            let parsed = (|| {
+                let mut columns = line.split('\t').map(str::trim);
+                let from = columns.next().unwrap_or_default();
+                let to = columns
+                    .next()
+                    .ok_or_else(|| "expected a tab-separated target".to_string())?;
+                let votes = match columns.next().filter(|v| !v.is_empty()) {
+                    Some(votes) => votes
+                        .parse::<i64>()
+                        .map_err(|_| format!("invalid votes {:?}", votes))?,
+                    None => 1,
+                };
+                let (book, chapter, verse) = osis_verse(from)?;
+                Ok::<_, String>((book, chapter, verse, osis_range(to)?, votes))
+            })();
+
+            let (book, chapter, verse, target, votes) =
+                parsed.map_err(|e| AppError::Data(format!("{}:{}: {}", source, index + 1, e)))?;
+            by_book.entry(book.to_string()).or_default().push(Link {
+                chapter,
+                verse,
+                target,
+                votes,
+            });
+            len += 1;
+        }
+
+        Ok(Self { by_book, len })
+    }
+
+    /// Number of references loaded
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Passages related to any verse of `ranges`, merged by target and ranked
+    /// by votes. Targets that start inside `ranges` and those below
+    /// `min_votes` are left out.
+    pub fn lookup(&self, ranges: &[ScriptureRange], min_votes: i64) -> Vec<CrossReference> {
+        let inside = |range: &ScriptureRange| {
+            let start = range.start.unwrap_or(VersePoint::verse(1, 1));
+            ranges.iter().any(|r| {
+                r.book == range.book
+                    && passage::contains(r, start.chapter, start.verse.unwrap_or(1))
+            })
+        };
+
+        let mut merged: Vec<CrossReference> = Vec::new();
+        let mut positions: HashMap<ScriptureRange, usize> = HashMap::new();
+        for range in ranges {
+            let Some(links) = self.by_book.get(&range.book) else {
+                continue;
+            };
+            for link in links {
+                if !passage::contains(range, link.chapter, link.verse) || inside(&link.target) {
+                    continue;
+                }
+                let from = VersePoint::verse(link.chapter, link.verse);
+                match positions.get(&link.target) {
+                    Some(&position) => {
+                        let entry = &mut merged[position];
+                        entry.votes += link.votes;
+                        if !entry.from.contains(&from) {
+                            entry.from.push(from);
+                        }
+                    }
+                    None => {
+                        positions.insert(link.target.clone(), merged.len());
+                        merged.push(CrossReference {
+                            reference: link.target.to_string(),
+                            range: link.target.clone(),
+                            votes: link.votes,
+                            from: vec![from],
+                            verses: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        merged.retain(|reference| reference.votes >= min_votes);
+        let canonical = |range: &ScriptureRange| {
+            (
+                books::by_code(&range.book).map_or(u16::MAX, |book| book.order),
+                range.start,
+            )
+        };
+        merged.sort_by(|a, b| {
+            b.votes
+                .cmp(&a.votes)
+                .then_with(|| canonical(&a.range).cmp(&canonical(&b.range)))
+        });
+        for reference in &mut merged {
+            reference.from.sort();
+        }
+        merged
+    }
+}
+
+/// The cross-reference dataset, loaded from `path` on first use
+pub struct CrossRefs {
+    path: Option<String>,
+    index: OnceCell<Arc<CrossRefIndex>>,
+}
+
+impl CrossRefs {
+    /// An empty path disables cross-references
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: (!path.is_empty()).then(|| path.to_string()),
+            index: OnceCell::new(),
+        }
+    }
+
+    pub async fn get(&self) -> Result<Arc<CrossRefIndex>> {
+        let Some(path) = &self.path else {
+            return Err(AppError::NotConfigured(
+                "Cross-references require a dataset (CROSSREFS_PATH)".to_string(),
+            ));
+        };
+
+        self.index
+            .get_or_try_init(|| async {
+                let text = tokio::fs::read_to_string(path).await.map_err(|e| {
+                    AppError::Data(format!("Cannot read cross-references from {}: {}", path, e))
+                })?;
+                let source = path.clone();
+                let index =
+                    tokio::task::spawn_blocking(move || CrossRefIndex::parse(&text, &source))
+                        .await
+                        .map_err(|e| {
+                            AppError::Internal(anyhow::anyhow!(
+                                "Cross-reference load failed: {}",
+                                e
+                            ))
+                        })??;
+                tracing::info!("Loaded {} cross-references from {}", index.len(), path);
+                Ok(Arc::new(index))
+            })
+            .await
+            .cloned()
+    }
+}
@@ -1,15 +1,48 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    Json,
 };
-use serde_json::json;
 use thiserror::Error;
 
+use crate::problem::Problem;
+
 #[derive(Error, Debug)]
 pub enum AppError {
-    #[error("Bible Brain API error: {0}")]
-    BibleBrainApi(String),
+    /// The request itself is malformed: bad chapter number, reference, filter...
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// The requested translation, book, chapter or resource does not exist
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// The feature needs configuration the server does not have
+    #[error("Not configured: {0}")]
+    NotConfigured(String),
+
+    /// An upstream API could not be reached or answered with a server error
+    #[error("Upstream unavailable: {0}")]
+    UpstreamUnavailable(String),
+
+    /// An upstream API did not answer in time
+    #[error("Upstream timed out: {0}")]
+    UpstreamTimeout(String),
+
+    /// An upstream API answered with a body we no longer understand
+    #[error("Upstream response format changed: {0}")]
+    UpstreamSchemaChanged(String),
+
+    /// An upstream API asked us to slow down
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after_secs: Option<u64>,
+    },
+
+    /// An upstream host kept failing and is skipped until its cooldown ends;
+    /// `host` is for logs and never shown to clients
+    #[error("Upstream temporarily disabled after repeated failures; retry in {retry_after_secs}s")]
+    CircuitOpen { host: String, retry_after_secs: u64 },
 
     #[error("HTTP client error: {0}")]
     HttpClient(#[from] reqwest::Error),
@@ -20,39 +53,141 @@ pub enum AppError {
     #[error("Configuration error: {0}")]
     Config(#[from] config::ConfigError),
 
+    /// A local dataset (cross-references, original texts, lexicon, imported
+    /// translations) could not be read or parsed; the message names the
+    /// file and is for logs, never shown to clients
+    #[error("Dataset error: {0}")]
+    Data(String),
+
     #[error("Internal server error: {0}")]
     Internal(#[from] anyhow::Error),
 }
 
+impl AppError {
+    /// Machine-readable error code sent to clients
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::NotFound(_) => "not_found",
+            AppError::NotConfigured(_) => "not_configured",
+            AppError::UpstreamUnavailable(_) => "upstream_unavailable",
+            AppError::UpstreamTimeout(_) => "upstream_timeout",
+            AppError::UpstreamSchemaChanged(_) => "upstream_schema_changed",
+            AppError::RateLimited { .. } => "rate_limited",
+            AppError::CircuitOpen { .. } => "circuit_open",
+            AppError::HttpClient(e) if e.is_timeout() => "upstream_timeout",
+            AppError::HttpClient(e) if e.is_decode() => "upstream_schema_changed",
+            AppError::HttpClient(_) => "upstream_unavailable",
+            AppError::Serialization(_) => "serialization_error",
+            AppError::Config(_) => "configuration_error",
+            AppError::Data(_) => "data_error",
+            AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self.code() {
+            "invalid_input" => StatusCode::BAD_REQUEST,
+            "not_found" => StatusCode::NOT_FOUND,
+            "rate_limited" => StatusCode::TOO_MANY_REQUESTS,
+            "not_configured" | "circuit_open" => StatusCode::SERVICE_UNAVAILABLE,
+            "upstream_timeout" => StatusCode::GATEWAY_TIMEOUT,
+            "upstream_unavailable" | "upstream_schema_changed" => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Seconds a client should wait before retrying, when known
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            AppError::RateLimited {
+                retry_after_secs, ..
+            } => *retry_after_secs,
+            AppError::CircuitOpen {
+                retry_after_secs, ..
+            } => Some(*retry_after_secs),
+            _ => None,
+        }
+    }
+
+    /// Classify a non-success upstream status for `what`
+    pub fn from_upstream_status(
+        status: reqwest::StatusCode,
+        what: &str,
+        body: &str,
+        retry_after_secs: Option<u64>,
+    ) -> Self {
+        let message = format!("Failed to fetch {}: {} - {}", what, status, body);
+        match status.as_u16() {
+            404 | 410 => AppError::NotFound(format!("{} does not exist upstream", what)),
+            429 => AppError::RateLimited {
+                message,
+                retry_after_secs,
+            },
+            408 | 504 => AppError::UpstreamTimeout(message),
+            _ => AppError::UpstreamUnavailable(message),
+        }
+    }
+
+    /// The error's description without the kind prefix added by `Display`
+    pub fn detail(&self) -> String {
+        match self {
+            AppError::InvalidInput(message)
+            | AppError::NotFound(message)
+            | AppError::NotConfigured(message)
+            | AppError::UpstreamUnavailable(message)
+            | AppError::UpstreamTimeout(message)
+            | AppError::UpstreamSchemaChanged(message)
+            | AppError::RateLimited { message, .. } => message.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    /// A copy of this error with its description replaced by `message`.
+    ///
+    /// Used where one error is reported on behalf of several (a provider
+    /// chain, waiters on a shared fetch); non-cloneable sources keep their
+    /// kind through their code.
+    pub fn with_message(&self, message: String) -> Self {
+        match self {
+            AppError::InvalidInput(_) => AppError::InvalidInput(message),
+            AppError::NotFound(_) => AppError::NotFound(message),
+            AppError::NotConfigured(_) => AppError::NotConfigured(message),
+            AppError::UpstreamTimeout(_) => AppError::UpstreamTimeout(message),
+            AppError::UpstreamSchemaChanged(_) => AppError::UpstreamSchemaChanged(message),
+            AppError::Data(_) => AppError::Data(message),
+            AppError::RateLimited {
+                retry_after_secs, ..
+            } => AppError::RateLimited {
+                message,
+                retry_after_secs: *retry_after_secs,
+            },
+            AppError::CircuitOpen {
+                host,
+                retry_after_secs,
+            } => AppError::CircuitOpen {
+                host: host.clone(),
+                retry_after_secs: *retry_after_secs,
+            },
+            other => match other.code() {
+                "upstream_timeout" => AppError::UpstreamTimeout(message),
+                "upstream_schema_changed" => AppError::UpstreamSchemaChanged(message),
+                "upstream_unavailable" => AppError::UpstreamUnavailable(message),
+                _ => AppError::Internal(anyhow::anyhow!(message)),
+            },
+        }
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            AppError::BibleBrainApi(msg) => (StatusCode::BAD_GATEWAY, msg),
-            AppError::HttpClient(e) => (
-                StatusCode::BAD_GATEWAY,
-                format!("HTTP client error: {}", e),
-            ),
-            AppError::Serialization(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Serialization error: {}", e),
-            ),
-            AppError::Config(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Configuration error: {}", e),
-            ),
-            AppError::Internal(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Internal error: {}", e),
-            ),
-        };
-
-        let body = Json(json!({
-            "error": error_message
-        }));
-
-        (status, body).into_response()
+        if self.status().is_server_error() {
+            tracing::error!("{}", self);
+        }
+
+        // Completed with the request path and ID by `problem::problem_details`
+        Problem::from_error(&self).into_response()
     }
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
-
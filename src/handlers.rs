@@ -1,11 +1,32 @@
 use axum::{
-    extract::Path,
+    extract::{Path, Query},
     response::Json,
 };
-use serde::Serialize;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use crate::api::helloao::HelloAOBibleClient;
+use crate::api::bible_brain::{
+    AudioChapter, Bible, BibleBrainClient, Book as BrainBook, Fileset, Language, Page, PageRequest,
+};
+use crate::api::cache::CacheStats;
+use crate::api::http::CoalescingStats;
+use crate::api::models::{Book, Chapter, Translation};
+use crate::api::provider::Served;
+use crate::api::video::{Video, VideoFilter};
+use crate::audio::{self, SyncedChapter};
+use crate::canons::{self, Canon};
+use crate::crossrefs::CrossReference;
 use crate::error::{AppError, Result};
+use crate::ids::{self, BookId, TranslationId};
+use crate::lexicon::{self, LexiconEntry};
+use crate::original::Interlinear;
+use crate::parallel::{self, ParallelPassage};
+use crate::passage::{self, Passage};
+use crate::reference;
+use crate::search::{SearchFilter, SearchResults};
+use crate::state::AppState;
+use crate::versification::Scheme;
 
 #[derive(Serialize)]
 pub struct HealthResponse {
@@ -20,68 +41,745 @@ pub async fn health() -> Json<HealthResponse> {
     })
 }
 
+/// Header naming the provider in the chain that served a response
+pub const SERVED_BY_HEADER: &str = "x-served-by";
+
+/// A JSON body with the `x-served-by` header set
+pub type ServedJson<T> = ([(&'static str, String); 1], Json<T>);
+
+fn served_json<T>(served: Served<T>) -> ServedJson<T> {
+    ([(SERVED_BY_HEADER, served.provider)], Json(served.value))
+}
+
 /// Get list of available translations
 pub async fn get_translations(
-    bible_client: axum::extract::State<HelloAOBibleClient>,
-) -> Result<Json<Vec<crate::api::helloao::Translation>>> {
-    let translations = bible_client.get_translations().await?;
-    Ok(Json(translations))
+    state: axum::extract::State<AppState>,
+) -> Result<ServedJson<Vec<Translation>>> {
+    let mut translations = state.provider.serve_translations().await?;
+    for translation in &mut translations.value {
+        translation.canon = state
+            .canon_of(translation)
+            .map(|canon| canon.id.to_string());
+    }
+    Ok(served_json(translations))
+}
+
+#[derive(Deserialize)]
+pub struct BooksQuery {
+    /// Canon ID; keeps only that canon's books, in its order
+    pub canon: Option<String>,
 }
 
 /// Get list of books for a translation
 pub async fn get_books(
-    bible_client: axum::extract::State<HelloAOBibleClient>,
+    state: axum::extract::State<AppState>,
     Path(translation): Path<String>,
-) -> Result<Json<Vec<crate::api::helloao::Book>>> {
-    let books = bible_client.get_books(&translation).await?;
-    Ok(Json(books))
+    Query(query): Query<BooksQuery>,
+) -> Result<ServedJson<Vec<Book>>> {
+    let canon = query
+        .canon
+        .as_deref()
+        .map(|id| {
+            canons::by_id(id)
+                .ok_or_else(|| AppError::InvalidInput(format!("Unknown canon: {}", id)))
+        })
+        .transpose()?;
+    let translation = state.translation_id(&translation).await?;
+    let mut books = state.provider.serve_books(translation.as_str()).await?;
+    if let Some(canon) = canon {
+        books.value = canon.arrange(books.value);
+    }
+    Ok(served_json(books))
+}
+
+/// List the canons `?canon=` accepts, with their books in order
+pub async fn get_canons() -> Json<&'static [Canon]> {
+    Json(canons::all())
 }
 
 /// Get a chapter from a translation
 pub async fn get_chapter(
-    bible_client: axum::extract::State<HelloAOBibleClient>,
+    state: axum::extract::State<AppState>,
     Path((translation, book, chapter)): Path<(String, String, String)>,
-) -> Result<Json<crate::api::helloao::Chapter>> {
-    let chapter_num: u32 = chapter.parse().map_err(|_| {
-        AppError::BibleBrainApi("Invalid chapter number".to_string())
+) -> Result<ServedJson<Chapter>> {
+    let chapter_num: u32 = chapter
+        .parse()
+        .map_err(|_| AppError::InvalidInput("Invalid chapter number".to_string()))?;
+    let book = BookId::parse(&book)?;
+    let translation = state.translation_id(&translation).await?;
+
+    let chapter_data = state
+        .provider
+        .serve_chapter(translation.as_str(), book.as_str(), chapter_num)
+        .await?;
+    Ok(served_json(chapter_data))
+}
+
+#[derive(Deserialize)]
+pub struct PassageQuery {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    pub translation: String,
+    /// Versification scheme the reference is numbered in; defaults to English
+    pub versification: Option<String>,
+}
+
+/// Parse the `versification` query parameter
+fn numbering(raw: Option<&str>) -> Result<Scheme> {
+    raw.map_or(Ok(Scheme::default()), |raw| {
+        raw.parse().map_err(AppError::InvalidInput)
+    })
+}
+
+/// Get the verses covered by a reference such as `John 3:16-18; Rom 8:28`
+pub async fn get_passage(
+    state: axum::extract::State<AppState>,
+    Query(query): Query<PassageQuery>,
+) -> Result<Json<Passage>> {
+    let numbering = numbering(query.versification.as_deref())?;
+    let translation = state.translation_id(&query.translation).await?;
+    let passage = passage::fetch_passage(
+        state.provider.as_ref(),
+        translation.as_str(),
+        state.versification(&translation).await,
+        &query.reference,
+        numbering,
+    )
+    .await?;
+    Ok(Json(passage))
+}
+
+#[derive(Deserialize)]
+pub struct ParallelQuery {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    /// Comma-separated translation IDs, e.g. `eng_kjv,BSB`
+    pub translations: String,
+    /// Versification scheme the reference is numbered in; defaults to English
+    pub versification: Option<String>,
+}
+
+/// Get the same passage from several translations, aligned by verse
+pub async fn get_parallel(
+    state: axum::extract::State<AppState>,
+    Query(query): Query<ParallelQuery>,
+) -> Result<Json<ParallelPassage>> {
+    let numbering = numbering(query.versification.as_deref())?;
+    let raw_ids: Vec<&str> = query
+        .translations
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+    // Malformed IDs are rejected before any catalog lookup
+    for raw in &raw_ids {
+        TranslationId::parse(raw)?;
+    }
+
+    let mut translations = Vec::with_capacity(raw_ids.len());
+    for raw in raw_ids {
+        let id = state.translation_id(raw).await?;
+        let scheme = state.versification(&id).await;
+        translations.push((id.to_string(), scheme));
+    }
+
+    let parallel = parallel::fetch_parallel(
+        state.provider.as_ref(),
+        &translations,
+        &query.reference,
+        numbering,
+    )
+    .await?;
+    Ok(Json(parallel))
+}
+
+#[derive(Deserialize)]
+pub struct CrossRefQuery {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    /// Include each related passage's text from this translation
+    pub translation: Option<String>,
+    /// Most cross-references returned, highest votes first (default 50)
+    pub limit: Option<usize>,
+    /// Leave out cross-references with fewer votes
+    pub min_votes: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct CrossRefResults {
+    pub reference: String,
+    /// Cross-references found before `limit` was applied
+    pub total: usize,
+    pub cross_references: Vec<CrossReference>,
+}
+
+/// Most cross-references fetched with text concurrently
+const CROSSREF_TEXT_CONCURRENCY: usize = 8;
+
+/// Most cross-references given their text, whatever the `limit`; each one
+/// costs a passage fetch
+const MAX_CROSSREF_TEXTS: usize = 50;
+
+/// Get the passages related to a reference, ranked by votes
+pub async fn get_crossrefs(
+    state: axum::extract::State<AppState>,
+    Query(query): Query<CrossRefQuery>,
+) -> Result<Json<CrossRefResults>> {
+    let ranges = reference::parse_references(&query.reference)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid reference: {}", e)))?;
+    let translation = match &query.translation {
+        Some(raw) => {
+            let id = state.translation_id(raw).await?;
+            let scheme = state.versification(&id).await;
+            Some((id, scheme))
+        }
+        None => None,
+    };
+
+    let index = state.crossrefs.get().await?;
+    let mut cross_references = index.lookup(&ranges, query.min_votes.unwrap_or(i64::MIN));
+    let total = cross_references.len();
+    cross_references.truncate(query.limit.unwrap_or(50).clamp(1, 500));
+
+    if let Some((translation, scheme)) = &translation {
+        let provider = state.provider.as_ref();
+        let references: Vec<String> = cross_references
+            .iter()
+            .take(MAX_CROSSREF_TEXTS)
+            .map(|cross_reference| cross_reference.reference.clone())
+            .collect();
+        let texts: Vec<Option<Vec<passage::PassageVerse>>> = stream::iter(references)
+            .map(|reference| async move {
+                passage::fetch_passage(
+                    provider,
+                    translation.as_str(),
+                    *scheme,
+                    &reference,
+                    Scheme::English,
+                )
+                .await
+                .map_err(|e| tracing::debug!("No text for {}: {}", reference, e))
+                .ok()
+                .map(|passage| passage.verses)
+            })
+            .buffered(CROSSREF_TEXT_CONCURRENCY)
+            .collect()
+            .await;
+        for (cross_reference, verses) in cross_references.iter_mut().zip(texts) {
+            cross_reference.verses = verses;
+        }
+    }
+
+    Ok(Json(CrossRefResults {
+        reference: ranges
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; "),
+        total,
+        cross_references,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct InterlinearQuery {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    /// Show each verse in this translation under the original
+    pub translation: Option<String>,
+    /// Versification scheme the reference is numbered in; defaults to English
+    pub versification: Option<String>,
+}
+
+/// Get the original-language words of a passage with lemma, Strong's
+/// number, morphology and gloss
+pub async fn get_interlinear(
+    state: axum::extract::State<AppState>,
+    Query(query): Query<InterlinearQuery>,
+) -> Result<Json<Interlinear>> {
+    let numbering = numbering(query.versification.as_deref())?;
+    let translation = match &query.translation {
+        Some(raw) => Some(state.translation_id(raw).await?),
+        None => None,
+    };
+    let lexicon = if state.lexicon.is_configured() {
+        Some(state.lexicon.get().await?)
+    } else {
+        None
+    };
+
+    let mut interlinear = state
+        .original
+        .interlinear(&query.reference, numbering, lexicon.as_deref())
+        .await?;
+
+    if let Some(translation) = translation {
+        let passage = passage::fetch_passage(
+            state.provider.as_ref(),
+            translation.as_str(),
+            state.versification(&translation).await,
+            &query.reference,
+            numbering,
+        )
+        .await?;
+        let texts: HashMap<(&str, u32, u32), &str> = passage
+            .verses
+            .iter()
+            .map(|v| ((v.book.as_str(), v.chapter, v.verse), v.text.as_str()))
+            .collect();
+        for verse in &mut interlinear.verses {
+            verse.translation_text = texts
+                .get(&(verse.book.as_str(), verse.chapter, verse.verse))
+                .map(|text| text.to_string());
+        }
+        interlinear.translation = Some(translation.to_string());
+    }
+
+    Ok(Json(interlinear))
+}
+
+/// Get a Strong's lexicon entry, e.g. `H7225` or `G3056`
+pub async fn get_lexicon_entry(
+    state: axum::extract::State<AppState>,
+    Path(strongs): Path<String>,
+) -> Result<Json<LexiconEntry>> {
+    let id = lexicon::normalize_strongs(&strongs).ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "Invalid Strong's number: {:?} (expected H or G followed by digits)",
+            strongs.chars().take(16).collect::<String>()
+        ))
     })?;
-    
-    let chapter_data = bible_client.get_chapter(&translation, &book, chapter_num).await?;
-    Ok(Json(chapter_data))
+    let index = state.lexicon.get().await?;
+    let entry = index
+        .get(&id)
+        .ok_or_else(|| AppError::NotFound(format!("No lexicon entry for {}", id)))?;
+    Ok(Json(entry.clone()))
+}
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+    /// Book name, abbreviation or USFM code
+    pub book: Option<String>,
+    /// "ot" or "nt"
+    pub testament: Option<String>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+/// Search every verse of a mirrored translation
+pub async fn search_translation(
+    state: axum::extract::State<AppState>,
+    Path(translation): Path<String>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<SearchResults>> {
+    let translation = state.translation_id(&translation).await?;
+    let store = state.store.as_ref().ok_or_else(|| {
+        AppError::NotConfigured("Search requires the on-disk store (STORE_PATH)".to_string())
+    })?;
+
+    let book = params
+        .book
+        .as_deref()
+        .map(reference::resolve_book)
+        .transpose()
+        .map_err(|e| AppError::InvalidInput(format!("Invalid book filter: {}", e)))?;
+    let new_testament = match params
+        .testament
+        .as_deref()
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        None => None,
+        Some("ot" | "old") => Some(false),
+        Some("nt" | "new") => Some(true),
+        Some(other) => {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid testament filter: {} (expected \"ot\" or \"nt\")",
+                other
+            )))
+        }
+    };
+    let filter = SearchFilter {
+        book: book.map(str::to_string),
+        new_testament,
+    };
+
+    let index = state
+        .search
+        .get_or_build(store, translation.as_str())
+        .await?;
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+    let results = index.search(&params.q, &filter, params.page.unwrap_or(1), per_page)?;
+    Ok(Json(results))
 }
 
 // Compatibility endpoints for frontend
 /// Get translations (alias for compatibility)
 pub async fn get_languages(
-    bible_client: axum::extract::State<HelloAOBibleClient>,
-) -> Result<Json<Vec<crate::api::helloao::Translation>>> {
-    get_translations(bible_client).await
+    state: axum::extract::State<AppState>,
+) -> Result<ServedJson<Vec<Translation>>> {
+    get_translations(state).await
 }
 
 /// Get translations as "bibles" for compatibility
 pub async fn get_bibles(
-    bible_client: axum::extract::State<HelloAOBibleClient>,
-) -> Result<Json<Vec<crate::api::helloao::Translation>>> {
-    get_translations(bible_client).await
+    state: axum::extract::State<AppState>,
+) -> Result<ServedJson<Vec<Translation>>> {
+    get_translations(state).await
 }
 
 /// Get list of available commentaries
 pub async fn get_commentaries(
-    bible_client: axum::extract::State<HelloAOBibleClient>,
+    state: axum::extract::State<AppState>,
 ) -> Result<Json<Vec<crate::api::helloao::Commentary>>> {
-    let commentaries = bible_client.get_commentaries().await?;
+    let commentaries = state.helloao.get_commentaries().await?;
     Ok(Json(commentaries))
 }
 
 /// Get commentary for a specific chapter
 pub async fn get_commentary(
-    bible_client: axum::extract::State<HelloAOBibleClient>,
+    state: axum::extract::State<AppState>,
     Path((commentary_id, book, chapter)): Path<(String, String, String)>,
 ) -> Result<Json<crate::api::helloao::CommentaryChapter>> {
-    let chapter_num: u32 = chapter.parse().map_err(|_| {
-        AppError::BibleBrainApi("Invalid chapter number".to_string())
-    })?;
-    
-    let commentary_data = bible_client.get_commentary(&commentary_id, &book, chapter_num).await?;
+    let chapter_num: u32 = chapter
+        .parse()
+        .map_err(|_| AppError::InvalidInput("Invalid chapter number".to_string()))?;
+    let book = BookId::parse(&book)?;
+    let commentary_id = state.commentary_id(&commentary_id).await?;
+
+    let commentary_data = state
+        .get_commentary(commentary_id.as_str(), book.as_str(), chapter_num)
+        .await?;
     Ok(Json(commentary_data))
 }
+
+#[derive(Serialize)]
+pub struct UpstreamStats {
+    pub helloao: CoalescingStats,
+    pub bible_brain: Option<CoalescingStats>,
+}
+
+/// Get request-coalescing counters for each upstream client
+pub async fn get_upstream_stats(state: axum::extract::State<AppState>) -> Json<UpstreamStats> {
+    Json(UpstreamStats {
+        helloao: state.helloao.coalescing_stats(),
+        bible_brain: state.brain.as_ref().map(|brain| brain.coalescing_stats()),
+    })
+}
+
+/// Get hit/miss counters for the HelloAO response cache
+pub async fn get_cache_stats(state: axum::extract::State<AppState>) -> Json<CacheStats> {
+    Json(state.helloao.cache_stats())
+}
+
+/// Get a commentary's introduction to a whole book
+pub async fn get_commentary_book_introduction(
+    state: axum::extract::State<AppState>,
+    Path((commentary_id, book)): Path<(String, String)>,
+) -> Result<Json<crate::api::helloao::CommentaryBookIntroduction>> {
+    let book = BookId::parse(&book)?;
+    let commentary_id = state.commentary_id(&commentary_id).await?;
+    let introduction = state
+        .helloao
+        .get_commentary_book_introduction(commentary_id.as_str(), book.as_str())
+        .await?;
+    Ok(Json(introduction))
+}
+
+/// Get the books covered by a commentary
+pub async fn get_commentary_books(
+    state: axum::extract::State<AppState>,
+    Path(commentary_id): Path<String>,
+) -> Result<Json<Vec<crate::api::helloao::CommentaryBook>>> {
+    let commentary_id = state.commentary_id(&commentary_id).await?;
+    let books = state
+        .helloao
+        .get_commentary_books(commentary_id.as_str())
+        .await?;
+    Ok(Json(books))
+}
+
+/// Get the topical and person profiles of a commentary
+pub async fn get_commentary_profiles(
+    state: axum::extract::State<AppState>,
+    Path(commentary_id): Path<String>,
+) -> Result<Json<Vec<crate::api::helloao::CommentaryProfile>>> {
+    let commentary_id = state.commentary_id(&commentary_id).await?;
+    let profiles = state
+        .helloao
+        .get_commentary_profiles(commentary_id.as_str())
+        .await?;
+    Ok(Json(profiles))
+}
+
+/// Get a single commentary profile article
+pub async fn get_commentary_profile(
+    state: axum::extract::State<AppState>,
+    Path((commentary_id, profile_id)): Path<(String, String)>,
+) -> Result<Json<crate::api::helloao::CommentaryProfileArticle>> {
+    let profile_id = ids::segment("profile", &profile_id)?;
+    let commentary_id = state.commentary_id(&commentary_id).await?;
+    let profile = state
+        .helloao
+        .get_commentary_profile(commentary_id.as_str(), profile_id)
+        .await?;
+    Ok(Json(profile))
+}
+
+/// Get list of available datasets
+pub async fn get_datasets(
+    state: axum::extract::State<AppState>,
+) -> Result<Json<Vec<crate::api::helloao::Dataset>>> {
+    let datasets = state.helloao.get_datasets().await?;
+    Ok(Json(datasets))
+}
+
+/// Get the books covered by a dataset
+pub async fn get_dataset_books(
+    state: axum::extract::State<AppState>,
+    Path(dataset_id): Path<String>,
+) -> Result<Json<Vec<crate::api::helloao::DatasetBook>>> {
+    let books = state
+        .helloao
+        .get_dataset_books(ids::segment("dataset", &dataset_id)?)
+        .await?;
+    Ok(Json(books))
+}
+
+/// Get a dataset's entries for a specific chapter
+pub async fn get_dataset_chapter(
+    state: axum::extract::State<AppState>,
+    Path((dataset_id, book, chapter)): Path<(String, String, String)>,
+) -> Result<Json<crate::api::helloao::DatasetChapter>> {
+    let chapter_num: u32 = chapter
+        .parse()
+        .map_err(|_| AppError::InvalidInput("Invalid chapter number".to_string()))?;
+
+    let dataset_id = ids::segment("dataset", &dataset_id)?;
+    let book = BookId::parse(&book)?;
+
+    let chapter_data = state
+        .helloao
+        .get_dataset_chapter(dataset_id, book.as_str(), chapter_num)
+        .await?;
+    Ok(Json(chapter_data))
+}
+
+/// The Bible Brain client, or an error explaining how to enable it
+fn brain(state: &AppState) -> Result<&BibleBrainClient> {
+    state.brain.as_ref().ok_or_else(|| {
+        AppError::NotConfigured(
+            "Bible Brain is not configured; set BIBLE_BRAIN_API_KEY".to_string(),
+        )
+    })
+}
+
+#[derive(Deserialize)]
+pub struct PageParams {
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+fn page_request(page: Option<u32>, limit: Option<u32>) -> PageRequest {
+    let default = PageRequest::default();
+    PageRequest {
+        page: page.unwrap_or(default.page).max(1),
+        limit: limit.unwrap_or(default.limit).clamp(1, 500),
+    }
+}
+
+/// Get one page of Bible Brain languages
+pub async fn get_brain_languages(
+    state: axum::extract::State<AppState>,
+    Query(params): Query<PageParams>,
+) -> Result<Json<Page<Language>>> {
+    let languages = brain(&state)?
+        .get_languages(page_request(params.page, params.limit))
+        .await?;
+    Ok(Json(languages))
+}
+
+#[derive(Deserialize)]
+pub struct BrainBiblesParams {
+    pub language_code: Option<String>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+/// Get one page of Bible Brain Bibles, optionally for a single language
+pub async fn get_brain_bibles(
+    state: axum::extract::State<AppState>,
+    Query(params): Query<BrainBiblesParams>,
+) -> Result<Json<Page<Bible>>> {
+    let bibles = brain(&state)?
+        .get_bibles(
+            params.language_code.as_deref(),
+            page_request(params.page, params.limit),
+        )
+        .await?;
+    Ok(Json(bibles))
+}
+
+/// Get books for a Bible Brain Bible
+pub async fn get_brain_books(
+    state: axum::extract::State<AppState>,
+    Path(bible_id): Path<String>,
+) -> Result<Json<Vec<BrainBook>>> {
+    let books = brain(&state)?
+        .get_books(ids::segment("Bible", &bible_id)?)
+        .await?;
+    Ok(Json(books))
+}
+
+#[derive(Deserialize)]
+pub struct FilesetParams {
+    /// Fileset type prefix, e.g. "audio" also matches "audio_drama"
+    #[serde(rename = "type")]
+    pub fileset_type: Option<String>,
+}
+
+/// Get the filesets published for a Bible Brain Bible
+pub async fn get_brain_filesets(
+    state: axum::extract::State<AppState>,
+    Path(bible_id): Path<String>,
+    Query(params): Query<FilesetParams>,
+) -> Result<Json<Vec<Fileset>>> {
+    let mut filesets = brain(&state)?
+        .get_filesets(ids::segment("Bible", &bible_id)?)
+        .await?;
+    if let Some(fileset_type) = &params.fileset_type {
+        filesets.retain(|fileset| fileset.fileset_type.starts_with(fileset_type.as_str()));
+    }
+    Ok(Json(filesets))
+}
+
+/// Get a chapter's audio URL and verse timestamps from an audio fileset
+pub async fn get_brain_audio_chapter(
+    state: axum::extract::State<AppState>,
+    Path((fileset_id, book, chapter)): Path<(String, String, String)>,
+) -> Result<Json<AudioChapter>> {
+    let chapter_num: u32 = chapter
+        .parse()
+        .map_err(|_| AppError::InvalidInput("Invalid chapter number".to_string()))?;
+
+    let fileset_id = ids::segment("fileset", &fileset_id)?;
+    let book = BookId::parse(&book)?;
+
+    let audio = brain(&state)?
+        .get_audio_chapter(fileset_id, book.as_str(), chapter_num)
+        .await?;
+    Ok(Json(audio))
+}
+
+#[derive(Deserialize)]
+pub struct VideoParams {
+    /// ISO 639-3 language code, e.g. "eng"
+    pub language: Option<String>,
+    /// e.g. "720p" or "1280x720"
+    pub resolution: Option<String>,
+}
+
+impl VideoParams {
+    fn filter(self) -> VideoFilter {
+        VideoFilter {
+            language: self.language,
+            resolution: self.resolution,
+        }
+    }
+}
+
+/// Get all Bible Brain videos
+pub async fn get_brain_videos(
+    state: axum::extract::State<AppState>,
+    Query(params): Query<VideoParams>,
+) -> Result<Json<Vec<Video>>> {
+    let videos = brain(&state)?.get_videos().await?;
+    Ok(Json(params.filter().apply(&videos)))
+}
+
+/// Get the videos for a Bible
+pub async fn get_brain_bible_videos(
+    state: axum::extract::State<AppState>,
+    Path(bible_id): Path<String>,
+    Query(params): Query<VideoParams>,
+) -> Result<Json<Vec<Video>>> {
+    let bible_id = ids::segment("Bible", &bible_id)?;
+    let videos = brain(&state)?.get_bible_videos(bible_id).await?;
+    Ok(Json(params.filter().apply(&videos)))
+}
+
+/// Get the videos for a book of a Bible
+pub async fn get_brain_book_videos(
+    state: axum::extract::State<AppState>,
+    Path((bible_id, book)): Path<(String, String)>,
+    Query(params): Query<VideoParams>,
+) -> Result<Json<Vec<Video>>> {
+    let bible_id = ids::segment("Bible", &bible_id)?;
+    let book = BookId::parse(&book)?;
+    let videos = brain(&state)?
+        .get_book_videos(bible_id, book.as_str())
+        .await?;
+    Ok(Json(params.filter().apply(&videos)))
+}
+
+/// Get the videos for a chapter of a Bible
+pub async fn get_brain_chapter_videos(
+    state: axum::extract::State<AppState>,
+    Path((bible_id, book, chapter)): Path<(String, String, String)>,
+    Query(params): Query<VideoParams>,
+) -> Result<Json<Vec<Video>>> {
+    let chapter_num: u32 = chapter
+        .parse()
+        .map_err(|_| AppError::InvalidInput("Invalid chapter number".to_string()))?;
+    let bible_id = ids::segment("Bible", &bible_id)?;
+    let book = BookId::parse(&book)?;
+    let videos = brain(&state)?
+        .get_chapter_videos(bible_id, book.as_str(), chapter_num)
+        .await?;
+    Ok(Json(params.filter().apply(&videos)))
+}
+
+/// Get a single video with a freshly signed link
+pub async fn get_brain_video(
+    state: axum::extract::State<AppState>,
+    Path((bible_id, video_id)): Path<(String, String)>,
+) -> Result<Json<Video>> {
+    let bible_id = ids::segment("Bible", &bible_id)?;
+    let video_id = ids::segment("video", &video_id)?;
+    let video = brain(&state)?.get_video(bible_id, video_id).await?;
+    Ok(Json(video))
+}
+
+#[derive(Deserialize)]
+pub struct AudioSyncParams {
+    /// Bible Brain audio fileset to play, e.g. "ENGESVN2DA"
+    pub fileset: String,
+}
+
+/// Get a chapter's audio with per-verse timings merged into its text
+pub async fn get_synced_audio(
+    state: axum::extract::State<AppState>,
+    Path((translation, book, chapter)): Path<(String, String, String)>,
+    Query(params): Query<AudioSyncParams>,
+) -> Result<Json<SyncedChapter>> {
+    let chapter_num: u32 = chapter
+        .parse()
+        .map_err(|_| AppError::InvalidInput("Invalid chapter number".to_string()))?;
+
+    let fileset = ids::segment("fileset", &params.fileset)?;
+    let book = BookId::parse(&book)?;
+    let brain = brain(&state)?;
+    let translation = state.translation_id(&translation).await?;
+
+    let synced = audio::fetch_synced_chapter(
+        state.provider.as_ref(),
+        brain,
+        translation.as_str(),
+        fileset,
+        book.as_str(),
+        chapter_num,
+    )
+    .await?;
+    Ok(Json(synced))
+}
@@ -0,0 +1,314 @@
+//! Validated identifiers for path segments that end up in upstream URLs
+//! and store paths.
+//!
+//! Parsing only checks syntax and static catalogs, so malformed IDs are
+//! rejected with `invalid_input` before any network call. Checking a
+//! well-formed ID against the upstream catalogs is `KnownIds`' job.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::books;
+use crate::error::{AppError, Result};
+
+/// Longest ID accepted in a path segment
+const MAX_ID_LEN: usize = 64;
+
+/// Check that `raw` is safe to use as one URL or path segment: ASCII
+/// letters, digits, `_` and `-`, starting with a letter or digit. This
+/// rules out `..`, `/`, `?`, `%` and anything else that could change
+/// which upstream resource is addressed.
+pub fn segment<'a>(kind: &str, raw: &'a str) -> Result<&'a str> {
+    let valid = !raw.is_empty()
+        && raw.len() <= MAX_ID_LEN
+        && raw.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && raw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if valid {
+        Ok(raw)
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Invalid {} ID: {:?} (expected up to {} letters, digits, '_' or '-')",
+            kind,
+            raw.chars().take(MAX_ID_LEN).collect::<String>(),
+            MAX_ID_LEN
+        )))
+    }
+}
+
+macro_rules! id_type {
+    ($(#[$doc:meta])* $name:ident, $kind:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            /// What the ID names, for error messages
+            pub const KIND: &'static str = $kind;
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = AppError;
+
+            fn from_str(raw: &str) -> Result<Self> {
+                Self::parse(raw)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// A translation ID such as `BSB` or `eng_kjv`
+    TranslationId,
+    "translation"
+);
+
+id_type!(
+    /// A commentary ID such as `matthew-henry`
+    CommentaryId,
+    "commentary"
+);
+
+id_type!(
+    /// A USFM book code such as `GEN` or `1CO`
+    BookId,
+    "book"
+);
+
+impl TranslationId {
+    pub fn parse(raw: &str) -> Result<Self> {
+        segment(Self::KIND, raw).map(|id| Self(id.to_string()))
+    }
+}
+
+impl CommentaryId {
+    pub fn parse(raw: &str) -> Result<Self> {
+        segment(Self::KIND, raw).map(|id| Self(id.to_string()))
+    }
+}
+
+impl BookId {
+    /// Resolve a book code, name or abbreviation through the book catalog:
+    /// `john`, `Jn`, `JHN`, `Johannes` and `Jean` all become `JHN`. Only
+    /// catalog codes come out, so the raw value never reaches a URL.
+    pub fn parse(raw: &str) -> Result<Self> {
+        if raw.len() > MAX_ID_LEN {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid book: longer than {} characters",
+                MAX_ID_LEN
+            )));
+        }
+        books::resolve(raw)
+            .map(|book| Self(book.usfm.to_string()))
+            .map_err(|e| AppError::InvalidInput(e.to_string()))
+    }
+}
+
+/// One catalog entry: an ID and, for translations, its language
+#[derive(Debug, Clone)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub language: Option<String>,
+}
+
+impl From<String> for CatalogEntry {
+    fn from(id: String) -> Self {
+        Self { id, language: None }
+    }
+}
+
+/// A catalog's entries as of the last time it was fetched
+struct Snapshot {
+    languages: Arc<HashMap<String, Option<String>>>,
+    fetched_at: Instant,
+}
+
+/// The last successful fetch of a catalog, and when a fetch last failed
+#[derive(Default)]
+struct Catalog {
+    snapshot: Option<Snapshot>,
+    failed_at: Option<Instant>,
+}
+
+/// Recently seen translation and commentary catalogs, used to turn
+/// well-formed but unknown IDs into `not_found` without a chapter fetch.
+///
+/// The check fails open: when a catalog cannot be fetched, every
+/// well-formed ID is let through and the providers decide. A failed fetch
+/// is not retried for `failure_ttl`, so an upstream outage doesn't cost
+/// every request a catalog fetch on top of its own.
+pub struct KnownIds {
+    translations: Mutex<Catalog>,
+    commentaries: Mutex<Catalog>,
+    ttl: Duration,
+    failure_ttl: Duration,
+}
+
+impl Default for KnownIds {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(3600))
+    }
+}
+
+impl KnownIds {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            translations: Mutex::default(),
+            commentaries: Mutex::default(),
+            ttl,
+            failure_ttl: Duration::from_secs(30).min(ttl),
+        }
+    }
+
+    /// How long after a failed catalog fetch IDs go unchecked before the
+    /// catalog is fetched again
+    pub fn with_failure_ttl(mut self, failure_ttl: Duration) -> Self {
+        self.failure_ttl = failure_ttl;
+        self
+    }
+
+    /// Check a translation against the catalog returned by `fetch`
+    pub async fn check_translation<F, Fut, E>(&self, id: &TranslationId, fetch: F) -> Result<()>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<E>>>,
+        E: Into<CatalogEntry>,
+    {
+        self.check(&self.translations, TranslationId::KIND, id.as_str(), fetch)
+            .await
+    }
+
+    /// Check a commentary against the catalog returned by `fetch`
+    pub async fn check_commentary<F, Fut>(&self, id: &CommentaryId, fetch: F) -> Result<()>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<String>>>,
+    {
+        self.check(&self.commentaries, CommentaryId::KIND, id.as_str(), fetch)
+            .await
+    }
+
+    /// The language of a translation per the catalog returned by `fetch`;
+    /// `None` when the catalog is unavailable or has no language for it
+    pub async fn translation_language<F, Fut, E>(
+        &self,
+        id: &TranslationId,
+        fetch: F,
+    ) -> Option<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<E>>>,
+        E: Into<CatalogEntry>,
+    {
+        let languages = self
+            .snapshot(&self.translations, TranslationId::KIND, fetch)
+            .await?;
+        languages.get(id.as_str()).cloned().flatten()
+    }
+
+    /// The cached catalog, refetched once it is older than the TTL; `None`
+    /// while the catalog cannot be fetched
+    async fn snapshot<F, Fut, E>(
+        &self,
+        catalog: &Mutex<Catalog>,
+        kind: &str,
+        fetch: F,
+    ) -> Option<Arc<HashMap<String, Option<String>>>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<E>>>,
+        E: Into<CatalogEntry>,
+    {
+        {
+            let catalog = catalog.lock().unwrap();
+            let cached = catalog
+                .snapshot
+                .as_ref()
+                .filter(|s| s.fetched_at.elapsed() < self.ttl)
+                .map(|s| s.languages.clone());
+            if cached.is_some() {
+                return cached;
+            }
+            if catalog
+                .failed_at
+                .is_some_and(|failed_at| failed_at.elapsed() < self.failure_ttl)
+            {
+                return None;
+            }
+        }
+
+        match fetch().await {
+            Ok(entries) => {
+                let languages: Arc<HashMap<String, Option<String>>> = Arc::new(
+                    entries
+                        .into_iter()
+                        .map(|entry| {
+                            let entry = entry.into();
+                            (entry.id, entry.language)
+                        })
+                        .collect(),
+                );
+                *catalog.lock().unwrap() = Catalog {
+                    snapshot: Some(Snapshot {
+                        languages: languages.clone(),
+                        fetched_at: Instant::now(),
+                    }),
+                    failed_at: None,
+                };
+                Some(languages)
+            }
+            Err(e) => {
+                tracing::debug!("{} catalog unavailable: {}", kind, e);
+                catalog.lock().unwrap().failed_at = Some(Instant::now());
+                None
+            }
+        }
+    }
+
+    async fn check<F, Fut, E>(
+        &self,
+        catalog: &Mutex<Catalog>,
+        kind: &str,
+        id: &str,
+        fetch: F,
+    ) -> Result<()>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<E>>>,
+        E: Into<CatalogEntry>,
+    {
+        let Some(known) = self.snapshot(catalog, kind, fetch).await else {
+            tracing::debug!("Not checking {} {} without a catalog", kind, id);
+            return Ok(());
+        };
+
+        if known.contains_key(id) {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Unknown {}: {}", kind, id)))
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Strong's lexicon loaded from local files.
+//!
+//! The expected format is the Open Scriptures Strong's dictionaries
+//! (`strongs-hebrew-dictionary.js`, `strongs-greek-dictionary.js`): a JSON
+//! object keyed by Strong's number, optionally wrapped in a JavaScript
+//! `var ... = {...};` assignment. Each entry may have `lemma`, `xlit`,
+//! `pron`, `derivation`, `strongs_def` and `kjv_def`; a `gloss` field, when
+//! present, overrides the gloss derived from `kjv_def`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use crate::error::{AppError, Result};
+
+/// Normalize a Strong's number: `h07225`, `H7225` and `H7225a` are all
+/// `H7225`. Returns `None` unless it is `H` or `G` followed by digits.
+pub fn normalize_strongs(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let mut chars = raw.chars();
+    let prefix = chars.next()?.to_ascii_uppercase();
+    if prefix != 'H' && prefix != 'G' {
+        return None;
+    }
+    let rest = chars.as_str();
+    let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    let suffix = &rest[digits.len()..];
+    if digits.is_empty() || digits.len() > 5 || suffix.len() > 1 {
+        return None;
+    }
+    if !suffix.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let number: u32 = digits.parse().ok()?;
+    (number > 0).then(|| format!("{}{}", prefix, number))
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct RawEntry {
+    #[serde(default)]
+    lemma: Option<String>,
+    #[serde(default)]
+    xlit: Option<String>,
+    #[serde(default)]
+    translit: Option<String>,
+    #[serde(default)]
+    pron: Option<String>,
+    #[serde(default)]
+    derivation: Option<String>,
+    #[serde(default)]
+    strongs_def: Option<String>,
+    #[serde(default)]
+    kjv_def: Option<String>,
+    #[serde(default)]
+    gloss: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LexiconEntry {
+    /// Normalized Strong's number, e.g. "H7225"
+    pub strongs: String,
+    pub lemma: Option<String>,
+    pub transliteration: Option<String>,
+    pub pronunciation: Option<String>,
+    pub derivation: Option<String>,
+    pub definition: Option<String>,
+    /// How the King James Version translates the word
+    pub kjv_usage: Option<String>,
+    /// A short English gloss for interlinear display
+    pub gloss: Option<String>,
+}
+
+/// First rendering in a KJV usage list, skipping those marked `X` or `+`
+/// (idioms and untranslated particles): ":--behold, lo." -> "behold"
+fn gloss_from_usage(usage: &str) -> Option<String> {
+    let usage = usage.trim().trim_start_matches(":--");
+    let renderings: Vec<&str> = usage
+        .split([',', ';'])
+        .map(|rendering| rendering.trim().trim_end_matches('.').trim())
+        .filter(|rendering| !rendering.is_empty())
+        .collect();
+    let marked = |rendering: &&str| rendering.starts_with("X ") || rendering.starts_with("+ ");
+    renderings
+        .iter()
+        .find(|rendering| !marked(rendering))
+        .or_else(|| renderings.first())
+        .map(|rendering| rendering.trim_start_matches(['X', '+']).trim().to_string())
+}
+
+impl LexiconEntry {
+    fn from_raw(strongs: String, raw: RawEntry) -> Self {
+        let trimmed = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let gloss =
+            trimmed(raw.gloss).or_else(|| raw.kjv_def.as_deref().and_then(gloss_from_usage));
+        Self {
+            strongs,
+            lemma: trimmed(raw.lemma),
+            transliteration: trimmed(raw.xlit.or(raw.translit)),
+            pronunciation: trimmed(raw.pron),
+            derivation: trimmed(raw.derivation),
+            definition: trimmed(raw.strongs_def),
+            kjv_usage: trimmed(raw.kjv_def),
+            gloss,
+        }
+    }
+}
+
+/// Lexicon entries by Strong's number, and Greek numbers by lemma
+#[derive(Debug, Default)]
+pub struct LexiconIndex {
+    entries: HashMap<String, LexiconEntry>,
+    by_lemma: HashMap<String, String>,
+}
+
+impl LexiconIndex {
+    /// Add the entries of one dictionary file; `source` names it in errors
+    pub fn load(&mut self, text: &str, source: &str) -> Result<()> {
+        // Strip a `var name = {...};` wrapper
+        let (offset, json) = match (text.find('{'), text.rfind('}')) {
+            (Some(start), Some(end)) if start < end => (start, &text[start..=end]),
+            _ => (0, text),
+        };
+        let raw: HashMap<String, RawEntry> = serde_json::from_str(json).map_err(|e| {
+            AppError::Data(format!(
+                "{}:{}: invalid lexicon JSON: {}",
+                source,
+                text[..offset].matches('\n').count() + e.line(),
+                e
+            ))
+        })?;
+
+        for (key, entry) in raw {
+            let Some(strongs) = normalize_strongs(&key) else {
+                return Err(AppError::Data(format!(
+                    "{}: invalid Strong's number {:?}",
+                    source, key
+                )));
+            };
+            let entry = LexiconEntry::from_raw(strongs.clone(), entry);
+            // Where two Greek entries share a lemma, the lower number wins
+            if let (true, Some(lemma)) = (strongs.starts_with('G'), &entry.lemma) {
+                let number = |id: &str| id[1..].parse::<u32>().unwrap_or(u32::MAX);
+                self.by_lemma
+                    .entry(lemma.clone())
+                    .and_modify(|existing| {
+                        if number(&strongs) < number(existing) {
+                            *existing = strongs.clone();
+                        }
+                    })
+                    .or_insert_with(|| strongs.clone());
+            }
+            self.entries.insert(strongs, entry);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, strongs: &str) -> Option<&LexiconEntry> {
+        self.entries.get(&normalize_strongs(strongs)?)
+    }
+
+    /// The Strong's number of a Greek lemma such as "λόγος"
+    pub fn greek_strongs(&self, lemma: &str) -> Option<&str> {
+        self.by_lemma.get(lemma).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The lexicon, loaded from its files on first use
+pub struct Lexicon {
+    paths: Vec<String>,
+    index: OnceCell<Arc<LexiconIndex>>,
+}
+
+impl Lexicon {
+    /// `paths` is a comma-separated list of dictionary files; empty disables
+    /// the lexicon
+    pub fn new(paths: &str) -> Self {
+        Self {
+            paths: paths
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(str::to_string)
+                .collect(),
+            index: OnceCell::new(),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.paths.is_empty()
+    }
+
+    pub async fn get(&self) -> Result<Arc<LexiconIndex>> {
+        if self.paths.is_empty() {
+            return Err(AppError::NotConfigured(
+                "The lexicon requires dictionary files (LEXICON_PATH)".to_string(),
+            ));
+        }
+
+        self.index
+            .get_or_try_init(|| async {
+                let mut index = LexiconIndex::default();
+                for path in &self.paths {
+                    let text = tokio::fs::read_to_string(path).await.map_err(|e| {
+                        AppError::Data(format!("Cannot read lexicon {}: {}", path, e))
+                    })?;
+                    index.load(&text, path)?;
+                }
+                tracing::info!("Loaded {} lexicon entries", index.len());
+                Ok(Arc::new(index))
+            })
+            .await
+            .cloned()
+    }
+}
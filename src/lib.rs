@@ -1,8 +1,23 @@
 pub mod api;
+pub mod audio;
+pub mod books;
+pub mod canons;
 pub mod config;
+pub mod crossrefs;
 pub mod error;
 pub mod handlers;
+pub mod ids;
+pub mod lexicon;
+pub mod original;
+pub mod parallel;
+pub mod passage;
+pub mod problem;
+pub mod reference;
+pub mod search;
+pub mod state;
+pub mod store;
+pub mod versification;
 
 pub use config::Config;
 pub use error::{AppError, Result};
-
+pub use state::AppState;
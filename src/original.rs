@@ -0,0 +1,436 @@
+//! Morphologically tagged Hebrew and Greek texts loaded from local files,
+//! and the interlinear view built from them.
+//!
+//! The Old Testament comes from the Open Scriptures Hebrew Bible (OSHB):
+//! one OSIS XML file per book, named by OSIS code (`Gen.xml`, `1Sam.xml`),
+//! numbered in the Hebrew versification. The New Testament comes from
+//! MorphGNT (SBLGNT): one text file per book, named by its MorphGNT number
+//! (`61-Mt-morphgnt.txt` for Matthew), numbered like English Bibles.
+//! MorphGNT has no Strong's numbers, so Greek words get theirs from the
+//! lexicon by lemma.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::books::{self, Testament};
+use crate::error::{AppError, Result};
+use crate::lexicon::LexiconIndex;
+use crate::passage;
+use crate::reference::{self, ScriptureRange, VersePoint};
+use crate::versification::{self, Scheme};
+
+/// Most verses one interlinear request may return
+pub const MAX_VERSES: usize = 500;
+
+/// A word as tagged in the source text
+#[derive(Debug, Clone)]
+pub struct TaggedWord {
+    pub text: String,
+    /// Lemma as given by the source: a Strong's-based lemma in OSHB
+    /// (`b/7225`), the dictionary form in MorphGNT (`λόγος`)
+    pub lemma: String,
+    /// Strong's number, when the source has it
+    pub strongs: Option<String>,
+    pub morph: String,
+}
+
+/// One book of a tagged text, in the source's own verse numbering
+#[derive(Debug)]
+pub struct TaggedBook {
+    pub book: String,
+    /// ISO 639-3 code: "hbo" or "grc"
+    pub language: &'static str,
+    pub scheme: Scheme,
+    pub verses: BTreeMap<(u32, u32), Vec<TaggedWord>>,
+}
+
+impl TaggedBook {
+    fn chapters(&self) -> Vec<u32> {
+        let mut chapters: Vec<u32> = self.verses.keys().map(|(chapter, _)| *chapter).collect();
+        chapters.dedup();
+        chapters
+    }
+}
+
+/// The Strong's number in an OSHB lemma: "c/d/776" -> "H776", "1254 a" -> "H1254"
+fn oshb_strongs(lemma: &str) -> Option<String> {
+    lemma.split('/').find_map(|part| {
+        let digits: String = part
+            .trim()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        let number: u32 = digits.parse().ok()?;
+        Some(format!("H{}", number))
+    })
+}
+
+/// Parse an OSHB book; `source` names the file in errors
+pub fn parse_oshb(text: &str, book: &str, source: &str) -> Result<TaggedBook> {
+    let document = roxmltree::Document::parse(text)
+        .map_err(|e| AppError::Data(format!("{}:{}: {}", source, e.pos().row, e)))?;
+    let line = |node: roxmltree::Node| document.text_pos_at(node.range().start).row;
+
+    let mut verses = BTreeMap::new();
+    for verse in document
+        .descendants()
+        .filter(|node| node.has_tag_name("verse") && node.has_attribute("osisID"))
+    {
+        let osis_id = verse.attribute("osisID").unwrap_or_default();
+        let numbers: Vec<u32> = osis_id
+            .split('.')
+            .skip(1)
+            .filter_map(|part| part.parse().ok())
+            .collect();
+        let [chapter, number] = numbers[..] else {
+            return Err(AppError::Data(format!(
+                "{}:{}: invalid verse osisID {:?}",
+                source,
+                line(verse),
+                osis_id
+            )));
+        };
+
+        let mut words = Vec::new();
+        collect_words(verse, &mut words);
+        verses.insert((chapter, number), words);
+    }
+
+    if verses.is_empty() {
+        return Err(AppError::Data(format!("{}: no verses found", source)));
+    }
+    Ok(TaggedBook {
+        book: book.to_string(),
+        language: "hbo",
+        scheme: Scheme::Hebrew,
+        verses,
+    })
+}
+
+/// The `<w>` elements under `node`, skipping textual notes and variants
+fn collect_words(node: roxmltree::Node, words: &mut Vec<TaggedWord>) {
+    for child in node.children().filter(roxmltree::Node::is_element) {
+        match child.tag_name().name() {
+            "note" => {}
+            "w" => {
+                let text: String = child
+                    .descendants()
+                    .filter(roxmltree::Node::is_text)
+                    .filter_map(|text| text.text())
+                    .collect();
+                let lemma = child.attribute("lemma").unwrap_or_default().to_string();
+                words.push(TaggedWord {
+                    text: text.replace('/', ""),
+                    strongs: oshb_strongs(&lemma),
+                    lemma,
+                    morph: child.attribute("morph").unwrap_or_default().to_string(),
+                });
+            }
+            _ => collect_words(child, words),
+        }
+    }
+}
+
+/// Parse a MorphGNT book: `BBCCVV POS PARSING TEXT WORD NORMALIZED LEMMA`
+pub fn parse_morphgnt(text: &str, book: &str, source: &str) -> Result<TaggedBook> {
+    let mut verses: BTreeMap<(u32, u32), Vec<TaggedWord>> = BTreeMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let invalid = |what: &str| AppError::Data(format!("{}:{}: {}", source, index + 1, what));
+        let [bcv, pos, parsing, text, _word, _normalized, lemma] = columns[..] else {
+            return Err(invalid("expected 7 space-separated columns"));
+        };
+        if bcv.len() != 6 || !bcv.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid("expected a 6-digit book/chapter/verse"));
+        }
+        let chapter: u32 = bcv[2..4].parse().unwrap_or_default();
+        let verse: u32 = bcv[4..6].parse().unwrap_or_default();
+        if chapter == 0 || verse == 0 {
+            return Err(invalid("chapter and verse start at 1"));
+        }
+
+        verses
+            .entry((chapter, verse))
+            .or_default()
+            .push(TaggedWord {
+                text: text.to_string(),
+                lemma: lemma.to_string(),
+                strongs: None,
+                morph: format!("{} {}", pos, parsing),
+            });
+    }
+
+    if verses.is_empty() {
+        return Err(AppError::Data(format!("{}: no verses found", source)));
+    }
+    Ok(TaggedBook {
+        book: book.to_string(),
+        language: "grc",
+        scheme: Scheme::English,
+        verses,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterlinearWord {
+    pub text: String,
+    pub lemma: String,
+    pub strongs: Option<String>,
+    pub morph: String,
+    pub gloss: Option<String>,
+    pub transliteration: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterlinearVerse {
+    pub book: String,
+    pub chapter: u32,
+    pub verse: u32,
+    /// Where the original text numbers this verse, when that differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub native: Option<VersePoint>,
+    /// "hbo" (Hebrew and Aramaic) or "grc" (Greek)
+    pub language: &'static str,
+    pub words: Vec<InterlinearWord>,
+    /// The verse in the requested translation, if one was requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translation_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Interlinear {
+    pub reference: String,
+    /// Scheme the reference and verse numbers are in
+    pub numbering: Scheme,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translation: Option<String>,
+    pub verses: Vec<InterlinearVerse>,
+}
+
+fn interlinear_word(word: &TaggedWord, lexicon: Option<&LexiconIndex>) -> InterlinearWord {
+    let strongs = word.strongs.clone().or_else(|| {
+        lexicon
+            .and_then(|lexicon| lexicon.greek_strongs(&word.lemma))
+            .map(str::to_string)
+    });
+    let entry = lexicon
+        .zip(strongs.as_deref())
+        .and_then(|(lexicon, id)| lexicon.get(id));
+    InterlinearWord {
+        text: word.text.clone(),
+        // OSHB lemmas are Strong's-based; the lexicon has the Hebrew word
+        lemma: match (word.strongs.is_some(), entry.and_then(|e| e.lemma.as_ref())) {
+            (true, Some(lemma)) => lemma.clone(),
+            _ => word.lemma.clone(),
+        },
+        morph: word.morph.clone(),
+        gloss: entry.and_then(|e| e.gloss.clone()),
+        transliteration: entry.and_then(|e| e.transliteration.clone()),
+        strongs,
+    }
+}
+
+/// Tagged texts, each book loaded from disk on first use
+pub struct OriginalTexts {
+    hebrew: Option<PathBuf>,
+    greek: Option<PathBuf>,
+    books: Mutex<HashMap<String, Arc<OnceCell<Arc<TaggedBook>>>>>,
+}
+
+impl OriginalTexts {
+    /// Empty paths leave that testament unavailable
+    pub fn new(hebrew: &str, greek: &str) -> Self {
+        let dir = |path: &str| (!path.is_empty()).then(|| PathBuf::from(path));
+        Self {
+            hebrew: dir(hebrew),
+            greek: dir(greek),
+            books: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The source file for a book, or why there is none
+    async fn book_file(&self, usfm: &str) -> Result<(PathBuf, bool)> {
+        let book = books::by_code(usfm)
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown book: {}", usfm)))?;
+        let not_configured = |what: &str, var: &str| {
+            AppError::NotConfigured(format!("The {} text requires {}", what, var))
+        };
+
+        match book.testament {
+            Testament::Old => {
+                let dir = self
+                    .hebrew
+                    .as_ref()
+                    .ok_or_else(|| not_configured("Hebrew", "OSHB_PATH"))?;
+                Ok((dir.join(format!("{}.xml", book.osis)), true))
+            }
+            Testament::New => {
+                let dir = self
+                    .greek
+                    .as_ref()
+                    .ok_or_else(|| not_configured("Greek", "MORPHGNT_PATH"))?;
+                let prefix = format!("{}-", 60 + book.order - 39);
+                let mut entries = tokio::fs::read_dir(dir)
+                    .await
+                    .map_err(|e| AppError::Data(format!("Cannot read {}: {}", dir.display(), e)))?;
+                while let Ok(Some(entry)) = entries.next_entry().await {
+                    if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                        return Ok((entry.path(), false));
+                    }
+                }
+                Err(AppError::NotFound(format!(
+                    "No tagged Greek text for {}",
+                    book.name()
+                )))
+            }
+            Testament::Deuterocanon => Err(AppError::NotFound(format!(
+                "No tagged original text for {}",
+                book.name()
+            ))),
+        }
+    }
+
+    pub async fn book(&self, usfm: &str) -> Result<Arc<TaggedBook>> {
+        let cell = self
+            .books
+            .lock()
+            .await
+            .entry(usfm.to_string())
+            .or_default()
+            .clone();
+
+        cell.get_or_try_init(|| async {
+            let (path, hebrew) = self.book_file(usfm).await?;
+            let text = match tokio::fs::read_to_string(&path).await {
+                Ok(text) => text,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(AppError::NotFound(format!(
+                        "No tagged original text for {}",
+                        usfm
+                    )))
+                }
+                Err(e) => {
+                    return Err(AppError::Data(format!(
+                        "Cannot read {}: {}",
+                        path.display(),
+                        e
+                    )))
+                }
+            };
+
+            let book = usfm.to_string();
+            let parsed = tokio::task::spawn_blocking(move || {
+                let source = path.display().to_string();
+                if hebrew {
+                    parse_oshb(&text, &book, &source)
+                } else {
+                    parse_morphgnt(&text, &book, &source)
+                }
+            })
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Tagged text load failed: {}", e)))??;
+            Ok(Arc::new(parsed))
+        })
+        .await
+        .cloned()
+    }
+
+    /// The tagged words of every verse in `reference` (numbered in
+    /// `numbering`), with lexicon glosses when a lexicon is given
+    pub async fn interlinear(
+        &self,
+        reference: &str,
+        numbering: Scheme,
+        lexicon: Option<&LexiconIndex>,
+    ) -> Result<Interlinear> {
+        let ranges = reference::parse_references(reference)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid reference: {}", e)))?;
+
+        let mut verses = Vec::new();
+        for range in &ranges {
+            let book = self.book(&range.book).await?;
+            let mut found = Vec::new();
+            for native in native_chapters(range, &book, numbering)? {
+                let chapter_verses = book.verses.range((native, 0)..=(native, u32::MAX));
+                for (&(chapter, verse), words) in chapter_verses {
+                    let Some((mapped_chapter, mapped_verse)) = versification::map_verse(
+                        book.scheme,
+                        numbering,
+                        &range.book,
+                        chapter,
+                        verse,
+                    ) else {
+                        continue;
+                    };
+                    if !passage::contains(range, mapped_chapter, mapped_verse) {
+                        continue;
+                    }
+                    found.push(InterlinearVerse {
+                        book: range.book.clone(),
+                        chapter: mapped_chapter,
+                        verse: mapped_verse,
+                        native: ((chapter, verse) != (mapped_chapter, mapped_verse))
+                            .then(|| VersePoint::verse(chapter, verse)),
+                        language: book.language,
+                        words: words
+                            .iter()
+                            .map(|word| interlinear_word(word, lexicon))
+                            .collect(),
+                        translation_text: None,
+                    });
+                }
+                if verses.len() + found.len() > MAX_VERSES {
+                    return Err(AppError::InvalidInput(format!(
+                        "Interlinear passages are limited to {} verses",
+                        MAX_VERSES
+                    )));
+                }
+            }
+            found.sort_by_key(|verse| (verse.chapter, verse.verse));
+            verses.extend(found);
+        }
+
+        if verses.is_empty() {
+            return Err(AppError::NotFound(format!(
+                "No tagged verses found for {}",
+                reference
+            )));
+        }
+
+        Ok(Interlinear {
+            reference: ranges
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+            numbering,
+            translation: None,
+            verses,
+        })
+    }
+}
+
+/// Chapters of the source text holding `range`
+fn native_chapters(
+    range: &ScriptureRange,
+    book: &TaggedBook,
+    numbering: Scheme,
+) -> Result<Vec<u32>> {
+    let (Some(start), Some(end)) = (range.start, range.end) else {
+        return Ok(book.chapters());
+    };
+    passage::map_chapter_span(
+        &range.book,
+        start.chapter,
+        end.chapter,
+        numbering,
+        book.scheme,
+    )
+}
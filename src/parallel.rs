@@ -0,0 +1,182 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::api::provider::BibleProvider;
+use crate::error::{AppError, Result};
+use crate::passage::{self, Passage};
+use crate::problem;
+use crate::versification::Scheme;
+
+/// Most translations one parallel request may compare
+pub const MAX_TRANSLATIONS: usize = 10;
+
+/// The same passage in several translations, aligned verse by verse
+#[derive(Debug, Clone, Serialize)]
+pub struct ParallelPassage {
+    /// Canonical form of the requested reference
+    pub reference: String,
+    /// Requested translations, in request order
+    pub translations: Vec<String>,
+    /// Scheme the verses are aligned in
+    pub numbering: Scheme,
+    /// Versification scheme of each translation
+    pub versifications: HashMap<String, Scheme>,
+    /// Translations that could not serve the passage at all, with the reason
+    pub errors: Vec<ParallelError>,
+    pub verses: Vec<ParallelVerse>,
+    /// Providers that served each translation
+    pub served_by: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParallelError {
+    pub translation: String,
+    pub error: String,
+    /// Machine-readable error code, as in error responses
+    pub code: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParallelVerse {
+    pub book: String,
+    pub chapter: u32,
+    pub verse: u32,
+    /// Verse text keyed by translation; `null` where a translation lacks the verse
+    pub texts: HashMap<String, Option<String>>,
+    /// Translations that do not contain this verse
+    pub missing: Vec<String>,
+}
+
+/// Fetch `reference` from every translation concurrently and align the results.
+///
+/// Each translation comes with its versification scheme; verses are mapped
+/// into `numbering` before alignment, so Psalm 23 in the Vulgate lines up
+/// with Psalm 23 in English even though the Vulgate calls it Psalm 22.
+///
+/// A translation that fails entirely is reported in `errors` rather than
+/// failing the request; the request only fails when no translation succeeds.
+pub async fn fetch_parallel(
+    provider: &dyn BibleProvider,
+    translations: &[(String, Scheme)],
+    reference: &str,
+    numbering: Scheme,
+) -> Result<ParallelPassage> {
+    if translations.is_empty() {
+        return Err(AppError::InvalidInput(
+            "At least one translation is required".to_string(),
+        ));
+    }
+    if translations.len() > MAX_TRANSLATIONS {
+        return Err(AppError::InvalidInput(format!(
+            "At most {} translations can be compared at once",
+            MAX_TRANSLATIONS
+        )));
+    }
+
+    let fetched = futures::future::join_all(translations.iter().map(|(translation, scheme)| {
+        passage::fetch_passage(provider, translation, *scheme, reference, numbering)
+    }))
+    .await;
+
+    let mut passages: Vec<(&String, Passage)> = Vec::new();
+    let mut errors = Vec::new();
+    let mut first_error = None;
+    for ((translation, _), result) in translations.iter().zip(fetched) {
+        match result {
+            Ok(passage) => passages.push((translation, passage)),
+            Err(e) => {
+                if e.status().is_server_error() {
+                    tracing::error!("Parallel passage from {} failed: {}", translation, e);
+                }
+                errors.push(ParallelError {
+                    translation: translation.clone(),
+                    error: problem::client_detail(&e),
+                    code: e.code(),
+                });
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    let Some((_, first)) = passages.first() else {
+        return Err(first_error.expect("every translation failed, so there is an error"));
+    };
+    let canonical = first.reference.clone();
+    let ranges = first.ranges.clone();
+
+    // Union of all verses, ordered by the range they fall in, then position
+    let mut order: Vec<(usize, String, u32, u32)> = Vec::new();
+    let mut seen = HashSet::new();
+    for (_, passage) in &passages {
+        for verse in &passage.verses {
+            let range = ranges
+                .iter()
+                .position(|range| {
+                    passage::contains(range, verse.chapter, verse.verse) && range.book == verse.book
+                })
+                .unwrap_or(ranges.len());
+            if seen.insert((verse.book.as_str(), verse.chapter, verse.verse)) {
+                order.push((range, verse.book.clone(), verse.chapter, verse.verse));
+            }
+        }
+    }
+    order.sort_by_key(|(range, _, chapter, verse)| (*range, *chapter, *verse));
+
+    let lookup: Vec<HashMap<(&str, u32, u32), &str>> = passages
+        .iter()
+        .map(|(_, passage)| {
+            passage
+                .verses
+                .iter()
+                .map(|verse| {
+                    (
+                        (verse.book.as_str(), verse.chapter, verse.verse),
+                        verse.text.as_str(),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    let verses = order
+        .into_iter()
+        .map(|(_, book, chapter, verse)| {
+            let mut texts = HashMap::new();
+            let mut missing = Vec::new();
+            for ((translation, _), verses) in passages.iter().zip(&lookup) {
+                let text = verses
+                    .get(&(book.as_str(), chapter, verse))
+                    .map(|text| text.to_string());
+                if text.is_none() {
+                    missing.push((*translation).clone());
+                }
+                texts.insert((*translation).clone(), text);
+            }
+            ParallelVerse {
+                book,
+                chapter,
+                verse,
+                texts,
+                missing,
+            }
+        })
+        .collect();
+
+    let served_by = passages
+        .iter()
+        .map(|(translation, passage)| ((*translation).clone(), passage.served_by.clone()))
+        .collect();
+
+    Ok(ParallelPassage {
+        reference: canonical,
+        translations: translations
+            .iter()
+            .map(|(translation, _)| translation.clone())
+            .collect(),
+        numbering,
+        versifications: translations.iter().cloned().collect(),
+        errors,
+        verses,
+        served_by,
+    })
+}
@@ -0,0 +1,220 @@
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::api::models::Chapter;
+use crate::api::provider::{BibleProvider, Served};
+use crate::error::{AppError, Result};
+use crate::reference::{self, ScriptureRange, VersePoint};
+use crate::versification::{self, Scheme};
+
+/// Upper bound on chapters fetched for one passage, so a reference like
+/// "Gen; Exo; Lev; ..." cannot fan out into hundreds of upstream calls
+const MAX_CHAPTERS: usize = 200;
+
+/// Chapters fetched concurrently while assembling a passage
+const FETCH_CONCURRENCY: usize = 8;
+
+/// The verses covered by a reference, in reference order
+#[derive(Debug, Clone, Serialize)]
+pub struct Passage {
+    /// Canonical form of the requested reference, e.g. "John 3:16-18; Romans 8:28"
+    pub reference: String,
+    pub translation: String,
+    /// Versification scheme of the translation
+    pub versification: Scheme,
+    /// Scheme the reference and the verse numbers below are in
+    pub numbering: Scheme,
+    pub ranges: Vec<ScriptureRange>,
+    pub verses: Vec<PassageVerse>,
+    /// Providers that served the chapters, in first-use order
+    pub served_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PassageVerse {
+    pub book: String,
+    pub chapter: u32,
+    pub verse: u32,
+    pub text: String,
+    /// Where the translation itself numbers this verse, when that differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub native: Option<VersePoint>,
+}
+
+fn too_many_chapters(count: u64) -> AppError {
+    AppError::InvalidInput(format!(
+        "Passage spans {} chapters; the limit is {}",
+        count, MAX_CHAPTERS
+    ))
+}
+
+/// Chapters of a text numbered in `scheme` that hold chapters
+/// `first..=last` of `book` in `numbering`. Spans over `MAX_CHAPTERS` are
+/// rejected before they are expanded.
+pub(crate) fn map_chapter_span(
+    book: &str,
+    first: u32,
+    last: u32,
+    numbering: Scheme,
+    scheme: Scheme,
+) -> Result<Vec<u32>> {
+    let span = u64::from(last.saturating_sub(first)) + 1;
+    if span > MAX_CHAPTERS as u64 {
+        return Err(too_many_chapters(span));
+    }
+    let mut chapters: Vec<u32> = (first..=last)
+        .flat_map(|chapter| versification::chapters(numbering, scheme, book, chapter))
+        .collect();
+    chapters.sort_unstable();
+    chapters.dedup();
+    Ok(chapters)
+}
+
+/// Chapters of the translation a range touches, given the reference's
+/// `numbering` and the translation's `scheme`; whole-book ranges need the
+/// book's chapter count
+fn chapters_for(
+    range: &ScriptureRange,
+    chapter_counts: &HashMap<String, u32>,
+    numbering: Scheme,
+    scheme: Scheme,
+) -> Result<Vec<u32>> {
+    match (range.start, range.end) {
+        (Some(start), Some(end)) => {
+            map_chapter_span(&range.book, start.chapter, end.chapter, numbering, scheme)
+        }
+        _ => {
+            let count = chapter_counts.get(&range.book).copied().ok_or_else(|| {
+                AppError::NotFound(format!("Unknown book for this translation: {}", range.book))
+            })?;
+            if count as usize > MAX_CHAPTERS {
+                return Err(too_many_chapters(count.into()));
+            }
+            Ok((1..=count).collect())
+        }
+    }
+}
+
+/// Whether a verse of `chapter` falls inside `range`
+pub(crate) fn contains(range: &ScriptureRange, chapter: u32, verse: u32) -> bool {
+    let (Some(start), Some(end)) = (range.start, range.end) else {
+        return true;
+    };
+    if chapter < start.chapter || chapter > end.chapter {
+        return false;
+    }
+    let after_start = chapter > start.chapter || start.verse.is_none_or(|first| verse >= first);
+    let before_end = chapter < end.chapter || end.verse.is_none_or(|last| verse <= last);
+    after_start && before_end
+}
+
+/// Parse `reference`, numbered in `numbering`, and assemble its verses from
+/// `translation`, which follows `scheme`. Verses come back renumbered into
+/// `numbering`, so a Hebrew Bible's Malachi 3:23 answers "Mal 4:5".
+pub async fn fetch_passage(
+    provider: &dyn BibleProvider,
+    translation: &str,
+    scheme: Scheme,
+    reference: &str,
+    numbering: Scheme,
+) -> Result<Passage> {
+    let ranges = reference::parse_references(reference)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid reference: {}", e)))?;
+
+    // Only whole-book references need the book list
+    let mut served_by = Vec::new();
+    let mut chapter_counts = HashMap::new();
+    if ranges.iter().any(ScriptureRange::is_whole_book) {
+        let books = provider.serve_books(translation).await?;
+        served_by.push(books.provider);
+        for book in books.value {
+            if let Some(count) = book.number_of_chapters {
+                chapter_counts.insert(book.id, count);
+            }
+        }
+    }
+
+    let mut needed: Vec<(String, u32)> = Vec::new();
+    let mut seen: HashSet<(String, u32)> = HashSet::new();
+    for range in &ranges {
+        for chapter in chapters_for(range, &chapter_counts, numbering, scheme)? {
+            let key = (range.book.clone(), chapter);
+            if seen.insert(key.clone()) {
+                needed.push(key);
+            }
+            if needed.len() > MAX_CHAPTERS {
+                return Err(too_many_chapters(needed.len() as u64));
+            }
+        }
+    }
+
+    let served: Vec<((String, u32), Served<Chapter>)> = stream::iter(needed)
+        .map(|(book, chapter)| async move {
+            let data = provider.serve_chapter(translation, &book, chapter).await?;
+            Ok::<_, AppError>(((book, chapter), data))
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let mut chapters: HashMap<(String, u32), Chapter> = HashMap::new();
+    for (key, data) in served {
+        if !served_by.contains(&data.provider) {
+            served_by.push(data.provider);
+        }
+        chapters.insert(key, data.value);
+    }
+
+    let mut verses = Vec::new();
+    for range in &ranges {
+        let mut found = Vec::new();
+        for chapter in chapters_for(range, &chapter_counts, numbering, scheme)? {
+            let Some(data) = chapters.get(&(range.book.clone(), chapter)) else {
+                continue;
+            };
+            for verse in &data.verses {
+                let Some((mapped_chapter, mapped_verse)) =
+                    versification::map_verse(scheme, numbering, &range.book, chapter, verse.verse)
+                else {
+                    continue;
+                };
+                if !contains(range, mapped_chapter, mapped_verse) {
+                    continue;
+                }
+                found.push(PassageVerse {
+                    book: range.book.clone(),
+                    chapter: mapped_chapter,
+                    verse: mapped_verse,
+                    text: verse.text.clone(),
+                    native: ((chapter, verse.verse) != (mapped_chapter, mapped_verse))
+                        .then(|| VersePoint::verse(chapter, verse.verse)),
+                });
+            }
+        }
+        // Mapped chapters can come back out of order (Malachi 3:19 is 4:1)
+        found.sort_by_key(|verse| (verse.chapter, verse.verse));
+        verses.extend(found);
+    }
+
+    if verses.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "No verses found for {} in {}",
+            reference, translation
+        )));
+    }
+
+    Ok(Passage {
+        reference: ranges
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; "),
+        translation: translation.to_string(),
+        versification: scheme,
+        numbering,
+        ranges,
+        verses,
+        served_by,
+    })
+}
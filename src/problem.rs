@@ -0,0 +1,307 @@
+//! RFC 7807 problem details for error responses.
+//!
+//! `AppError` renders itself as `application/problem+json`. The
+//! `problem_details` middleware then fills in the request-specific
+//! `instance` and `request_id` members, turns axum's plain-text
+//! rejections (a missing query parameter, an unknown route) into problems,
+//! and switches to plain text for clients that prefer it.
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::AppError;
+
+/// Header carrying the request ID, accepted from clients and always returned
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest detail sent to clients; longer upstream messages are cut
+const MAX_DETAIL_CHARS: usize = 300;
+
+/// Largest rejection body read back to become a problem's detail
+const MAX_REJECTION_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    /// URI reference identifying the problem type, e.g. "/problems/not_found"
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    /// Path of the request that failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Machine-readable error code, also the last segment of `type`
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip)]
+    pub retry_after_secs: Option<u64>,
+}
+
+impl Problem {
+    pub fn from_error(error: &AppError) -> Self {
+        let code = error.code();
+        Self {
+            problem_type: format!("/problems/{}", code),
+            title: title(code),
+            status: error.status().as_u16(),
+            detail: client_detail(error),
+            instance: None,
+            code,
+            request_id: None,
+            retry_after_secs: error.retry_after_secs(),
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn with_retry_after(&self, mut response: Response) -> Response {
+        if let Some(secs) = self.retry_after_secs {
+            if let Ok(value) = HeaderValue::from_str(&secs.to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+        }
+        response
+    }
+
+    fn json_response(&self) -> Response {
+        let body = serde_json::to_vec(self).unwrap_or_default();
+        let mut response = (
+            self.status_code(),
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            body,
+        )
+            .into_response();
+        response.extensions_mut().insert(self.clone());
+        self.with_retry_after(response)
+    }
+
+    fn text_response(&self) -> Response {
+        let mut text = format!(
+            "{} {}: {}\ncode: {}\n",
+            self.status, self.title, self.detail, self.code
+        );
+        if let Some(request_id) = &self.request_id {
+            text.push_str(&format!("request id: {}\n", request_id));
+        }
+        let response = (
+            self.status_code(),
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            text,
+        )
+            .into_response();
+        self.with_retry_after(response)
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        self.json_response()
+    }
+}
+
+fn title(code: &str) -> &'static str {
+    match code {
+        "invalid_input" => "Invalid input",
+        "not_found" => "Not found",
+        "not_configured" => "Not configured",
+        "rate_limited" => "Rate limited",
+        "circuit_open" => "Upstream temporarily disabled",
+        "upstream_unavailable" => "Upstream unavailable",
+        "upstream_timeout" => "Upstream timed out",
+        "upstream_schema_changed" => "Upstream response not understood",
+        _ => "Internal server error",
+    }
+}
+
+/// What a client is told about `error`.
+///
+/// Internal failures (bad configuration, unreadable data, bugs) say nothing
+/// about our setup; their original message only goes to the logs. Other
+/// details are sanitized.
+pub fn client_detail(error: &AppError) -> String {
+    if error.status() == StatusCode::INTERNAL_SERVER_ERROR {
+        return "The server could not complete this request; \
+                quote the request ID when reporting it"
+            .to_string();
+    }
+    sanitize(&error.detail())
+}
+
+/// Make an error message safe to show to clients: URLs (which name our
+/// upstreams and their paths) are masked, markup from upstream error
+/// pages is dropped, and the result is kept short.
+pub fn sanitize(detail: &str) -> String {
+    let mut clean = String::with_capacity(detail.len());
+    let mut rest = detail;
+
+    while let Some(start) = find_url(rest) {
+        clean.push_str(&rest[..start]);
+        let url = &rest[start..];
+        let end = url
+            .find(|c: char| c.is_whitespace() || matches!(c, ')' | '"' | '\'' | '>' | ','))
+            .unwrap_or(url.len());
+        clean.push_str("[upstream]");
+        rest = &url[end..];
+    }
+    clean.push_str(rest);
+
+    // Upstream HTML error pages are noise to API clients
+    if let Some(markup) = clean.find('<') {
+        clean.truncate(markup);
+        clean = clean.trim_end_matches([' ', '-', ':']).to_string();
+    }
+
+    let clean = clean.split_whitespace().collect::<Vec<_>>().join(" ");
+    match clean.char_indices().nth(MAX_DETAIL_CHARS) {
+        Some((cut, _)) => format!("{}…", &clean[..cut]),
+        None => clean,
+    }
+}
+
+fn find_url(text: &str) -> Option<usize> {
+    match (text.find("http://"), text.find("https://")) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Whether the client ranks plain text above JSON in its `Accept` header
+fn prefers_plain_text(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+
+    let mut json = 0.0f32;
+    let mut text = 0.0f32;
+    for item in accept.split(',') {
+        let mut parts = item.split(';').map(str::trim);
+        let media = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let quality = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .and_then(|q| q.parse().ok())
+            .unwrap_or(1.0);
+
+        match media.as_str() {
+            "application/problem+json" | "application/json" | "application/*" | "*/*" => {
+                json = json.max(quality)
+            }
+            "text/plain" | "text/*" => text = text.max(quality),
+            _ => {}
+        }
+    }
+    text > json
+}
+
+/// A client-supplied request ID if it is reasonable, otherwise a new one
+fn request_id(headers: &HeaderMap) -> String {
+    let supplied = headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= 64
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+    if let Some(id) = supplied {
+        return id.to_string();
+    }
+
+    static SEED: OnceLock<RandomState> = OnceLock::new();
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut hasher = SEED.get_or_init(RandomState::new).build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    hasher.write_u128(now.as_nanos());
+    let high = hasher.finish();
+    hasher.write_u64(high);
+    format!("{:016x}{:016x}", high, hasher.finish())
+}
+
+/// Turn a plain-text 4xx response, such as axum's rejection of a missing
+/// query parameter or its empty 404 for an unknown route, into a problem
+/// with the same status
+async fn rejection_problem(response: Response) -> Response {
+    let is_text = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_none_or(|value| value.as_bytes().starts_with(b"text/plain"));
+    if !is_text {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let status = parts.status;
+    let text = axum::body::to_bytes(body, MAX_REJECTION_BYTES)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+        .unwrap_or_default();
+    let detail = if text.is_empty() {
+        status.to_string()
+    } else {
+        text
+    };
+
+    let error = if status == StatusCode::NOT_FOUND {
+        AppError::NotFound(detail)
+    } else {
+        AppError::InvalidInput(detail)
+    };
+    let mut problem = Problem::from_error(&error);
+    problem.status = status.as_u16();
+
+    // Keep headers such as `Allow` on a 405
+    let mut response = problem.into_response();
+    for (name, value) in &parts.headers {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            response.headers_mut().insert(name, value.clone());
+        }
+    }
+    response
+}
+
+/// Tag every response with a request ID, and complete problem responses
+/// with the request's path and ID, rendered in the format the client wants
+pub async fn problem_details(request: Request, next: Next) -> Response {
+    let id = request_id(request.headers());
+    let plain_text = prefers_plain_text(request.headers());
+    let path = request.uri().path().to_string();
+
+    let mut response = next.run(request).await;
+    if response.status().is_client_error() && response.extensions().get::<Problem>().is_none() {
+        response = rejection_problem(response).await;
+    }
+
+    if let Some(mut problem) = response.extensions_mut().remove::<Problem>() {
+        problem.instance = Some(path);
+        problem.request_id = Some(id.clone());
+        response = if plain_text {
+            problem.text_response()
+        } else {
+            problem.json_response()
+        };
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -0,0 +1,387 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+use crate::books;
+
+/// Books with a single chapter, where "Jude 3" means verse 3
+const SINGLE_CHAPTER_BOOKS: &[&str] = &["OBA", "PHM", "2JN", "3JN", "JUD"];
+
+/// Why a reference string could not be parsed
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceError {
+    #[error("Reference is empty")]
+    Empty,
+
+    #[error("Unknown book: {0}")]
+    UnknownBook(String),
+
+    #[error("Ambiguous book name: {0}")]
+    AmbiguousBook(String),
+
+    #[error("Reference does not name a book: {0}")]
+    MissingBook(String),
+
+    #[error("Invalid chapter or verse: {0}")]
+    InvalidNumber(String),
+
+    #[error("Invalid range: {0}")]
+    InvalidRange(String),
+}
+
+/// A chapter, optionally narrowed to a single verse.
+///
+/// A `verse` of `None` marks a chapter boundary: the first verse when used
+/// as a range start, the last verse when used as a range end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct VersePoint {
+    pub chapter: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verse: Option<u32>,
+}
+
+impl VersePoint {
+    pub fn chapter(chapter: u32) -> Self {
+        Self {
+            chapter,
+            verse: None,
+        }
+    }
+
+    pub fn verse(chapter: u32, verse: u32) -> Self {
+        Self {
+            chapter,
+            verse: Some(verse),
+        }
+    }
+}
+
+/// A contiguous span of scripture within one book
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ScriptureRange {
+    /// USFM book code, matching `Book.id` from the books endpoint
+    pub book: String,
+    /// First chapter/verse; `None` (together with `end`) for a whole book
+    pub start: Option<VersePoint>,
+    /// Last chapter/verse; `None` (together with `start`) for a whole book
+    pub end: Option<VersePoint>,
+}
+
+impl ScriptureRange {
+    pub fn whole_book(book: &str) -> Self {
+        Self {
+            book: book.to_string(),
+            start: None,
+            end: None,
+        }
+    }
+
+    pub fn new(book: &str, start: VersePoint, end: VersePoint) -> Self {
+        Self {
+            book: book.to_string(),
+            start: Some(start),
+            end: Some(end),
+        }
+    }
+
+    pub fn is_whole_book(&self) -> bool {
+        self.start.is_none()
+    }
+}
+
+impl fmt::Display for ScriptureRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = book_name(&self.book).unwrap_or(&self.book);
+        let (Some(start), Some(end)) = (self.start, self.end) else {
+            return write!(f, "{}", name);
+        };
+
+        write!(f, "{} {}", name, start.chapter)?;
+        match (start.verse, end.verse) {
+            (None, None) if start.chapter == end.chapter => Ok(()),
+            (None, None) => write!(f, "-{}", end.chapter),
+            (Some(first), Some(last)) if start.chapter == end.chapter => {
+                if first == last {
+                    write!(f, ":{}", first)
+                } else {
+                    write!(f, ":{}-{}", first, last)
+                }
+            }
+            (Some(first), Some(last)) => write!(f, ":{}-{}:{}", first, end.chapter, last),
+            (Some(first), None) => write!(f, ":{}-{}", first, end.chapter),
+            (None, Some(last)) => write!(f, "-{}:{}", end.chapter, last),
+        }
+    }
+}
+
+/// English name for a USFM book code
+pub fn book_name(id: &str) -> Option<&'static str> {
+    books::by_code(id).map(|book| book.name())
+}
+
+/// Whether a USFM book code belongs to the New Testament (Matthew onwards)
+pub fn is_new_testament(id: &str) -> Option<bool> {
+    books::by_code(id).map(|book| book.is_new_testament())
+}
+
+/// Resolve a book name, abbreviation or code in any catalog language to a
+/// USFM code
+pub fn resolve_book(name: &str) -> Result<&'static str, ReferenceError> {
+    books::resolve(name).map(|book| book.usfm)
+}
+
+/// Parse a list of references such as `"John 3:16-18; Rom 8:28"`.
+///
+/// Supported forms:
+/// - whole books (`Jude`), chapters (`Ps 23`) and chapter ranges (`Ps 1-3`)
+/// - verses and verse ranges (`John 3:16`, `John 3:16-18`)
+/// - ranges across chapters (`Gen 1:26-2:3`)
+/// - comma lists, which continue the current chapter (`John 3:16, 18`)
+/// - semicolon lists, which continue the current book (`John 3:16; 4:1`)
+pub fn parse_references(input: &str) -> Result<Vec<ScriptureRange>, ReferenceError> {
+    if input.trim().is_empty() {
+        return Err(ReferenceError::Empty);
+    }
+
+    let mut ranges = Vec::new();
+    let mut book: Option<&'static str> = None;
+
+    for group in input.split(';') {
+        // After a semicolon a bare number is a chapter, not a verse
+        let mut chapter: Option<u32> = None;
+
+        for item in group.split(',') {
+            // Tolerate stray separators such as a trailing ";"
+            let item = item.trim();
+            if item.is_empty() {
+                continue;
+            }
+
+            let (name, numbers) = split_book_name(item);
+            if !name.is_empty() {
+                book = Some(resolve_book(name)?);
+                chapter = None;
+            }
+            let book = book.ok_or_else(|| ReferenceError::MissingBook(item.to_string()))?;
+
+            let range = parse_numbers(book, numbers, chapter)?;
+            // A verse reference sets the chapter context for the next comma item
+            chapter = match range.end {
+                Some(VersePoint {
+                    chapter,
+                    verse: Some(_),
+                }) => Some(chapter),
+                _ => None,
+            };
+            ranges.push(range);
+        }
+    }
+
+    if ranges.is_empty() {
+        return Err(ReferenceError::Empty);
+    }
+    Ok(ranges)
+}
+
+/// Split `"1 John 3:16"` into `("1 John", "3:16")`: the book name runs up to
+/// the last letter in the item.
+fn split_book_name(item: &str) -> (&str, &str) {
+    match item.rfind(|c: char| c.is_alphabetic()) {
+        Some(index) => {
+            let split = index + item[index..].chars().next().map_or(1, char::len_utf8);
+            (
+                item[..split].trim(),
+                // An abbreviation's period is part of the name: "Jn. 3:16"
+                item[split..].trim_start_matches('.').trim(),
+            )
+        }
+        None => ("", item),
+    }
+}
+
+fn parse_number(text: &str) -> Result<u32, ReferenceError> {
+    match text.trim().parse::<u32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(ReferenceError::InvalidNumber(text.trim().to_string())),
+    }
+}
+
+/// Parse `"3"` or `"3:16"` (a `.` separator is accepted as well)
+fn parse_point(text: &str) -> Result<(u32, Option<u32>), ReferenceError> {
+    match text.split_once([':', '.']) {
+        Some((chapter, verse)) => Ok((parse_number(chapter)?, Some(parse_number(verse)?))),
+        None => Ok((parse_number(text)?, None)),
+    }
+}
+
+/// Parse the chapter/verse part of a reference for `book`.
+///
+/// `verse_context` holds the chapter of a preceding verse reference in the
+/// same comma list, in which case bare numbers are verses of that chapter.
+fn parse_numbers(
+    book: &'static str,
+    numbers: &str,
+    verse_context: Option<u32>,
+) -> Result<ScriptureRange, ReferenceError> {
+    if numbers.is_empty() {
+        return Ok(ScriptureRange::whole_book(book));
+    }
+
+    let single_chapter = SINGLE_CHAPTER_BOOKS.contains(&book);
+    let (first, second) = match numbers.split_once(['-', '–', '—']) {
+        Some((first, second)) => (first, Some(second)),
+        None => (numbers, None),
+    };
+
+    let start = match (parse_point(first)?, verse_context) {
+        ((chapter, Some(verse)), _) => VersePoint::verse(chapter, verse),
+        ((verse, None), Some(chapter)) => VersePoint::verse(chapter, verse),
+        ((verse, None), None) if single_chapter => VersePoint::verse(1, verse),
+        ((chapter, None), None) => VersePoint::chapter(chapter),
+    };
+
+    let end = match second {
+        None => start,
+        Some(second) => match parse_point(second)? {
+            (chapter, Some(verse)) => VersePoint::verse(chapter, verse),
+            // "3:16-18": the end is a verse in the start chapter
+            (verse, None) if start.verse.is_some() => VersePoint::verse(start.chapter, verse),
+            (chapter, None) => VersePoint::chapter(chapter),
+        },
+    };
+
+    let ordered = match (start.verse, end.verse) {
+        (Some(first), Some(last)) if start.chapter == end.chapter => first <= last,
+        _ => start.chapter < end.chapter || (start.chapter == end.chapter && start.verse.is_none()),
+    };
+    if !ordered {
+        return Err(ReferenceError::InvalidRange(numbers.to_string()));
+    }
+
+    Ok(ScriptureRange::new(book, start, end))
+}
+
+/// A reference spotted inside free text such as commentary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoundReference {
+    /// The reference exactly as written
+    pub text: String,
+    /// Canonical form, e.g. "Genesis 1:1-3"
+    pub reference: String,
+    pub ranges: Vec<ScriptureRange>,
+    /// Byte offsets of `text` within the searched string
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Longest book name considered while scanning text ("Song of Songs")
+const MAX_NAME_WORDS: usize = 3;
+
+/// Find chapter:verse references ("Gen. 1:1-3", "1 Cor 13:4, 7") in prose.
+///
+/// Only references with an explicit verse and a capitalized or numbered
+/// book name are reported, to keep ordinary words from matching.
+pub fn find_references(text: &str) -> Vec<FoundReference> {
+    let mut found = Vec::new();
+    let mut index = 0;
+
+    while let Some(c) = text[index..].chars().next() {
+        let at_word_start = !text[..index]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        let candidate = at_word_start && (c.is_uppercase() || matches!(c, '1'..='3'));
+
+        if candidate {
+            if let Some(reference) = reference_at(text, index) {
+                index = reference.end;
+                found.push(reference);
+                continue;
+            }
+        }
+        index += c.len_utf8();
+    }
+
+    found
+}
+
+/// Try to read "<book name> <chapter>:<verse>..." starting at `start`
+fn reference_at(text: &str, start: usize) -> Option<FoundReference> {
+    let rest = &text[start..];
+
+    // A numbered book's leading digit ("1 Cor") counts as part of the name
+    let mut cursor = 0;
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        cursor = 1;
+        cursor += rest[cursor..].len() - rest[cursor..].trim_start_matches(' ').len();
+    }
+
+    // Up to MAX_NAME_WORDS words, each optionally ending in a period
+    let mut name_end = None;
+    for _ in 0..MAX_NAME_WORDS {
+        let word_len = rest[cursor..]
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len() - cursor);
+        if word_len == 0 {
+            break;
+        }
+        cursor += word_len;
+        if rest[cursor..].starts_with('.') {
+            cursor += 1;
+        }
+        name_end = Some(cursor);
+
+        let spaces = rest[cursor..].len() - rest[cursor..].trim_start_matches(' ').len();
+        if rest[cursor + spaces..].starts_with(|c: char| c.is_ascii_digit()) {
+            break;
+        }
+        cursor += spaces;
+    }
+    let name_end = name_end?;
+
+    let numbers_start =
+        name_end + rest[name_end..].len() - rest[name_end..].trim_start_matches(' ').len();
+    let numbers = &rest[numbers_start..];
+    if !numbers.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // Digits, separators and ", <digit>" continuations
+    let mut numbers_len = 0;
+    let chars: Vec<(usize, char)> = numbers.char_indices().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let next_is_digit = chars.get(i + 1).is_some_and(|(_, c)| c.is_ascii_digit());
+        let accepted = c.is_ascii_digit()
+            || (matches!(c, ':' | '.' | '-' | '–') && next_is_digit)
+            || (c == ','
+                && chars.get(i + 1).is_some_and(|(_, c)| *c == ' ')
+                && chars.get(i + 2).is_some_and(|(_, c)| c.is_ascii_digit()))
+            || (c == ' ' && i > 0 && chars[i - 1].1 == ',' && next_is_digit);
+        if !accepted {
+            break;
+        }
+        numbers_len = offset + c.len_utf8();
+        i += 1;
+    }
+    let numbers = &numbers[..numbers_len];
+    if !numbers.contains([':', '.']) {
+        return None;
+    }
+
+    let name = rest[..name_end].trim_end_matches('.');
+    let ranges = parse_references(&format!("{} {}", name, numbers)).ok()?;
+    let end = start + numbers_start + numbers_len;
+    Some(FoundReference {
+        text: text[start..end].to_string(),
+        reference: ranges
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; "),
+        ranges,
+        start,
+        end,
+    })
+}
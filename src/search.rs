@@ -0,0 +1,529 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::api::models::Chapter;
+use crate::error::{AppError, Result};
+use crate::reference;
+use crate::store::DiskStore;
+
+/// Extra weight for a phrase match over its words matching separately
+const PHRASE_BOOST: f32 = 1.5;
+
+/// Longest snippet returned before it is trimmed around the first match
+const MAX_SNIPPET_CHARS: usize = 240;
+
+/// Split text into lowercase word tokens with their byte ranges.
+/// Apostrophes inside words are dropped so "don't" and "dont" match.
+fn tokenize(text: &str) -> Vec<(usize, usize, String)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut word = String::new();
+
+    let is_apostrophe = |c: char| c == '\'' || c == '’';
+    for (index, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            start.get_or_insert(index);
+            word.extend(c.to_lowercase());
+        } else if is_apostrophe(c) && start.is_some() {
+            continue;
+        } else if let Some(begin) = start.take() {
+            tokens.push((begin, index, std::mem::take(&mut word)));
+        }
+    }
+    if let Some(begin) = start {
+        tokens.push((begin, text.len(), word));
+    }
+
+    // A trailing apostrophe ("fathers'") is not part of the word's range
+    for token in &mut tokens {
+        let trimmed = text[token.0..token.1].trim_end_matches(is_apostrophe);
+        token.1 = token.0 + trimmed.len();
+    }
+    tokens
+}
+
+/// A single word or an exact phrase
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+    Term(String),
+    Phrase(Vec<String>),
+}
+
+impl Atom {
+    fn from_words(words: Vec<String>) -> Option<Self> {
+        match words.len() {
+            0 => None,
+            1 => words.into_iter().next().map(Atom::Term),
+            _ => Some(Atom::Phrase(words)),
+        }
+    }
+
+    fn words(&self) -> &[String] {
+        match self {
+            Atom::Term(term) => std::slice::from_ref(term),
+            Atom::Phrase(words) => words,
+        }
+    }
+}
+
+/// Atoms that must all match, minus atoms that must not
+#[derive(Debug, Default)]
+struct Clause {
+    required: Vec<Atom>,
+    excluded: Vec<Atom>,
+}
+
+/// A parsed search query: clauses joined by OR.
+///
+/// Syntax: words are ANDed, `"quoted text"` is a phrase, `OR` (or `|`)
+/// separates alternatives and `NOT word` / `-word` excludes verses.
+#[derive(Debug)]
+pub struct SearchQuery {
+    clauses: Vec<Clause>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut clauses = vec![Clause::default()];
+        let mut negate = false;
+        let mut chars = input.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+
+            let (raw, quoted) = if c == '"' {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                (phrase, true)
+            } else {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || (c == '"' && !word.is_empty()) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                (word, false)
+            };
+
+            if !quoted {
+                match raw.as_str() {
+                    "OR" | "|" => {
+                        clauses.push(Clause::default());
+                        negate = false;
+                        continue;
+                    }
+                    "AND" | "&&" => continue,
+                    "NOT" => {
+                        negate = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            let (raw, excluded) = match raw.strip_prefix('-') {
+                Some(rest) if !quoted && !rest.is_empty() => (rest.to_string(), true),
+                _ => (raw, std::mem::take(&mut negate)),
+            };
+            let words = tokenize(&raw)
+                .into_iter()
+                .map(|(_, _, word)| word)
+                .collect();
+
+            if let Some(atom) = Atom::from_words(words) {
+                let clause = clauses.last_mut().expect("there is always a clause");
+                if excluded {
+                    clause.excluded.push(atom);
+                } else {
+                    clause.required.push(atom);
+                }
+            }
+        }
+
+        clauses.retain(|clause| !clause.required.is_empty() || !clause.excluded.is_empty());
+        if clauses.is_empty() {
+            return Err(AppError::InvalidInput("Search query is empty".to_string()));
+        }
+        if clauses.iter().any(|clause| clause.required.is_empty()) {
+            return Err(AppError::InvalidInput(
+                "Every part of a search query needs at least one word that is not excluded"
+                    .to_string(),
+            ));
+        }
+        Ok(Self { clauses })
+    }
+
+    /// Words to highlight in snippets
+    fn highlight_terms(&self) -> HashSet<&str> {
+        self.clauses
+            .iter()
+            .flat_map(|clause| &clause.required)
+            .flat_map(|atom| atom.words())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Restrict a search to part of the Bible
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// USFM book code
+    pub book: Option<String>,
+    /// `Some(true)` for the New Testament, `Some(false)` for the Old
+    pub new_testament: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub book: String,
+    pub chapter: u32,
+    pub verse: u32,
+    pub text: String,
+    /// HTML-escaped verse text with matches wrapped in `<mark>` tags
+    pub snippet: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResults {
+    pub translation: String,
+    pub query: String,
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub results: Vec<SearchHit>,
+}
+
+struct IndexedVerse {
+    book: String,
+    chapter: u32,
+    verse: u32,
+    text: String,
+    length: usize,
+}
+
+/// Inverted index over every verse of one translation
+pub struct SearchIndex {
+    translation: String,
+    verses: Vec<IndexedVerse>,
+    /// term -> verse index -> word positions
+    postings: HashMap<String, HashMap<u32, Vec<u32>>>,
+}
+
+impl SearchIndex {
+    pub fn build(translation: &str, chapters: &[Chapter]) -> Self {
+        let mut verses = Vec::new();
+        let mut postings: HashMap<String, HashMap<u32, Vec<u32>>> = HashMap::new();
+
+        for chapter in chapters {
+            for verse in &chapter.verses {
+                let id = verses.len() as u32;
+                let tokens = tokenize(&verse.text);
+                for (position, (_, _, word)) in tokens.iter().enumerate() {
+                    postings
+                        .entry(word.clone())
+                        .or_default()
+                        .entry(id)
+                        .or_default()
+                        .push(position as u32);
+                }
+                verses.push(IndexedVerse {
+                    book: chapter.book.clone(),
+                    chapter: chapter.chapter,
+                    verse: verse.verse,
+                    text: verse.text.clone(),
+                    length: tokens.len(),
+                });
+            }
+        }
+
+        tracing::info!(
+            "Built search index for {}: {} verses, {} terms",
+            translation,
+            verses.len(),
+            postings.len()
+        );
+        Self {
+            translation: translation.to_string(),
+            verses,
+            postings,
+        }
+    }
+
+    pub fn verse_count(&self) -> usize {
+        self.verses.len()
+    }
+
+    fn idf(&self, term: &str) -> f32 {
+        let frequency = self.postings.get(term).map_or(0, HashMap::len) as f32;
+        (1.0 + self.verses.len() as f32 / (1.0 + frequency)).ln()
+    }
+
+    /// Verses matching an atom, with the number of times it occurs in each
+    fn matches(&self, atom: &Atom) -> HashMap<u32, u32> {
+        match atom {
+            Atom::Term(term) => self
+                .postings
+                .get(term)
+                .map(|docs| {
+                    docs.iter()
+                        .map(|(&id, positions)| (id, positions.len() as u32))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Atom::Phrase(words) => {
+                let Some(first) = self.postings.get(&words[0]) else {
+                    return HashMap::new();
+                };
+                let mut found = HashMap::new();
+                for (&id, positions) in first {
+                    let count = positions
+                        .iter()
+                        .filter(|&&start| {
+                            words.iter().enumerate().skip(1).all(|(offset, word)| {
+                                self.postings
+                                    .get(word)
+                                    .and_then(|docs| docs.get(&id))
+                                    .is_some_and(|p| p.contains(&(start + offset as u32)))
+                            })
+                        })
+                        .count() as u32;
+                    if count > 0 {
+                        found.insert(id, count);
+                    }
+                }
+                found
+            }
+        }
+    }
+
+    fn atom_weight(&self, atom: &Atom) -> f32 {
+        match atom {
+            Atom::Term(term) => self.idf(term),
+            Atom::Phrase(words) => {
+                words.iter().map(|word| self.idf(word)).sum::<f32>() * PHRASE_BOOST
+            }
+        }
+    }
+
+    /// Score every verse that satisfies at least one clause
+    fn evaluate(&self, query: &SearchQuery) -> HashMap<u32, f32> {
+        let mut scores: HashMap<u32, f32> = HashMap::new();
+
+        for clause in &query.clauses {
+            let mut candidates: Option<HashMap<u32, f32>> = None;
+            for atom in &clause.required {
+                let weight = self.atom_weight(atom);
+                let matched = self.matches(atom);
+                candidates = Some(match candidates {
+                    None => matched
+                        .into_iter()
+                        .map(|(id, count)| (id, count as f32 * weight))
+                        .collect(),
+                    Some(current) => current
+                        .into_iter()
+                        .filter_map(|(id, score)| {
+                            matched
+                                .get(&id)
+                                .map(|&count| (id, score + count as f32 * weight))
+                        })
+                        .collect(),
+                });
+            }
+
+            let mut candidates = candidates.unwrap_or_default();
+            for atom in &clause.excluded {
+                for id in self.matches(atom).keys() {
+                    candidates.remove(id);
+                }
+            }
+
+            for (id, score) in candidates {
+                // Favour short verses where the match is a larger share of the text
+                let length = self.verses[id as usize].length.max(1) as f32;
+                let score = score / length.sqrt();
+                let best = scores.entry(id).or_insert(0.0);
+                *best = best.max(score);
+            }
+        }
+        scores
+    }
+
+    pub fn search(
+        &self,
+        query_text: &str,
+        filter: &SearchFilter,
+        page: usize,
+        per_page: usize,
+    ) -> Result<SearchResults> {
+        let query = SearchQuery::parse(query_text)?;
+
+        let mut ranked: Vec<(u32, f32)> = self
+            .evaluate(&query)
+            .into_iter()
+            .filter(|(id, _)| {
+                let verse = &self.verses[*id as usize];
+                filter
+                    .book
+                    .as_ref()
+                    .is_none_or(|book| verse.book.eq_ignore_ascii_case(book))
+                    && filter
+                        .new_testament
+                        .is_none_or(|nt| reference::is_new_testament(&verse.book) == Some(nt))
+            })
+            .collect();
+        // Best score first, then canonical order
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let page = page.max(1);
+        let highlight = query.highlight_terms();
+        let results = ranked
+            .iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .map(|&(id, score)| {
+                let verse = &self.verses[id as usize];
+                SearchHit {
+                    book: verse.book.clone(),
+                    chapter: verse.chapter,
+                    verse: verse.verse,
+                    text: verse.text.clone(),
+                    snippet: snippet(&verse.text, &highlight),
+                    score,
+                }
+            })
+            .collect();
+
+        Ok(SearchResults {
+            translation: self.translation.clone(),
+            query: query_text.to_string(),
+            total: ranked.len(),
+            page,
+            per_page,
+            results,
+        })
+    }
+}
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// HTML-escape `text`, wrap query words in `<mark>` and trim long verses
+/// to a window around the first match
+fn snippet(text: &str, terms: &HashSet<&str>) -> String {
+    let marks: Vec<(usize, usize)> = tokenize(text)
+        .into_iter()
+        .filter(|(_, _, word)| terms.contains(word.as_str()))
+        .map(|(start, end, _)| (start, end))
+        .collect();
+
+    let (mut from, mut to) = (0, text.len());
+    if text.chars().count() > MAX_SNIPPET_CHARS {
+        let first = marks.first().map_or(0, |mark| mark.0);
+        from = text[..first]
+            .char_indices()
+            .rev()
+            .nth(MAX_SNIPPET_CHARS / 3)
+            .map_or(0, |(index, _)| index);
+        to = text[from..]
+            .char_indices()
+            .nth(MAX_SNIPPET_CHARS)
+            .map_or(text.len(), |(index, _)| from + index);
+    }
+
+    let mut out = String::new();
+    if from > 0 {
+        out.push('…');
+    }
+    let mut cursor = from;
+    for (start, end) in marks {
+        if start < from || end > to {
+            continue;
+        }
+        escape_html(&text[cursor..start], &mut out);
+        out.push_str("<mark>");
+        escape_html(&text[start..end], &mut out);
+        out.push_str("</mark>");
+        cursor = end;
+    }
+    escape_html(&text[cursor..to], &mut out);
+    if to < text.len() {
+        out.push('…');
+    }
+    out
+}
+
+fn not_mirrored(translation: &str) -> AppError {
+    AppError::NotFound(format!(
+        "Translation {} has not been mirrored; run `mirror {}` to enable search",
+        translation, translation
+    ))
+}
+
+/// An index, or its pending build, for one run of the mirror command
+type IndexCell = (u64, Arc<OnceCell<Arc<SearchIndex>>>);
+
+/// Search indexes built lazily from the disk store, once per completed
+/// mirror of a translation: mirroring it again replaces the index
+#[derive(Default)]
+pub struct SearchIndexes {
+    indexes: Mutex<HashMap<String, IndexCell>>,
+}
+
+impl SearchIndexes {
+    pub async fn get_or_build(
+        &self,
+        store: &DiskStore,
+        translation: &str,
+    ) -> Result<Arc<SearchIndex>> {
+        let mirrored = store
+            .load_mirror_summary(translation)
+            .await?
+            .ok_or_else(|| not_mirrored(translation))?;
+
+        let cell = {
+            let mut indexes = self.indexes.lock().await;
+            let entry = indexes
+                .entry(translation.to_string())
+                .or_insert_with(|| (mirrored.completed_at_ms, Default::default()));
+            if entry.0 != mirrored.completed_at_ms {
+                *entry = (mirrored.completed_at_ms, Default::default());
+            }
+            entry.1.clone()
+        };
+
+        cell.get_or_try_init(|| async {
+            let chapters = store
+                .load_translation_chapters(translation)
+                .await?
+                .ok_or_else(|| not_mirrored(translation))?;
+
+            let translation = translation.to_string();
+            let index =
+                tokio::task::spawn_blocking(move || SearchIndex::build(&translation, &chapters))
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!("Search index build failed: {}", e))
+                    })?;
+            Ok(Arc::new(index))
+        })
+        .await
+        .cloned()
+    }
+}
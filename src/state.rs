@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::api::bible_brain::BibleBrainClient;
+use crate::api::helloao::{CommentaryChapter, HelloAOBibleClient};
+use crate::api::models::Translation;
+use crate::api::provider::{self, BibleProvider};
+use crate::canons::{self, Canon};
+use crate::config::Config;
+use crate::crossrefs::CrossRefs;
+use crate::error::Result;
+use crate::ids::{CatalogEntry, CommentaryId, KnownIds, TranslationId};
+use crate::lexicon::Lexicon;
+use crate::original::OriginalTexts;
+use crate::search::SearchIndexes;
+use crate::store::{self, DiskStore};
+use crate::versification::{Scheme, TranslationSchemes};
+
+/// Shared state handed to every route handler
+#[derive(Clone)]
+pub struct AppState {
+    /// Provider chain that serves translations, books and chapters
+    pub provider: Arc<dyn BibleProvider>,
+    /// HelloAO client for commentary data, which only HelloAO offers
+    pub helloao: HelloAOBibleClient,
+    /// Bible Brain client for filesets and audio; `None` without an API key
+    pub brain: Option<BibleBrainClient>,
+    /// On-disk mirror that persists fetched data between restarts
+    pub store: Option<DiskStore>,
+    /// Full-text indexes over mirrored translations, built on first use
+    pub search: Arc<SearchIndexes>,
+    /// Recently fetched translation and commentary catalogs for ID checks
+    pub known_ids: Arc<KnownIds>,
+    /// Configured versification schemes, by translation ID
+    pub schemes: Arc<TranslationSchemes>,
+    /// Configured canon IDs, by translation ID
+    pub translation_canons: Arc<HashMap<String, String>>,
+    /// Cross-reference dataset, loaded on first use
+    pub crossrefs: Arc<CrossRefs>,
+    /// Tagged Hebrew and Greek texts, loaded per book on first use
+    pub original: Arc<OriginalTexts>,
+    /// Strong's lexicon, loaded on first use
+    pub lexicon: Arc<Lexicon>,
+}
+
+impl AppState {
+    /// State serving scripture from `provider` with every optional part
+    /// (Bible Brain, the store, datasets, configured schemes and canons)
+    /// left out; set the public fields to add them
+    pub fn new(provider: Arc<dyn BibleProvider>, helloao: HelloAOBibleClient) -> Self {
+        Self {
+            provider,
+            helloao,
+            brain: None,
+            store: None,
+            search: Arc::new(SearchIndexes::default()),
+            known_ids: Arc::new(KnownIds::default()),
+            schemes: Arc::new(TranslationSchemes::default()),
+            translation_canons: Arc::new(HashMap::new()),
+            crossrefs: Arc::new(CrossRefs::new("")),
+            original: Arc::new(OriginalTexts::new("", "")),
+            lexicon: Arc::new(Lexicon::new("")),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let helloao = HelloAOBibleClient::with_options(
+            config.bible_api.base_url.clone(),
+            config.cache.clone(),
+            config.http.clone(),
+        );
+
+        let brain = config.bible_brain.api_key.clone().map(|api_key| {
+            BibleBrainClient::with_http(
+                config.bible_brain.base_url.clone(),
+                api_key,
+                config.http.clone(),
+            )
+        });
+
+        let store = (!config.store.path.is_empty()).then(|| DiskStore::new(&config.store.path));
+
+        let provider = provider::from_config(config, &helloao, brain.as_ref(), store.as_ref())?;
+
+        Ok(Self {
+            brain,
+            store,
+            schemes: Arc::new(TranslationSchemes::new(
+                config.bible_api.versification.clone(),
+            )),
+            translation_canons: Arc::new(config.bible_api.canons.clone()),
+            crossrefs: Arc::new(CrossRefs::new(&config.crossrefs.path)),
+            original: Arc::new(OriginalTexts::new(
+                &config.original.hebrew_path,
+                &config.original.greek_path,
+            )),
+            lexicon: Arc::new(Lexicon::new(&config.original.lexicon_path)),
+            ..Self::new(provider, helloao)
+        })
+    }
+
+    /// Validate a translation ID from a request: malformed IDs are
+    /// `invalid_input`, IDs missing from the translation list `not_found`.
+    /// A provider chain lists every provider's translations under our IDs,
+    /// so an ID any provider serves passes.
+    pub async fn translation_id(&self, raw: &str) -> Result<TranslationId> {
+        let id = TranslationId::parse(raw)?;
+        self.known_ids
+            .check_translation(&id, || self.translation_catalog())
+            .await?;
+        Ok(id)
+    }
+
+    async fn translation_catalog(&self) -> Result<Vec<CatalogEntry>> {
+        let translations = self.provider.get_translations().await?;
+        Ok(translations
+            .into_iter()
+            .map(|t| CatalogEntry {
+                id: t.id,
+                language: t.language,
+            })
+            .collect())
+    }
+
+    /// The versification scheme a translation follows: configured, or
+    /// guessed from its language in the translation list
+    pub async fn versification(&self, translation: &TranslationId) -> Scheme {
+        if let Some(scheme) = self.schemes.configured(translation.as_str()) {
+            return scheme;
+        }
+        let language = self
+            .known_ids
+            .translation_language(translation, || self.translation_catalog())
+            .await;
+        self.schemes
+            .scheme(translation.as_str(), language.as_deref())
+    }
+
+    /// The canon a translation is tagged with: the one configured in
+    /// `CANONS`, else the provider's own tag, which imported and HelloAO
+    /// translations take from their book lists
+    pub fn canon_of(&self, translation: &Translation) -> Option<&'static Canon> {
+        match self.translation_canons.get(&translation.id) {
+            Some(id) => canons::by_id(id),
+            None => translation.canon.as_deref().and_then(canons::by_id),
+        }
+    }
+
+    /// Validate a commentary ID from a request, like `translation_id`
+    pub async fn commentary_id(&self, raw: &str) -> Result<CommentaryId> {
+        let id = CommentaryId::parse(raw)?;
+        self.known_ids
+            .check_commentary(&id, || async {
+                let commentaries = self.helloao.get_commentaries().await?;
+                Ok(commentaries.into_iter().map(|c| c.id).collect())
+            })
+            .await?;
+        Ok(id)
+    }
+
+    /// Get commentary for a chapter, persisting it to (or serving it from)
+    /// the on-disk store when one is configured
+    pub async fn get_commentary(
+        &self,
+        commentary_id: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<CommentaryChapter> {
+        let fetched = self
+            .helloao
+            .get_commentary(commentary_id, book, chapter)
+            .await;
+        let Some(disk) = &self.store else {
+            return fetched;
+        };
+
+        let what = format!("commentary {} {} {}", commentary_id, book, chapter);
+        match fetched {
+            Ok(commentary) => {
+                store::log_persist_error(&what, disk.save_commentary(&commentary).await);
+                Ok(commentary)
+            }
+            Err(e) => store::fallback(
+                &what,
+                e,
+                disk.load_commentary(commentary_id, book, chapter).await,
+            ),
+        }
+    }
+}
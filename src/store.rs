@@ -0,0 +1,421 @@
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::api::helloao::{CommentaryChapter, HelloAOBibleClient};
+use crate::api::models::{Book, Chapter, Translation};
+use crate::api::provider::BibleProvider;
+use crate::error::{AppError, Result};
+
+/// Disk-backed store of normalized scripture data.
+///
+/// Everything is kept as JSON files in a directory tree that mirrors the
+/// HelloAO URL layout:
+///
+/// ```text
+/// <root>/translations.json
+/// <root>/<translation>/books.json
+/// <root>/<translation>/mirror.json
+/// <root>/<translation>/<book>/<chapter>.json
+/// <root>/c/<commentary>/<book>/<chapter>.json
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiskStore {
+    root: PathBuf,
+}
+
+impl DiskStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Build a path below the store root, refusing segments that could
+    /// escape it (`..`, separators and other unexpected characters)
+    fn path(&self, segments: &[&str], file: &str) -> Option<PathBuf> {
+        let valid = |segment: &str| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        };
+        if !segments.iter().all(|segment| valid(segment)) {
+            return None;
+        }
+
+        let mut path = self.root.clone();
+        path.extend(segments);
+        path.push(file);
+        Some(path)
+    }
+
+    async fn load<T: DeserializeOwned>(&self, segments: &[&str], file: &str) -> Result<Option<T>> {
+        let Some(path) = self.path(segments, file) else {
+            return Ok(None);
+        };
+
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AppError::Internal(anyhow::anyhow!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    async fn save<T: Serialize>(&self, segments: &[&str], file: &str, value: &T) -> Result<()> {
+        let Some(path) = self.path(segments, file) else {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Refusing to store {}/{}: invalid path segment",
+                segments.join("/"),
+                file
+            )));
+        };
+
+        let io_error = |e: std::io::Error| {
+            AppError::Internal(anyhow::anyhow!("Failed to write {}: {}", path.display(), e))
+        };
+
+        // Most saves re-store what an earlier read already stored; leave
+        // an identical document alone rather than rewrite it
+        let bytes = serde_json::to_vec(value)?;
+        if tokio::fs::read(&path)
+            .await
+            .is_ok_and(|stored| stored == bytes)
+        {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+
+        // Write to a temporary file first so readers never see a partial
+        // document; the name is unique so concurrent writers don't collide
+        static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+        let tmp = path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = match tokio::fs::write(&tmp, bytes).await {
+            Ok(()) => tokio::fs::rename(&tmp, &path).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            tokio::fs::remove_file(&tmp).await.ok();
+            return Err(io_error(e));
+        }
+        Ok(())
+    }
+
+    /// Delete a document if it exists
+    async fn remove(&self, segments: &[&str], file: &str) -> Result<()> {
+        let Some(path) = self.path(segments, file) else {
+            return Ok(());
+        };
+
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(AppError::Internal(
+                anyhow::anyhow!("Failed to remove {}: {}", path.display(), e),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn load_translations(&self) -> Result<Option<Vec<Translation>>> {
+        self.load(&[], "translations.json").await
+    }
+
+    pub async fn save_translations(&self, translations: &[Translation]) -> Result<()> {
+        self.save(&[], "translations.json", &translations).await
+    }
+
+    pub async fn load_books(&self, translation: &str) -> Result<Option<Vec<Book>>> {
+        self.load(&[translation], "books.json").await
+    }
+
+    pub async fn save_books(&self, translation: &str, books: &[Book]) -> Result<()> {
+        self.save(&[translation], "books.json", &books).await
+    }
+
+    pub async fn load_chapter(
+        &self,
+        translation: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<Option<Chapter>> {
+        self.load(&[translation, book], &format!("{}.json", chapter))
+            .await
+    }
+
+    pub async fn save_chapter(&self, chapter: &Chapter) -> Result<()> {
+        let file = format!("{}.json", chapter.chapter);
+        self.save(&[&chapter.translation, &chapter.book], &file, chapter)
+            .await
+    }
+
+    pub async fn load_commentary(
+        &self,
+        commentary_id: &str,
+        book: &str,
+        chapter: u32,
+    ) -> Result<Option<CommentaryChapter>> {
+        self.load(&["c", commentary_id, book], &format!("{}.json", chapter))
+            .await
+    }
+
+    pub async fn save_commentary(&self, commentary: &CommentaryChapter) -> Result<()> {
+        let file = format!("{}.json", commentary.chapter);
+        let segments = ["c", &commentary.commentary_id, &commentary.book];
+        self.save(&segments, &file, commentary).await
+    }
+
+    /// The record `mirror_translation` leaves once a translation is complete
+    pub async fn load_mirror_summary(&self, translation: &str) -> Result<Option<MirrorSummary>> {
+        self.load(&[translation], "mirror.json").await
+    }
+
+    /// Load every chapter of a fully mirrored translation in book-list order.
+    ///
+    /// Returns `None` unless `mirror_translation` has completed for the
+    /// translation, so chapters merely cached by the server are never
+    /// mistaken for the whole text. Fails when a mirrored chapter is missing.
+    pub async fn load_translation_chapters(
+        &self,
+        translation: &str,
+    ) -> Result<Option<Vec<Chapter>>> {
+        if self.load_mirror_summary(translation).await?.is_none() {
+            return Ok(None);
+        }
+        let Some(books) = self.load_books(translation).await? else {
+            return Ok(None);
+        };
+
+        let mut chapters = Vec::new();
+        for book in &books {
+            for chapter in 1..=book.number_of_chapters.unwrap_or(0) {
+                let data = self
+                    .load_chapter(translation, &book.id, chapter)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::NotFound(format!(
+                            "{} {} is missing from the mirror of {}; run `mirror {}` again",
+                            book.id, chapter, translation, translation
+                        ))
+                    })?;
+                chapters.push(data);
+            }
+        }
+        Ok(Some(chapters))
+    }
+
+    /// Download a whole translation (book list and every chapter) into the store
+    pub async fn mirror_translation(
+        &self,
+        client: &HelloAOBibleClient,
+        translation: &str,
+        concurrency: usize,
+    ) -> Result<MirrorSummary> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        // Until the new copy is complete, the translation is not mirrored
+        self.remove(&[translation], "mirror.json").await?;
+
+        let books = client.get_books(translation).await?;
+        self.save_books(translation, &books).await?;
+
+        let mut chapters = Vec::new();
+        for book in &books {
+            match book.number_of_chapters {
+                Some(count) => {
+                    chapters.extend((1..=count).map(|chapter| (book.id.clone(), chapter)))
+                }
+                None => tracing::warn!(
+                    "Book {} in {} has no chapter count; skipping",
+                    book.id,
+                    translation
+                ),
+            }
+        }
+
+        let total = chapters.len();
+        stream::iter(chapters)
+            .map(|(book, chapter)| async move {
+                let data = client.get_chapter(translation, &book, chapter).await?;
+                self.save_chapter(&data).await?;
+                tracing::debug!("Mirrored {} {} {}", translation, book, chapter);
+                Ok::<_, AppError>(())
+            })
+            .buffer_unordered(concurrency.max(1))
+            .try_collect::<()>()
+            .await?;
+
+        let summary = MirrorSummary {
+            translation: translation.to_string(),
+            books: books.len(),
+            chapters: total,
+            completed_at_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        };
+        self.save(&[translation], "mirror.json", &summary).await?;
+        Ok(summary)
+    }
+}
+
+/// What `DiskStore::mirror_translation` downloaded, stored alongside the
+/// translation as the mark of a complete mirror
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorSummary {
+    pub translation: String,
+    pub books: usize,
+    pub chapters: usize,
+    /// Unix time the mirror finished, in milliseconds
+    pub completed_at_ms: u64,
+}
+
+fn not_mirrored(what: String) -> AppError {
+    AppError::NotFound(format!("{} is not available in the local mirror", what))
+}
+
+/// The store on its own serves only what has been mirrored or fetched before
+#[async_trait]
+impl BibleProvider for DiskStore {
+    fn name(&self) -> &str {
+        "mirror"
+    }
+
+    fn is_snapshot(&self) -> bool {
+        true
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        self.load_translations()
+            .await?
+            .ok_or_else(|| not_mirrored("Translation list".to_string()))
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        self.load_books(translation)
+            .await?
+            .ok_or_else(|| not_mirrored(format!("Book list for {}", translation)))
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        self.load_chapter(translation, book, chapter)
+            .await?
+            .ok_or_else(|| not_mirrored(format!("{} {} {}", translation, book, chapter)))
+    }
+}
+
+/// Wraps an upstream provider so successful responses are written to the
+/// store (unless the stored copy is identical), and the stored copy is
+/// served when the upstream call fails.
+///
+/// Only one provider should own the stored translation and book lists:
+/// the others are wrapped with `without_catalogs`, so a fallback's catalog
+/// never overwrites the primary's.
+pub struct StoreBackedProvider {
+    upstream: Arc<dyn BibleProvider>,
+    store: DiskStore,
+    catalogs: bool,
+}
+
+impl StoreBackedProvider {
+    pub fn new(upstream: Arc<dyn BibleProvider>, store: DiskStore) -> Self {
+        Self {
+            upstream,
+            store,
+            catalogs: true,
+        }
+    }
+
+    /// Store and fall back to chapters only, passing translation and book
+    /// lists straight through
+    pub fn without_catalogs(mut self) -> Self {
+        self.catalogs = false;
+        self
+    }
+}
+
+/// Log (but otherwise ignore) a failure to persist freshly fetched data
+pub(crate) fn log_persist_error(what: &str, result: Result<()>) {
+    if let Err(e) = result {
+        tracing::warn!("Failed to persist {}: {}", what, e);
+    }
+}
+
+/// Serve the stored copy after an upstream failure, or return the original error
+pub(crate) fn fallback<T>(what: &str, error: AppError, stored: Result<Option<T>>) -> Result<T> {
+    match stored {
+        Ok(Some(stored)) => {
+            tracing::warn!(
+                "Upstream failed for {} ({}); serving stored copy",
+                what,
+                error
+            );
+            Ok(stored)
+        }
+        _ => Err(error),
+    }
+}
+
+#[async_trait]
+impl BibleProvider for StoreBackedProvider {
+    fn name(&self) -> &str {
+        self.upstream.name()
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        if !self.catalogs {
+            return self.upstream.get_translations().await;
+        }
+        match self.upstream.get_translations().await {
+            Ok(translations) => {
+                log_persist_error(
+                    "translation list",
+                    self.store.save_translations(&translations).await,
+                );
+                Ok(translations)
+            }
+            Err(e) => fallback("translation list", e, self.store.load_translations().await),
+        }
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        if !self.catalogs {
+            return self.upstream.get_books(translation).await;
+        }
+        let what = format!("book list for {}", translation);
+        match self.upstream.get_books(translation).await {
+            Ok(books) => {
+                log_persist_error(&what, self.store.save_books(translation, &books).await);
+                Ok(books)
+            }
+            Err(e) => fallback(&what, e, self.store.load_books(translation).await),
+        }
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        let what = format!("{} {} {}", translation, book, chapter);
+        match self.upstream.get_chapter(translation, book, chapter).await {
+            Ok(data) => {
+                log_persist_error(&what, self.store.save_chapter(&data).await);
+                Ok(data)
+            }
+            Err(e) => fallback(
+                &what,
+                e,
+                self.store.load_chapter(translation, book, chapter).await,
+            ),
+        }
+    }
+}
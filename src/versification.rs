@@ -0,0 +1,437 @@
+//! Versification schemes and the mappings between them.
+//!
+//! Translations number some verses differently: Hebrew-based Bibles count
+//! Psalm titles as verse 1, put Malachi 4 at Malachi 3:19-24, and the
+//! Septuagint, Vulgate and Russian Synodal number most Psalms one lower.
+//!
+//! Every scheme is described by its differences from the English (KJV)
+//! scheme, so mapping between two schemes goes through English. The tables
+//! cover verses that move between chapters and verse offsets from Psalm
+//! titles; where traditions split a verse differently, the verse is mapped
+//! to where it begins. Verses with no counterpart (a Psalm title that
+//! English does not number) map to nothing.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scheme {
+    /// King James Version numbering, used by most English Bibles
+    #[default]
+    English,
+    /// Masoretic numbering of the Hebrew Bible (BHS, "Original")
+    Hebrew,
+    /// Greek Old Testament numbering
+    #[serde(rename = "lxx")]
+    Septuagint,
+    /// Latin Vulgate numbering
+    Vulgate,
+    /// Russian Synodal numbering
+    Synodal,
+}
+
+impl Scheme {
+    pub const ALL: [Scheme; 5] = [
+        Scheme::English,
+        Scheme::Hebrew,
+        Scheme::Septuagint,
+        Scheme::Vulgate,
+        Scheme::Synodal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scheme::English => "english",
+            Scheme::Hebrew => "hebrew",
+            Scheme::Septuagint => "lxx",
+            Scheme::Vulgate => "vulgate",
+            Scheme::Synodal => "synodal",
+        }
+    }
+
+    /// The scheme translations in a language usually follow, by ISO 639
+    /// code ("heb", "ru", ...)
+    pub fn for_language(language: &str) -> Self {
+        match language.to_ascii_lowercase().as_str() {
+            "heb" | "hbo" | "he" => Scheme::Hebrew,
+            "grc" | "ell" | "el" => Scheme::Septuagint,
+            "lat" | "la" => Scheme::Vulgate,
+            "rus" | "ru" => Scheme::Synodal,
+            _ => Scheme::English,
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "english" | "kjv" | "eng" => Ok(Scheme::English),
+            "hebrew" | "original" | "org" | "mt" => Ok(Scheme::Hebrew),
+            "lxx" | "septuagint" => Ok(Scheme::Septuagint),
+            "vulgate" | "vul" | "vulg" => Ok(Scheme::Vulgate),
+            "synodal" | "rso" | "russian" => Ok(Scheme::Synodal),
+            other => Err(format!(
+                "Unknown versification scheme: {} (expected one of english, hebrew, lxx, vulgate, synodal)",
+                other
+            )),
+        }
+    }
+}
+
+/// English verses `chapter:first-last` (to the end of the chapter when
+/// `last` is `None`) are `to_chapter:to_first...` in the scheme
+#[derive(Debug, Clone, Copy)]
+struct Rule {
+    book: &'static str,
+    chapter: u32,
+    first: u32,
+    last: Option<u32>,
+    to_chapter: u32,
+    to_first: u32,
+}
+
+impl Rule {
+    fn covers(&self, chapter: u32, verse: u32) -> bool {
+        chapter == self.chapter && verse >= self.first && self.last.is_none_or(|last| verse <= last)
+    }
+
+    fn covers_target(&self, chapter: u32, verse: u32) -> bool {
+        chapter == self.to_chapter
+            && verse >= self.to_first
+            && self
+                .last
+                .is_none_or(|last| verse <= self.to_first + (last - self.first))
+    }
+}
+
+/// `(book, chapter, first, last, to_chapter, to_first)`; a `last` of 0 runs
+/// to the end of the chapter
+type Shift = (&'static str, u32, u32, u32, u32, u32);
+
+/// English to Hebrew outside the Psalms
+const HEBREW: &[Shift] = &[
+    ("GEN", 31, 55, 55, 32, 1),
+    ("GEN", 32, 1, 0, 32, 2),
+    ("EXO", 8, 1, 4, 7, 26),
+    ("EXO", 8, 5, 0, 8, 1),
+    ("EXO", 22, 1, 1, 21, 37),
+    ("EXO", 22, 2, 0, 22, 1),
+    ("LEV", 6, 1, 7, 5, 20),
+    ("LEV", 6, 8, 0, 6, 1),
+    ("NUM", 16, 36, 50, 17, 1),
+    ("NUM", 17, 1, 0, 17, 16),
+    ("NUM", 29, 40, 40, 30, 1),
+    ("NUM", 30, 1, 0, 30, 2),
+    ("DEU", 12, 32, 32, 13, 1),
+    ("DEU", 13, 1, 0, 13, 2),
+    ("DEU", 22, 30, 30, 23, 1),
+    ("DEU", 23, 1, 0, 23, 2),
+    ("DEU", 29, 1, 1, 28, 69),
+    ("DEU", 29, 2, 0, 29, 1),
+    ("1SA", 21, 1, 0, 21, 2),
+    ("1SA", 23, 29, 29, 24, 1),
+    ("1SA", 24, 1, 0, 24, 2),
+    ("2SA", 18, 33, 33, 19, 1),
+    ("2SA", 19, 1, 0, 19, 2),
+    ("1KI", 4, 21, 34, 5, 1),
+    ("1KI", 5, 1, 0, 5, 15),
+    ("1KI", 22, 44, 0, 22, 45),
+    ("2KI", 11, 21, 21, 12, 1),
+    ("2KI", 12, 1, 0, 12, 2),
+    ("1CH", 6, 1, 15, 5, 27),
+    ("1CH", 6, 16, 0, 6, 1),
+    ("1CH", 12, 5, 0, 12, 6),
+    ("2CH", 2, 1, 1, 1, 18),
+    ("2CH", 2, 2, 0, 2, 1),
+    ("2CH", 14, 1, 1, 13, 23),
+    ("2CH", 14, 2, 0, 14, 1),
+    ("NEH", 4, 1, 6, 3, 33),
+    ("NEH", 4, 7, 0, 4, 1),
+    ("NEH", 9, 38, 38, 10, 1),
+    ("NEH", 10, 1, 0, 10, 2),
+    ("JOB", 41, 1, 8, 40, 25),
+    ("JOB", 41, 9, 0, 41, 1),
+    ("ECC", 5, 1, 1, 4, 17),
+    ("ECC", 5, 2, 0, 5, 1),
+    ("SNG", 6, 13, 13, 7, 1),
+    ("SNG", 7, 1, 0, 7, 2),
+    ("ISA", 9, 1, 1, 8, 23),
+    ("ISA", 9, 2, 0, 9, 1),
+    ("ISA", 64, 1, 1, 63, 19),
+    ("ISA", 64, 2, 0, 64, 1),
+    ("JER", 9, 1, 1, 8, 23),
+    ("JER", 9, 2, 0, 9, 1),
+    ("EZK", 20, 45, 49, 21, 1),
+    ("EZK", 21, 1, 0, 21, 6),
+    ("DAN", 4, 1, 3, 3, 31),
+    ("DAN", 4, 4, 0, 4, 1),
+    ("DAN", 5, 31, 31, 6, 1),
+    ("DAN", 6, 1, 0, 6, 2),
+    ("HOS", 1, 10, 11, 2, 1),
+    ("HOS", 2, 1, 0, 2, 3),
+    ("HOS", 11, 12, 12, 12, 1),
+    ("HOS", 12, 1, 0, 12, 2),
+    ("HOS", 13, 16, 16, 14, 1),
+    ("HOS", 14, 1, 0, 14, 2),
+    ("JOL", 2, 28, 32, 3, 1),
+    ("JOL", 3, 1, 0, 4, 1),
+    ("JON", 1, 17, 17, 2, 1),
+    ("JON", 2, 1, 0, 2, 2),
+    ("MIC", 5, 1, 1, 4, 14),
+    ("MIC", 5, 2, 0, 5, 1),
+    ("NAM", 1, 15, 15, 2, 1),
+    ("NAM", 2, 1, 0, 2, 2),
+    ("ZEC", 1, 18, 21, 2, 1),
+    ("ZEC", 2, 1, 0, 2, 5),
+    ("MAL", 4, 1, 0, 3, 19),
+];
+
+/// English to Septuagint outside the Psalms
+const SEPTUAGINT: &[Shift] = &[("JOL", 2, 28, 32, 3, 1), ("JOL", 3, 1, 0, 4, 1)];
+
+/// English to Vulgate outside the Psalms
+const VULGATE: &[Shift] = &[("DAN", 4, 1, 3, 3, 98), ("DAN", 4, 4, 0, 4, 1)];
+
+/// English to Russian Synodal outside the Psalms
+const SYNODAL: &[Shift] = &[
+    ("JOB", 40, 1, 5, 39, 31),
+    ("JOB", 40, 6, 0, 40, 1),
+    ("JOB", 41, 1, 8, 40, 20),
+    ("JOB", 41, 9, 0, 41, 1),
+    ("SNG", 6, 13, 13, 7, 1),
+    ("SNG", 7, 1, 0, 7, 2),
+    ("DAN", 4, 1, 3, 3, 98),
+    ("DAN", 4, 4, 0, 4, 1),
+    ("ROM", 16, 25, 27, 14, 24),
+];
+
+/// English Psalms whose title is a numbered verse in Hebrew-based schemes,
+/// with how many verses the title takes
+#[rustfmt::skip]
+const PSALM_TITLES: &[(u32, u32)] = &[
+    (3, 1), (4, 1), (5, 1), (6, 1), (7, 1), (8, 1), (9, 1), (12, 1), (13, 1), (18, 1),
+    (19, 1), (20, 1), (21, 1), (22, 1), (30, 1), (31, 1), (34, 1), (36, 1), (38, 1),
+    (39, 1), (40, 1), (41, 1), (42, 1), (44, 1), (45, 1), (46, 1), (47, 1), (48, 1),
+    (49, 1), (51, 2), (52, 2), (53, 1), (54, 2), (55, 1), (56, 1), (57, 1), (58, 1),
+    (59, 1), (60, 2), (61, 1), (62, 1), (63, 1), (64, 1), (65, 1), (67, 1), (68, 1),
+    (69, 1), (70, 1), (75, 1), (76, 1), (77, 1), (80, 1), (81, 1), (83, 1), (84, 1),
+    (85, 1), (88, 1), (89, 1), (92, 1), (102, 1), (108, 1), (140, 1), (142, 1),
+];
+
+fn title_verses(psalm: u32) -> u32 {
+    PSALM_TITLES
+        .iter()
+        .find(|(number, _)| *number == psalm)
+        .map_or(0, |(_, verses)| *verses)
+}
+
+fn rule(shift: Shift) -> Rule {
+    let (book, chapter, first, last, to_chapter, to_first) = shift;
+    Rule {
+        book,
+        chapter,
+        first,
+        last: (last != 0).then_some(last),
+        to_chapter,
+        to_first,
+    }
+}
+
+/// Hebrew Psalms: same chapters, titles numbered
+fn hebrew_psalms() -> Vec<Rule> {
+    PSALM_TITLES
+        .iter()
+        .map(|&(psalm, offset)| rule(("PSA", psalm, 1, 0, psalm, 1 + offset)))
+        .collect()
+}
+
+/// Greek Psalms (Septuagint, Vulgate, Synodal): titles numbered as in
+/// Hebrew, with 9-10 and 114-115 joined and 116 and 147 split
+fn greek_psalms() -> Vec<Rule> {
+    let mut rules = Vec::new();
+    for psalm in 1..=150 {
+        let offset = title_verses(psalm);
+        let shifts: &[Shift] = match psalm {
+            9 => &[("PSA", 9, 1, 20, 9, 2)],
+            10 => &[("PSA", 10, 1, 0, 9, 22)],
+            114 => &[("PSA", 114, 1, 8, 113, 1)],
+            115 => &[("PSA", 115, 1, 0, 113, 9)],
+            116 => &[("PSA", 116, 1, 9, 114, 1), ("PSA", 116, 10, 0, 115, 1)],
+            147 => &[("PSA", 147, 1, 11, 146, 1), ("PSA", 147, 12, 0, 147, 1)],
+            11..=113 | 117..=146 => {
+                rules.push(rule(("PSA", psalm, 1, 0, psalm - 1, 1 + offset)));
+                continue;
+            }
+            _ if offset > 0 => {
+                rules.push(rule(("PSA", psalm, 1, 0, psalm, 1 + offset)));
+                continue;
+            }
+            _ => continue,
+        };
+        rules.extend(shifts.iter().copied().map(rule));
+    }
+    rules
+}
+
+fn rules(scheme: Scheme) -> &'static [Rule] {
+    static RULES: OnceLock<HashMap<Scheme, Vec<Rule>>> = OnceLock::new();
+    let all = RULES.get_or_init(|| {
+        let with = |shifts: &[Shift], psalms: Vec<Rule>| {
+            shifts
+                .iter()
+                .copied()
+                .map(rule)
+                .chain(psalms)
+                .collect::<Vec<_>>()
+        };
+        HashMap::from([
+            (Scheme::English, Vec::new()),
+            (Scheme::Hebrew, with(HEBREW, hebrew_psalms())),
+            (Scheme::Septuagint, with(SEPTUAGINT, greek_psalms())),
+            (Scheme::Vulgate, with(VULGATE, greek_psalms())),
+            (Scheme::Synodal, with(SYNODAL, greek_psalms())),
+        ])
+    });
+    &all[&scheme]
+}
+
+fn book_rules(scheme: Scheme, book: &str) -> impl Iterator<Item = &'static Rule> + '_ {
+    rules(scheme).iter().filter(move |rule| rule.book == book)
+}
+
+/// English `chapter:verse` in `scheme`
+fn from_english(scheme: Scheme, book: &str, chapter: u32, verse: u32) -> (u32, u32) {
+    match book_rules(scheme, book).find(|rule| rule.covers(chapter, verse)) {
+        Some(rule) => (rule.to_chapter, rule.to_first + (verse - rule.first)),
+        None => (chapter, verse),
+    }
+}
+
+/// `scheme`'s `chapter:verse` in English, if English has it
+fn to_english(scheme: Scheme, book: &str, chapter: u32, verse: u32) -> Option<(u32, u32)> {
+    if let Some(rule) = book_rules(scheme, book).find(|rule| rule.covers_target(chapter, verse)) {
+        return Some((rule.chapter, rule.first + (verse - rule.to_first)));
+    }
+    // Unmapped verses keep their number, unless English numbers that
+    // verse differently (so this one is a title or a split verse)
+    let moved = book_rules(scheme, book).any(|rule| rule.covers(chapter, verse));
+    (!moved).then_some((chapter, verse))
+}
+
+/// Where `chapter:verse` of `book` in the `from` scheme is in the `to` scheme
+pub fn map_verse(
+    from: Scheme,
+    to: Scheme,
+    book: &str,
+    chapter: u32,
+    verse: u32,
+) -> Option<(u32, u32)> {
+    if from == to {
+        return Some((chapter, verse));
+    }
+    let (chapter, verse) = to_english(from, book, chapter, verse)?;
+    Some(from_english(to, book, chapter, verse))
+}
+
+/// Whether `rules` map every verse of a chapter, from verse 1 on
+fn covers_whole_chapter(mut spans: Vec<(u32, Option<u32>)>) -> bool {
+    spans.sort();
+    let mut next = 1;
+    for (first, last) in spans {
+        if first != next {
+            return false;
+        }
+        match last {
+            Some(last) => next = last + 1,
+            None => return true,
+        }
+    }
+    false
+}
+
+/// English chapters holding verses of `scheme`'s `chapter`
+fn english_chapters(scheme: Scheme, book: &str, chapter: u32) -> Vec<u32> {
+    let targets: Vec<&Rule> = book_rules(scheme, book)
+        .filter(|rule| rule.to_chapter == chapter)
+        .collect();
+    let spans = targets
+        .iter()
+        .map(|rule| {
+            (
+                rule.to_first,
+                rule.last.map(|last| rule.to_first + last - rule.first),
+            )
+        })
+        .collect();
+
+    let mut chapters: Vec<u32> = targets.iter().map(|rule| rule.chapter).collect();
+    if !covers_whole_chapter(spans) {
+        chapters.push(chapter);
+    }
+    chapters
+}
+
+/// `scheme`'s chapters holding verses of English `chapter`
+fn scheme_chapters(scheme: Scheme, book: &str, chapter: u32) -> Vec<u32> {
+    let sources: Vec<&Rule> = book_rules(scheme, book)
+        .filter(|rule| rule.chapter == chapter)
+        .collect();
+    let spans = sources.iter().map(|rule| (rule.first, rule.last)).collect();
+
+    let mut chapters: Vec<u32> = sources.iter().map(|rule| rule.to_chapter).collect();
+    if !covers_whole_chapter(spans) {
+        chapters.push(chapter);
+    }
+    chapters
+}
+
+/// The chapters of `book` in the `to` scheme that hold the verses of
+/// `chapter` in the `from` scheme, in order
+pub fn chapters(from: Scheme, to: Scheme, book: &str, chapter: u32) -> Vec<u32> {
+    if from == to {
+        return vec![chapter];
+    }
+    let mut chapters: Vec<u32> = english_chapters(from, book, chapter)
+        .into_iter()
+        .flat_map(|english| scheme_chapters(to, book, english))
+        .collect();
+    chapters.sort_unstable();
+    chapters.dedup();
+    chapters
+}
+
+/// Which scheme each translation follows: configured per translation, or
+/// guessed from the translation's language
+#[derive(Debug, Clone, Default)]
+pub struct TranslationSchemes {
+    configured: HashMap<String, Scheme>,
+}
+
+impl TranslationSchemes {
+    pub fn new(configured: HashMap<String, Scheme>) -> Self {
+        Self { configured }
+    }
+
+    /// The scheme configured for `translation`, if any
+    pub fn configured(&self, translation: &str) -> Option<Scheme> {
+        self.configured.get(translation).copied()
+    }
+
+    pub fn scheme(&self, translation: &str, language: Option<&str>) -> Scheme {
+        self.configured(translation)
+            .or_else(|| language.map(Scheme::for_language))
+            .unwrap_or_default()
+    }
+}
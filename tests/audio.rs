@@ -0,0 +1,100 @@
+use thebible::api::bible_brain::{AudioChapter, VerseTimestamp};
+use thebible::api::models::{Chapter, Verse};
+use thebible::audio::{synchronize, SyncedVerse};
+
+fn text(verses: &[u32]) -> Chapter {
+    Chapter {
+        translation: "ENGESV".to_string(),
+        book: "JHN".to_string(),
+        chapter: 3,
+        verses: verses
+            .iter()
+            .map(|&verse| Verse {
+                verse,
+                text: format!("verse {}", verse),
+                footnotes: None,
+            })
+            .collect(),
+        footnotes: None,
+        content: Vec::new(),
+    }
+}
+
+fn audio(duration: Option<u32>, timestamps: &[(u32, f64)]) -> AudioChapter {
+    AudioChapter {
+        fileset_id: "ENGESVN2DA".to_string(),
+        book: "JHN".to_string(),
+        chapter: 3,
+        url: "https://example.org/JHN3.mp3".to_string(),
+        duration,
+        timestamps: timestamps
+            .iter()
+            .map(|&(verse, start)| VerseTimestamp { verse, start })
+            .collect(),
+    }
+}
+
+fn timeline(verses: &[SyncedVerse]) -> Vec<(u32, Option<f64>, Option<f64>, bool)> {
+    verses
+        .iter()
+        .map(|v| (v.verse, v.start, v.end, v.timed))
+        .collect()
+}
+
+#[test]
+fn verses_end_where_the_next_one_starts() {
+    let synced = synchronize(
+        &text(&[1, 2, 3]),
+        &audio(Some(40), &[(0, 0.0), (1, 4.5), (2, 12.0), (3, 25.0)]),
+    );
+    assert_eq!(
+        timeline(&synced),
+        vec![
+            (1, Some(4.5), Some(12.0), true),
+            (2, Some(12.0), Some(25.0), true),
+            (3, Some(25.0), Some(40.0), true),
+        ]
+    );
+    assert_eq!(synced[0].text, "verse 1");
+}
+
+#[test]
+fn partial_verses_count_from_their_first_part() {
+    // DBP times "2a" and "2b" separately; both arrive as verse 2, unsorted
+    let synced = synchronize(
+        &text(&[1, 2, 3]),
+        &audio(None, &[(3, 20.0), (2, 14.0), (1, 3.0), (2, 8.0)]),
+    );
+    assert_eq!(
+        timeline(&synced),
+        vec![
+            (1, Some(3.0), Some(8.0), true),
+            (2, Some(8.0), Some(20.0), true),
+            (3, Some(20.0), None, true),
+        ]
+    );
+}
+
+#[test]
+fn untimed_verses_are_kept_and_extra_timestamps_only_bound_others() {
+    // Verse 2 has no timestamp; verse 4 is not in the text
+    let synced = synchronize(
+        &text(&[1, 2, 3]),
+        &audio(Some(60), &[(1, 2.0), (3, 10.0), (4, 30.0)]),
+    );
+    assert_eq!(
+        timeline(&synced),
+        vec![
+            (1, Some(2.0), Some(10.0), true),
+            (2, None, None, false),
+            (3, Some(10.0), Some(30.0), true),
+        ]
+    );
+}
+
+#[test]
+fn audio_without_timestamps_leaves_every_verse_untimed() {
+    let synced = synchronize(&text(&[1, 2]), &audio(Some(60), &[]));
+    assert!(synced.iter().all(|v| !v.timed && v.start.is_none()));
+    assert_eq!(synced.len(), 2);
+}
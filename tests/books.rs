@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use thebible::books::{self, normalize_name, Testament};
+use thebible::reference::{parse_references, resolve_book};
+
+#[test]
+fn resolves_names_in_every_catalog_language() {
+    for name in [
+        "john", "Jn", "JHN", "Johannes", "Jean", "Juan", "João", "John",
+    ] {
+        assert_eq!(resolve_book(name), Ok("JHN"), "{}", name);
+    }
+    assert_eq!(resolve_book("Hiob"), Ok("JOB"));
+    assert_eq!(resolve_book("Handelinge"), Ok("ACT"));
+    assert_eq!(resolve_book("Apostelgeschichte"), Ok("ACT"));
+    assert_eq!(resolve_book("Hechos"), Ok("ACT"));
+    assert_eq!(resolve_book("Openbaring"), Ok("REV"));
+    assert_eq!(resolve_book("1 Korintiërs"), Ok("1CO"));
+    assert_eq!(resolve_book("2 Rois"), Ok("2KI"));
+    assert_eq!(resolve_book("Sprüche"), Ok("PRO"));
+}
+
+#[test]
+fn resolves_osis_codes_and_german_mose_names() {
+    assert_eq!(resolve_book("1Cor"), Ok("1CO"));
+    assert_eq!(resolve_book("Phlm"), Ok("PHM"));
+    assert_eq!(resolve_book("1. Mose"), Ok("GEN"));
+    assert_eq!(resolve_book("5. Mose"), Ok("DEU"));
+}
+
+#[test]
+fn prefixes_ignore_accents() {
+    assert_eq!(resolve_book("Genese"), Ok("GEN"));
+    assert_eq!(resolve_book("Romer"), Ok("ROM"));
+    assert_eq!(resolve_book("Ezech"), Ok("EZK"));
+}
+
+#[test]
+fn protestant_canon_wins_prefix_ties() {
+    assert_eq!(resolve_book("Est"), Ok("EST"));
+    assert_eq!(resolve_book("Psal"), Ok("PSA"));
+    assert_eq!(resolve_book("Tobit"), Ok("TOB"));
+    assert_eq!(resolve_book("1 Macc"), Ok("1MA"));
+}
+
+#[test]
+fn parses_references_with_localized_names() {
+    let ranges = parse_references("Johannes 3:16; Römer 8:28").unwrap();
+    assert_eq!(ranges[0].book, "JHN");
+    assert_eq!(ranges[1].book, "ROM");
+    assert_eq!(ranges[1].to_string(), "Romans 8:28");
+}
+
+#[test]
+fn exact_names_and_abbreviations_are_unambiguous() {
+    let mut owners: HashMap<String, &str> = HashMap::new();
+    for book in books::all() {
+        let keys = book
+            .names
+            .iter()
+            .map(|name| normalize_name(name))
+            .chain(book.abbreviations.iter().map(|a| a.to_string()))
+            .chain([book.usfm.to_lowercase(), book.osis.to_lowercase()]);
+        for key in keys {
+            let owner = owners.entry(key.clone()).or_insert(book.usfm);
+            assert_eq!(*owner, book.usfm, "{:?} names two books", key);
+        }
+    }
+}
+
+#[test]
+fn catalog_is_in_canonical_order() {
+    let all = books::all();
+    assert_eq!(all.len(), 91);
+    assert!(all
+        .iter()
+        .enumerate()
+        .all(|(i, book)| book.order as usize == i + 1));
+    assert_eq!(
+        all.iter().filter(|b| b.testament == Testament::Old).count(),
+        39
+    );
+    assert_eq!(
+        all.iter().filter(|b| b.testament == Testament::New).count(),
+        27
+    );
+    assert_eq!(
+        books::by_code("1cor").unwrap().localized_name("nl"),
+        Some("1 Korintiërs")
+    );
+}
@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use thebible::api::bible_brain::{BibleBrainClient, PageRequest};
+
+/// How many pages the mock's `/bibles` listing claims to have
+#[derive(Clone, Copy)]
+enum Pages {
+    Three,
+    Endless,
+}
+
+#[derive(Clone)]
+struct MockDbp {
+    hits: Arc<AtomicUsize>,
+    pages: Pages,
+}
+
+/// DBP rejects requests without the API key or version
+fn authorized(headers: &HeaderMap, query: &HashMap<String, String>) -> bool {
+    headers
+        .get("dbp-api-key")
+        .is_some_and(|key| key == "secret")
+        && query.get("v").map(String::as_str) == Some("4")
+}
+
+async fn bibles(
+    State(mock): State<MockDbp>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    mock.hits.fetch_add(1, Ordering::SeqCst);
+    if !authorized(&headers, &query) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let page: u64 = query["page"].parse().unwrap();
+    let total_pages = match mock.pages {
+        Pages::Three => 3,
+        Pages::Endless => page + 1,
+    };
+    Json(json!({
+        "data": [{ "abbr": format!("BIBLE{}", page), "name": format!("Bible {}", page) }],
+        "meta": {
+            "pagination": {
+                "total": total_pages,
+                "count": 1,
+                "per_page": query["limit"].parse::<u64>().unwrap(),
+                "current_page": page,
+                "total_pages": total_pages
+            }
+        }
+    }))
+    .into_response()
+}
+
+/// A single Bible, sent without DBP's `data` envelope
+async fn bible(Path(bible_id): Path<String>) -> Response {
+    if bible_id != "ENGESV" {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    Json(json!({
+        "abbr": "ENGESV",
+        "name": "English Standard Version",
+        "vname": "ESV",
+        "iso": "eng",
+        "filesets": {
+            "dbp-prod": [
+                { "id": "ENGESVO2DA", "type": "audio_drama", "size": "OT" },
+                { "id": "ENGESV", "type": "text_plain", "size": "C" }
+            ],
+            "dbp-vid": [
+                { "id": "ENGESVP2DV", "type": "video_stream", "size": "NTP" },
+                { "id": "ENGESV", "type": "text_plain", "size": "C" }
+            ]
+        }
+    }))
+    .into_response()
+}
+
+async fn audio_files(Path((fileset, book, chapter)): Path<(String, String, u32)>) -> Json<Value> {
+    let files = if chapter == 1 {
+        json!([{
+            "book_id": book,
+            "chapter_start": "1",
+            "path": format!("https://cdn.example/{}/{}1.mp3?Signature=abc", fileset, book),
+            "duration": 312
+        }])
+    } else {
+        json!([])
+    };
+    Json(json!({ "data": files }))
+}
+
+/// Timing data, out of order; only the drama fileset has any
+async fn timestamps(Path((fileset, _book, _chapter)): Path<(String, String, u32)>) -> Response {
+    if fileset != "ENGESVN2DA" {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    Json(json!({
+        "data": [
+            { "verse_start": "2", "timestamp": 14.5 },
+            { "verse_start": 1, "timestamp": 0.0 },
+            { "verse_start": "3b", "timestamp": 29.25 }
+        ]
+    }))
+    .into_response()
+}
+
+/// Serve `router` on an ephemeral local port and return its base URL
+async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn mock_dbp(pages: Pages) -> (BibleBrainClient, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let router = Router::new()
+        .route("/bibles", get(bibles))
+        .route("/bibles/:bible_id", get(bible))
+        .route("/bibles/filesets/:fileset/:book/:chapter", get(audio_files))
+        .route("/timestamps/:fileset/:book/:chapter", get(timestamps))
+        .with_state(MockDbp {
+            hits: hits.clone(),
+            pages,
+        });
+    let client = BibleBrainClient::new(serve(router).await, "secret".to_string());
+    (client, hits)
+}
+
+#[tokio::test]
+async fn unwraps_the_data_envelope_and_its_pagination() {
+    let (brain, _) = mock_dbp(Pages::Three).await;
+
+    let page = brain
+        .get_bibles(None, PageRequest { page: 2, limit: 10 })
+        .await
+        .unwrap();
+    assert_eq!(page.data.len(), 1);
+    assert_eq!(page.data[0].id, "BIBLE2");
+    let pagination = page.pagination.unwrap();
+    assert_eq!(
+        (
+            pagination.current_page,
+            pagination.total_pages,
+            pagination.per_page
+        ),
+        (2, 3, 10)
+    );
+
+    // A bare body is the data itself
+    let bible = brain.get_bible("ENGESV").await.unwrap();
+    assert_eq!(
+        (bible.name.as_str(), bible.name_local.as_deref()),
+        ("English Standard Version", Some("ESV"))
+    );
+    assert_eq!(
+        brain.get_bible("NONE").await.unwrap_err().code(),
+        "not_found"
+    );
+}
+
+#[tokio::test]
+async fn collects_every_page_of_a_listing() {
+    let (brain, hits) = mock_dbp(Pages::Three).await;
+
+    let bibles = brain.get_all_bibles().await.unwrap();
+    let ids: Vec<&str> = bibles.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["BIBLE1", "BIBLE2", "BIBLE3"]);
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn stops_collecting_after_fifty_pages() {
+    let (brain, hits) = mock_dbp(Pages::Endless).await;
+
+    let bibles = brain.get_all_bibles().await.unwrap();
+    assert_eq!(bibles.len(), 50);
+    assert_eq!(bibles[49].id, "BIBLE50");
+    assert_eq!(hits.load(Ordering::SeqCst), 50);
+}
+
+#[tokio::test]
+async fn lists_filesets_across_buckets_once_each() {
+    let (brain, _) = mock_dbp(Pages::Three).await;
+
+    let filesets = brain.get_filesets("ENGESV").await.unwrap();
+    let listed: Vec<(&str, &str)> = filesets
+        .iter()
+        .map(|f| (f.id.as_str(), f.fileset_type.as_str()))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("ENGESV", "text_plain"),
+            ("ENGESVO2DA", "audio_drama"),
+            ("ENGESVP2DV", "video_stream"),
+        ]
+    );
+    assert!(filesets[1].is_audio());
+    assert!(!filesets[2].is_audio());
+}
+
+#[tokio::test]
+async fn joins_chapter_audio_with_sorted_timestamps() {
+    let (brain, _) = mock_dbp(Pages::Three).await;
+
+    let audio = brain
+        .get_audio_chapter("ENGESVN2DA", "MRK", 1)
+        .await
+        .unwrap();
+    assert_eq!(
+        audio.url,
+        "https://cdn.example/ENGESVN2DA/MRK1.mp3?Signature=abc"
+    );
+    assert_eq!(audio.duration, Some(312));
+    let timestamps: Vec<(u32, f64)> = audio
+        .timestamps
+        .iter()
+        .map(|t| (t.verse, t.start))
+        .collect();
+    assert_eq!(timestamps, vec![(1, 0.0), (2, 14.5), (3, 29.25)]);
+
+    // Filesets without timing data still serve their audio
+    let audio = brain
+        .get_audio_chapter("ENGESVN1DA", "MRK", 1)
+        .await
+        .unwrap();
+    assert!(audio.timestamps.is_empty());
+
+    let error = brain
+        .get_audio_chapter("ENGESVN2DA", "MRK", 2)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "not_found");
+}
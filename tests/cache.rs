@@ -0,0 +1,98 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use thebible::api::cache::{CacheEndpoint, ResponseCache};
+use thebible::config::CacheConfig;
+
+fn body(bytes: usize) -> Arc<str> {
+    "x".repeat(bytes).into()
+}
+
+fn cache(max_bytes: usize) -> ResponseCache {
+    ResponseCache::new(CacheConfig {
+        max_bytes,
+        max_entry_bytes: 40,
+        chapter_ttl_secs: 60,
+        commentary_ttl_secs: 1,
+        books_ttl_secs: 0,
+        ..CacheConfig::default()
+    })
+}
+
+#[test]
+fn evicts_least_recently_used_entries_beyond_the_byte_budget() {
+    let cache = cache(100);
+    cache.insert(CacheEndpoint::Chapter, "a", body(40));
+    cache.insert(CacheEndpoint::Chapter, "b", body(40));
+    // Reading "a" makes "b" the least recently used
+    assert!(cache.get("a").is_some());
+
+    cache.insert(CacheEndpoint::Chapter, "c", body(30));
+    assert!(cache.get("b").is_none());
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("c").is_some());
+
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.bytes, stats.evictions), (2, 70, 1));
+    assert_eq!((stats.hits, stats.misses), (3, 1));
+}
+
+#[test]
+fn replacing_an_entry_keeps_the_byte_count_exact() {
+    let cache = cache(100);
+    cache.insert(CacheEndpoint::Chapter, "a", body(40));
+    cache.insert(CacheEndpoint::Chapter, "a", body(10));
+
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.bytes, stats.evictions), (1, 10, 0));
+}
+
+#[test]
+fn skips_oversized_bodies_and_disabled_endpoints() {
+    let cache = cache(100);
+    cache.insert(CacheEndpoint::Chapter, "big", body(41));
+    cache.insert(CacheEndpoint::Books, "books", body(10));
+
+    assert!(cache.get("big").is_none());
+    assert!(cache.get("books").is_none());
+    assert_eq!(cache.stats().entries, 0);
+}
+
+#[test]
+fn entries_expire_after_their_endpoint_ttl() {
+    let cache = cache(100);
+    cache.insert(CacheEndpoint::Commentary, "short", body(10));
+    cache.insert(CacheEndpoint::Chapter, "long", body(10));
+    assert!(cache.get("short").is_some());
+
+    std::thread::sleep(Duration::from_millis(1100));
+    assert!(cache.get("short").is_none());
+    assert!(cache.get("long").is_some());
+
+    // The expired entry no longer counts against the budget
+    let stats = cache.stats();
+    assert_eq!((stats.entries, stats.bytes), (1, 10));
+}
+
+#[test]
+fn datasets_and_profiles_keep_their_own_lifetimes() {
+    // Disabling the Bible endpoints leaves the other families cached
+    let cache = ResponseCache::new(CacheConfig {
+        translations_ttl_secs: 0,
+        books_ttl_secs: 0,
+        chapter_ttl_secs: 0,
+        commentaries_ttl_secs: 0,
+        commentary_ttl_secs: 0,
+        datasets_ttl_secs: 0,
+        ..CacheConfig::default()
+    });
+    cache.insert(CacheEndpoint::CommentaryBooks, "commentary books", body(10));
+    cache.insert(CacheEndpoint::Profiles, "profiles", body(10));
+    cache.insert(CacheEndpoint::Datasets, "datasets", body(10));
+    cache.insert(CacheEndpoint::DatasetChapter, "dataset chapter", body(10));
+
+    assert!(cache.get("commentary books").is_some());
+    assert!(cache.get("profiles").is_some());
+    assert!(cache.get("datasets").is_none());
+    assert!(cache.get("dataset chapter").is_some());
+}
@@ -0,0 +1,58 @@
+use thebible::api::models::Book;
+use thebible::books;
+use thebible::canons;
+
+fn book(id: &str) -> Book {
+    Book {
+        id: id.to_string(),
+        name: id.to_string(),
+        common_name: None,
+        order: None,
+        number_of_chapters: None,
+    }
+}
+
+#[test]
+fn canons_have_their_expected_sizes() {
+    let sizes: Vec<(&str, usize)> = canons::all()
+        .iter()
+        .map(|canon| (canon.id, canon.books.len()))
+        .collect();
+    assert_eq!(sizes[0], ("protestant", 66));
+    assert!(sizes.iter().all(|(_, size)| *size >= 66));
+}
+
+#[test]
+fn every_canon_book_is_in_the_catalog() {
+    for canon in canons::all() {
+        for code in canon.books {
+            assert!(books::by_code(code).is_some(), "{} in {}", code, canon.id);
+        }
+    }
+}
+
+#[test]
+fn arrange_filters_and_reorders() {
+    let catholic = canons::by_id("Catholic").unwrap();
+    let arranged = catholic.arrange(vec![book("MAT"), book("TOB"), book("GEN"), book("3MA")]);
+    let ids: Vec<(&str, Option<u32>)> = arranged
+        .iter()
+        .map(|book| (book.id.as_str(), book.order))
+        .collect();
+    assert_eq!(
+        ids,
+        vec![("GEN", Some(1)), ("TOB", Some(2)), ("MAT", Some(3))]
+    );
+
+    let orthodox = canons::by_id("orthodox").unwrap();
+    assert!(orthodox.position("HOS") < orthodox.position("ISA"));
+}
+
+#[test]
+fn infers_the_narrowest_canon() {
+    assert_eq!(canons::infer(["GEN", "MAT"]).unwrap().id, "protestant");
+    assert_eq!(canons::infer(["GEN", "SIR"]).unwrap().id, "catholic");
+    assert_eq!(canons::infer(["PS2", "3MA"]).unwrap().id, "orthodox");
+    assert_eq!(canons::infer(["ENO", "JUB"]).unwrap().id, "ethiopian");
+    assert!(canons::infer(["ENO", "4MA"]).is_none());
+}
@@ -0,0 +1,278 @@
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use thebible::api::chain::ProviderChain;
+use thebible::api::models::{Book, Chapter, Translation};
+use thebible::api::provider::BibleProvider;
+use thebible::api::provider::Served;
+use thebible::error::{AppError, Result};
+
+/// How a scripted provider answers chapter requests
+#[derive(Clone, Copy)]
+enum Answer {
+    Chapter,
+    NotFound,
+    Down,
+    Hang,
+}
+
+struct Scripted {
+    name: &'static str,
+    answer: Answer,
+    calls: AtomicUsize,
+}
+
+fn scripted(name: &'static str, answer: Answer) -> Arc<Scripted> {
+    Arc::new(Scripted {
+        name,
+        answer,
+        calls: AtomicUsize::new(0),
+    })
+}
+
+#[async_trait]
+impl BibleProvider for Scripted {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_books(&self, _translation: &str) -> Result<Vec<Book>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match self.answer {
+            Answer::Chapter => Ok(Chapter {
+                translation: translation.to_string(),
+                book: book.to_string(),
+                chapter,
+                verses: Vec::new(),
+                footnotes: None,
+                content: Vec::new(),
+            }),
+            Answer::NotFound => Err(AppError::NotFound(format!("{} has no {}", self.name, book))),
+            Answer::Down => Err(AppError::UpstreamUnavailable(format!(
+                "{} is down",
+                self.name
+            ))),
+            Answer::Hang => {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Err(AppError::UpstreamUnavailable("too late".to_string()))
+            }
+        }
+    }
+}
+
+fn chain(links: &[&Arc<Scripted>]) -> ProviderChain {
+    let links = links
+        .iter()
+        .map(|link| (*link).clone() as Arc<dyn BibleProvider>)
+        .collect();
+    ProviderChain::new(links, Duration::from_millis(100))
+}
+
+/// A provider whose translation list is `ids`, or that is down when `None`
+struct Catalog {
+    name: &'static str,
+    ids: Option<&'static [&'static str]>,
+    snapshot: bool,
+}
+
+fn catalog(name: &'static str, ids: Option<&'static [&'static str]>) -> Arc<dyn BibleProvider> {
+    Arc::new(Catalog {
+        name,
+        ids,
+        snapshot: false,
+    })
+}
+
+#[async_trait]
+impl BibleProvider for Catalog {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn is_snapshot(&self) -> bool {
+        self.snapshot
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        let ids = self
+            .ids
+            .ok_or_else(|| AppError::UpstreamUnavailable(format!("{} is down", self.name)))?;
+        Ok(ids
+            .iter()
+            .map(|id| Translation {
+                id: id.to_string(),
+                name: format!("{} from {}", id, self.name),
+                language: None,
+                english_name: None,
+                language_name: None,
+                language_english_name: None,
+                short_name: None,
+                description: None,
+                number_of_books: None,
+                canon: None,
+            })
+            .collect())
+    }
+
+    async fn get_books(&self, _translation: &str) -> Result<Vec<Book>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_chapter(&self, translation: &str, _book: &str, _chapter: u32) -> Result<Chapter> {
+        Err(AppError::NotFound(translation.to_string()))
+    }
+}
+
+fn listed(served: &Served<Vec<Translation>>) -> Vec<(&str, &str)> {
+    served
+        .value
+        .iter()
+        .map(|t| (t.id.as_str(), t.name.as_str()))
+        .collect()
+}
+
+fn calls(link: &Scripted) -> usize {
+    link.calls.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn the_first_provider_that_answers_serves_the_request() {
+    let down = scripted("mirror", Answer::Down);
+    let helloao = scripted("helloao", Answer::Chapter);
+    let brain = scripted("bible_brain", Answer::Chapter);
+
+    let served = chain(&[&down, &helloao, &brain])
+        .serve_chapter("BSB", "GEN", 1)
+        .await
+        .unwrap();
+    assert_eq!(served.provider, "helloao");
+    assert_eq!((calls(&down), calls(&helloao), calls(&brain)), (1, 1, 0));
+}
+
+#[tokio::test]
+async fn a_provider_past_the_timeout_is_skipped() {
+    let slow = scripted("helloao", Answer::Hang);
+    let brain = scripted("bible_brain", Answer::Chapter);
+
+    let started = Instant::now();
+    let served = chain(&[&slow, &brain])
+        .serve_chapter("BSB", "GEN", 1)
+        .await
+        .unwrap();
+    assert_eq!(served.provider, "bible_brain");
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn not_found_only_when_every_provider_agrees() {
+    let missing = scripted("mirror", Answer::NotFound);
+    let also_missing = scripted("helloao", Answer::NotFound);
+    let down = scripted("bible_brain", Answer::Down);
+
+    let error = chain(&[&missing, &also_missing])
+        .get_chapter("BSB", "XYZ", 1)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "not_found");
+
+    // A failing provider might have had it, so its failure is reported
+    let error = chain(&[&missing, &down])
+        .get_chapter("BSB", "XYZ", 1)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "upstream_unavailable");
+    let detail = error.detail();
+    assert!(
+        detail.contains("mirror: ") && detail.contains("bible_brain: "),
+        "{}",
+        detail
+    );
+}
+
+#[tokio::test]
+async fn timeouts_are_reported_in_chain_order() {
+    let slow = scripted("helloao", Answer::Hang);
+    let down = scripted("bible_brain", Answer::Down);
+
+    let error = chain(&[&slow, &down])
+        .get_chapter("BSB", "GEN", 1)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "upstream_timeout");
+    let detail = error.detail();
+    let timed_out = detail.find("helloao: ").unwrap();
+    assert!(
+        timed_out < detail.find("bible_brain: ").unwrap(),
+        "{}",
+        detail
+    );
+}
+
+#[tokio::test]
+async fn translation_lists_are_merged_across_live_providers() {
+    let mirror = Arc::new(Catalog {
+        name: "mirror",
+        ids: Some(&["BSB", "OLD"]),
+        snapshot: true,
+    });
+    let links = vec![
+        mirror as Arc<dyn BibleProvider>,
+        catalog("helloao", Some(&["BSB", "KJV"])),
+        catalog("down", None),
+        catalog("bible_brain", Some(&["KJV", "ENGESV"])),
+    ];
+
+    let served = ProviderChain::new(links, Duration::from_millis(100))
+        .serve_translations()
+        .await
+        .unwrap();
+    assert_eq!(served.provider, "helloao,bible_brain");
+    // A translation both providers list keeps the first provider's entry; the
+    // snapshot is not consulted while a live provider answers
+    assert_eq!(
+        listed(&served),
+        vec![
+            ("BSB", "BSB from helloao"),
+            ("KJV", "KJV from helloao"),
+            ("ENGESV", "ENGESV from bible_brain"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn snapshots_list_translations_when_no_live_provider_answers() {
+    let mirror = Arc::new(Catalog {
+        name: "mirror",
+        ids: Some(&["BSB"]),
+        snapshot: true,
+    });
+    let links = vec![mirror as Arc<dyn BibleProvider>, catalog("helloao", None)];
+    let served = ProviderChain::new(links, Duration::from_millis(100))
+        .serve_translations()
+        .await
+        .unwrap();
+    assert_eq!(served.provider, "mirror");
+    assert_eq!(listed(&served), vec![("BSB", "BSB from mirror")]);
+
+    let links = vec![catalog("helloao", None), catalog("bible_brain", None)];
+    let error = ProviderChain::new(links, Duration::from_millis(100))
+        .get_translations()
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "upstream_unavailable");
+    assert!(
+        error.detail().contains("bible_brain: "),
+        "{}",
+        error.detail()
+    );
+}
@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use axum::extract::{Query, State};
+use axum::Json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use thebible::api::models::{Book, Chapter, Translation, Verse};
+use thebible::api::provider::BibleProvider;
+use thebible::api::HelloAOBibleClient;
+use thebible::crossrefs::{CrossRefIndex, CrossRefs};
+use thebible::error::{AppError, Result};
+use thebible::handlers::{self, CrossRefQuery};
+use thebible::reference::parse_references;
+use thebible::state::AppState;
+
+const DATA: &str = "From Verse\tTo Verse\tVotes\t#www.openbible.info CC-BY 2024-01-01
+Gen.1.1\tHeb.11.3\t57
+Gen.1.1\tJohn.1.1-John.1.3\t336
+Gen.1.2\tJohn.1.1-John.1.3\t12
+Gen.1.2\tGen.1.1\t40
+Gen.1.3\tPs.33.6\t-2
+John.3.16\tRom.5.8\t300
+";
+
+#[test]
+fn ranks_and_merges_by_target() {
+    let index = CrossRefIndex::parse(DATA, "test.txt").unwrap();
+    assert_eq!(index.len(), 6);
+
+    let found = index.lookup(&parse_references("Gen 1:1-2").unwrap(), i64::MIN);
+    let ranked: Vec<(&str, i64)> = found
+        .iter()
+        .map(|c| (c.reference.as_str(), c.votes))
+        .collect();
+    // Gen 1:1 is inside the requested passage, so it is left out
+    assert_eq!(ranked, vec![("John 1:1-3", 348), ("Hebrews 11:3", 57)]);
+    assert_eq!(found[0].from.len(), 2);
+}
+
+#[test]
+fn min_votes_filters_weak_references() {
+    let index = CrossRefIndex::parse(DATA, "test.txt").unwrap();
+    let ranges = parse_references("Gen 1").unwrap();
+    assert_eq!(index.lookup(&ranges, i64::MIN).len(), 3);
+    assert_eq!(index.lookup(&ranges, 0).len(), 2);
+}
+
+#[test]
+fn votes_are_optional() {
+    let index = CrossRefIndex::parse("Rom.8.28\tGen.50.20\n", "tsk.txt").unwrap();
+    let found = index.lookup(&parse_references("Rom 8:28").unwrap(), 0);
+    assert_eq!(found[0].votes, 1);
+}
+
+#[test]
+fn errors_name_the_file_and_line() {
+    let error = CrossRefIndex::parse("Gen.1.1\tHeb.11.3\t5\nGen.1\tHeb.11.3\t5\n", "refs.txt")
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("refs.txt:2:"), "{}", error);
+
+    let error = CrossRefIndex::parse("Gen.1.1\tXyz.1.1\t5\n", "refs.txt")
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("unknown book"), "{}", error);
+}
+
+struct Counting {
+    chapters: AtomicUsize,
+}
+
+#[async_trait]
+impl BibleProvider for Counting {
+    fn name(&self) -> &str {
+        "helloao"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(vec![Translation {
+            id: "BSB".to_string(),
+            name: "Berean Standard Bible".to_string(),
+            language: Some("eng".to_string()),
+            english_name: None,
+            language_name: None,
+            language_english_name: None,
+            short_name: None,
+            description: None,
+            number_of_books: None,
+            canon: None,
+        }])
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        Err(AppError::NotFound(format!("no books for {}", translation)))
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        self.chapters.fetch_add(1, Ordering::SeqCst);
+        Ok(Chapter {
+            translation: translation.to_string(),
+            book: book.to_string(),
+            chapter,
+            verses: (1..=2)
+                .map(|verse| Verse {
+                    verse,
+                    text: format!("{} {}:{}", book, chapter, verse),
+                    footnotes: None,
+                })
+                .collect(),
+            footnotes: None,
+            content: Vec::new(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn only_the_top_cross_references_get_text() {
+    // Genesis 1:1 links to the first verse of 120 Psalms
+    let data: String = (1..=120)
+        .map(|psalm| format!("Gen.1.1\tPs.{}.1\t{}\n", psalm, 1000 - psalm))
+        .collect();
+    let path = std::env::temp_dir().join(format!("thebible-crossrefs-{}.txt", std::process::id()));
+    std::fs::write(&path, data).unwrap();
+
+    let provider = Arc::new(Counting {
+        chapters: AtomicUsize::new(0),
+    });
+    let helloao = HelloAOBibleClient::new("http://127.0.0.1:9".to_string());
+    let mut state = AppState::new(provider.clone(), helloao);
+    state.crossrefs = Arc::new(CrossRefs::new(path.to_str().unwrap()));
+
+    let query = Query(CrossRefQuery {
+        reference: "Gen 1:1".to_string(),
+        translation: Some("BSB".to_string()),
+        limit: Some(500),
+        min_votes: None,
+    });
+    let Json(results) = handlers::get_crossrefs(State(state), query).await.unwrap();
+
+    assert_eq!(results.cross_references.len(), 120);
+    let with_text = results
+        .cross_references
+        .iter()
+        .filter(|c| c.verses.is_some())
+        .count();
+    assert_eq!(with_text, 50);
+    assert!(results.cross_references[0].verses.is_some());
+    assert_eq!(provider.chapters.load(Ordering::SeqCst), 50);
+
+    std::fs::remove_file(path).ok();
+}
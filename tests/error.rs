@@ -0,0 +1,131 @@
+use axum::http::StatusCode;
+use reqwest::StatusCode as UpstreamStatus;
+
+use thebible::error::AppError;
+
+fn message() -> String {
+    "GEN 51".to_string()
+}
+
+#[test]
+fn every_code_maps_to_its_status() {
+    let serialization = serde_json::from_str::<u32>("{").unwrap_err();
+    let cases = [
+        (AppError::InvalidInput(message()), "invalid_input", 400),
+        (AppError::NotFound(message()), "not_found", 404),
+        (AppError::NotConfigured(message()), "not_configured", 503),
+        (
+            AppError::UpstreamUnavailable(message()),
+            "upstream_unavailable",
+            502,
+        ),
+        (
+            AppError::UpstreamTimeout(message()),
+            "upstream_timeout",
+            504,
+        ),
+        (
+            AppError::UpstreamSchemaChanged(message()),
+            "upstream_schema_changed",
+            502,
+        ),
+        (
+            AppError::RateLimited {
+                message: message(),
+                retry_after_secs: None,
+            },
+            "rate_limited",
+            429,
+        ),
+        (
+            AppError::CircuitOpen {
+                host: "bible.helloao.org".to_string(),
+                retry_after_secs: 30,
+            },
+            "circuit_open",
+            503,
+        ),
+        (
+            AppError::Serialization(serialization),
+            "serialization_error",
+            500,
+        ),
+        (AppError::Data(message()), "data_error", 500),
+        (
+            AppError::Internal(anyhow::anyhow!(message())),
+            "internal_error",
+            500,
+        ),
+    ];
+
+    for (error, code, status) in cases {
+        assert_eq!(error.code(), code, "{}", error);
+        assert_eq!(error.status().as_u16(), status, "{}", error);
+    }
+}
+
+#[test]
+fn retry_hints_come_from_rate_limits_and_open_circuits() {
+    let limited = AppError::RateLimited {
+        message: message(),
+        retry_after_secs: Some(7),
+    };
+    let open = AppError::CircuitOpen {
+        host: "bible.helloao.org".to_string(),
+        retry_after_secs: 30,
+    };
+    assert_eq!(limited.retry_after_secs(), Some(7));
+    assert_eq!(open.retry_after_secs(), Some(30));
+    assert_eq!(
+        AppError::UpstreamTimeout(message()).retry_after_secs(),
+        None
+    );
+}
+
+#[test]
+fn upstream_statuses_are_classified() {
+    let classify = |status: u16| {
+        let status = UpstreamStatus::from_u16(status).unwrap();
+        AppError::from_upstream_status(status, "chapter", "", Some(3))
+    };
+
+    for (upstream, code, status) in [
+        (404, "not_found", StatusCode::NOT_FOUND),
+        (410, "not_found", StatusCode::NOT_FOUND),
+        (429, "rate_limited", StatusCode::TOO_MANY_REQUESTS),
+        (408, "upstream_timeout", StatusCode::GATEWAY_TIMEOUT),
+        (504, "upstream_timeout", StatusCode::GATEWAY_TIMEOUT),
+        (500, "upstream_unavailable", StatusCode::BAD_GATEWAY),
+        (403, "upstream_unavailable", StatusCode::BAD_GATEWAY),
+    ] {
+        let error = classify(upstream);
+        assert_eq!(
+            (error.code(), error.status()),
+            (code, status),
+            "{}",
+            upstream
+        );
+    }
+    assert_eq!(classify(429).retry_after_secs(), Some(3));
+}
+
+#[test]
+fn rewording_an_error_keeps_its_code() {
+    let internal = AppError::Internal(anyhow::anyhow!("disk full"));
+    let errors = [
+        AppError::NotFound(message()),
+        AppError::UpstreamTimeout(message()),
+        AppError::CircuitOpen {
+            host: "bible.helloao.org".to_string(),
+            retry_after_secs: 30,
+        },
+        AppError::Data(message()),
+        internal,
+    ];
+
+    for error in errors {
+        let reworded = error.with_message("No provider could serve GEN 51".to_string());
+        assert_eq!(reworded.code(), error.code());
+        assert_eq!(reworded.status(), error.status());
+    }
+}
@@ -0,0 +1,361 @@
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use thebible::api::chain::OverlayProvider;
+use thebible::api::files::{self, FileProvider};
+use thebible::api::models::{Book, Chapter, ContentNode, Translation, VerseSegment};
+use thebible::api::provider::BibleProvider;
+use thebible::error::{AppError, Result};
+
+const JOHN_USFM: &str = r"\id JHN Afrikaans 1953
+\h Johannes
+\mt1 Die Evangelie volgens Johannes
+\c 3
+\s1 Jesus en Nikodemus
+\p
+\v 16 Want so lief het God die wêreld\f + \fr 3:16 \ft Of: \fq eniggebore.\f* gehad,
+\v 17 \wj Want God het sy Seun nie\wj* gestuur nie.
+";
+
+const PSALM_USX: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<usx version="3.0">
+  <book code="PSA" style="id">World English Bible</book>
+  <para style="h">Psalms</para>
+  <chapter number="23" style="c" sid="PSA 23"/>
+  <para style="d">A Psalm by David.</para>
+  <para style="q1"><verse number="1" style="v" sid="PSA 23:1"/>Yahweh is my shepherd:</para>
+  <para style="q2">I shall lack nothing.<note caller="+" style="f"><char style="fr">23:1 </char><char style="ft">Or, want.</char></note><verse eid="PSA 23:1"/></para>
+  <para style="q1"><verse number="2" style="v" sid="PSA 23:2"/>He makes me lie down.<verse eid="PSA 23:2"/></para>
+  <chapter eid="PSA 23"/>
+</usx>
+"#;
+
+const METADATA: &str = r#"<DBLMetadata>
+  <identification><name>World English Bible</name><abbreviation>WEB</abbreviation></identification>
+  <language><iso>eng</iso><name>English</name></language>
+</DBLMetadata>
+"#;
+
+const GENESIS_OSIS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osis xmlns="http://www.bibletechnologies.net/2003/OSIS/namespace">
+<osisText osisIDWork="KJV" xml:lang="en">
+<header><work osisWork="KJV"><title>King James Version</title></work></header>
+<div type="book" osisID="Gen">
+<title type="main">GENESIS</title>
+<chapter osisID="Gen.1" sID="Gen.1"/>
+<title>The Creation</title>
+<verse osisID="Gen.1.1" sID="Gen.1.1"/>In the beginning God created the heaven and the earth.<verse eID="Gen.1.1"/>
+<verse osisID="Gen.1.2" sID="Gen.1.2"/>And the earth was without form<note type="translation">Or, waste.</note>.<verse eID="Gen.1.2"/>
+<chapter eID="Gen.1"/>
+</div>
+</osisText>
+</osis>
+"#;
+
+const ZEFANIA: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<XMLBIBLE biblename="Afrikaans 1933">
+<INFORMATION><title>Afrikaans 1933/1953</title><language>AFR</language></INFORMATION>
+<BIBLEBOOK bnumber="43">
+<CHAPTER cnumber="1">
+<CAPTION>Die Woord</CAPTION>
+<VERS vnumber="1">In die begin was die Woord<NOTE>Gr. Logos</NOTE>, en die Woord was by God.</VERS>
+<VERS vnumber="2">Hy was in die begin by God.</VERS>
+</CHAPTER>
+</BIBLEBOOK>
+</XMLBIBLE>
+"#;
+
+fn fixtures() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("thebible-files-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("afr_usfm")).unwrap();
+    std::fs::create_dir_all(dir.join("eng_web/release/USX_1")).unwrap();
+    std::fs::write(dir.join("afr_usfm/44JHNAFR.SFM"), JOHN_USFM).unwrap();
+    std::fs::write(
+        dir.join("afr_usfm/translation.json"),
+        r#"{"name": "Afrikaans 1953", "language": "afr"}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("eng_web/release/USX_1/PSA.usx"), PSALM_USX).unwrap();
+    std::fs::write(dir.join("eng_web/metadata.xml"), METADATA).unwrap();
+    std::fs::write(dir.join("eng_kjv.xml"), GENESIS_OSIS).unwrap();
+    std::fs::write(dir.join("afr33.xml"), ZEFANIA).unwrap();
+    std::fs::write(dir.join("README.txt"), "not a translation").unwrap();
+    dir
+}
+
+fn footnote_ids(chapter: &Chapter, verse: u32) -> Vec<String> {
+    chapter
+        .verses
+        .iter()
+        .find(|v| v.verse == verse)
+        .and_then(|v| v.footnotes.clone())
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn imports_every_format_with_headings_and_footnotes() {
+    let dir = fixtures();
+    let provider = FileProvider::load(dir.to_str().unwrap()).unwrap();
+
+    let translations = provider.get_translations().await.unwrap();
+    let ids: Vec<&str> = translations.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, vec!["afr33", "afr_usfm", "eng_kjv", "eng_web"]);
+    assert_eq!(translations[0].name, "Afrikaans 1933/1953");
+    assert_eq!(translations[0].language.as_deref(), Some("afr"));
+    assert_eq!(translations[3].short_name.as_deref(), Some("WEB"));
+    assert_eq!(translations[2].canon.as_deref(), Some("protestant"));
+
+    // USFM: heading, footnote without its reference, words of Jesus
+    let john = provider.get_chapter("afr_usfm", "JHN", 3).await.unwrap();
+    assert_eq!(
+        john.content[0],
+        ContentNode::Heading {
+            text: "Jesus en Nikodemus".to_string()
+        }
+    );
+    assert_eq!(
+        john.verses[0].text,
+        "Want so lief het God die wêreld gehad,"
+    );
+    let note = &john.footnotes.as_ref().unwrap()[0];
+    assert_eq!(
+        (note.text.as_str(), note.verse),
+        ("Of: eniggebore.", Some(16))
+    );
+    assert_eq!(footnote_ids(&john, 16), vec![note.id.clone()]);
+    let ContentNode::Verse { content, .. } = &john.content[2] else {
+        panic!("expected verse 17");
+    };
+    assert!(matches!(
+        &content[0],
+        VerseSegment::WordsOfJesus { text, .. } if text == "Want God het sy Seun nie"
+    ));
+    let books = provider.get_books("afr_usfm").await.unwrap();
+    assert_eq!(books[0].name, "Johannes");
+
+    // USX: Psalm title, poetry levels and a note
+    let psalm = provider.get_chapter("eng_web", "PSA", 23).await.unwrap();
+    assert!(matches!(
+        &psalm.content[0],
+        ContentNode::HebrewSubtitle { .. }
+    ));
+    assert_eq!(
+        psalm.verses[0].text,
+        "Yahweh is my shepherd: I shall lack nothing."
+    );
+    assert_eq!(footnote_ids(&psalm, 1).len(), 1);
+    let ContentNode::Verse { content, .. } = &psalm.content[1] else {
+        panic!("expected verse 1");
+    };
+    assert_eq!(content[1], VerseSegment::LineBreak);
+    assert!(matches!(
+        &content[2],
+        VerseSegment::Text { poem: Some(2), .. }
+    ));
+
+    // OSIS milestones
+    let genesis = provider.get_chapter("eng_kjv", "GEN", 1).await.unwrap();
+    assert_eq!(genesis.translation, "eng_kjv");
+    assert!(matches!(&genesis.content[0], ContentNode::Heading { text } if text == "The Creation"));
+    assert_eq!(genesis.verses[1].text, "And the earth was without form.");
+    assert_eq!(genesis.footnotes.as_ref().unwrap()[0].text, "Or, waste.");
+
+    // Zefania, with the book found by its number
+    let zefania = provider.get_chapter("afr33", "JHN", 1).await.unwrap();
+    assert_eq!(zefania.verses.len(), 2);
+    assert_eq!(footnote_ids(&zefania, 1).len(), 1);
+
+    let error = provider.get_chapter("afr33", "JHN", 2).await.unwrap_err();
+    assert_eq!(error.code(), "not_found");
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn structural_errors_name_the_file_and_line() {
+    let unclosed = "\\id JHN\n\\c 1\n\\v 1 Text\\f + \\ft note\n\\v 2 More\n";
+    let error = files::parse_usfm(unclosed, "JHN.usfm").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("JHN.usfm:3: footnote is not closed"),
+        "{}",
+        error
+    );
+
+    let early = "\\id JHN\n\\v 1 Text\n";
+    let error = files::parse_usfm(early, "JHN.usfm").unwrap_err();
+    let expected = "JHN.usfm:2: verse 1 before the first chapter";
+    assert!(error.to_string().contains(expected), "{}", error);
+
+    let error = files::parse_usfm("\\id XYZ\n", "x.usfm").unwrap_err();
+    assert!(
+        error.to_string().contains("x.usfm:1: unknown book code"),
+        "{}",
+        error
+    );
+
+    let repeated = "\\id JHN\n\\c 1\n\\v 1 a\n\\c 1\n";
+    let error = files::parse_usfm(repeated, "JHN.usfm").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("JHN.usfm:4: chapter 1 appears twice"),
+        "{}",
+        error
+    );
+
+    let zefania = "<XMLBIBLE>\n<BIBLEBOOK bnumber=\"1\">\n<CHAPTER cnumber=\"1\">\n\
+                   <VERS vnumber=\"x\">Text</VERS>\n</CHAPTER>\n</BIBLEBOOK>\n</XMLBIBLE>";
+    let error = files::parse_xml(zefania, "bible.xml").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("bible.xml:4: invalid verse number"),
+        "{}",
+        error
+    );
+
+    let error = files::parse_xml("<html>\n</html>", "page.xml").unwrap_err();
+    assert!(
+        error.to_string().contains("page.xml:1: expected an OSIS"),
+        "{}",
+        error
+    );
+
+    let error = files::parse_usx("<usx>\n<book code=\"GEN\">\n</usx>", "GEN.usx").unwrap_err();
+    assert!(error.to_string().contains("GEN.usx:3:"), "{}", error);
+}
+
+struct Upstream;
+
+#[async_trait]
+impl BibleProvider for Upstream {
+    fn name(&self) -> &str {
+        "helloao"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(["BSB", "afr33"]
+            .into_iter()
+            .map(|id| Translation {
+                id: id.to_string(),
+                name: format!("Upstream {}", id),
+                language: None,
+                english_name: None,
+                language_name: None,
+                language_english_name: None,
+                short_name: None,
+                description: None,
+                number_of_books: None,
+                canon: None,
+            })
+            .collect())
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        Err(AppError::NotFound(format!("no books for {}", translation)))
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        Ok(Chapter {
+            translation: translation.to_string(),
+            book: book.to_string(),
+            chapter,
+            verses: Vec::new(),
+            footnotes: None,
+            content: Vec::new(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn imported_translations_are_served_alongside_upstream_ones() {
+    let dir = std::env::temp_dir().join(format!("thebible-overlay-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("afr33.xml"), ZEFANIA).unwrap();
+    let files = FileProvider::load(dir.to_str().unwrap()).unwrap();
+    let ids: HashSet<String> = files.translation_ids().map(str::to_string).collect();
+    let provider = OverlayProvider::new(Arc::new(files), ids, Arc::new(Upstream));
+
+    let served = provider.serve_translations().await.unwrap();
+    assert_eq!(served.provider, "files,helloao");
+    let names: Vec<&str> = served.value.iter().map(|t| t.name.as_str()).collect();
+    // The imported afr33 hides the upstream one
+    assert_eq!(names, vec!["Afrikaans 1933/1953", "Upstream BSB"]);
+
+    let local = provider.serve_chapter("afr33", "JHN", 1).await.unwrap();
+    assert_eq!(
+        (local.provider.as_str(), local.value.verses.len()),
+        ("files", 2)
+    );
+    let upstream = provider.serve_chapter("BSB", "JHN", 1).await.unwrap();
+    assert_eq!(upstream.provider, "helloao");
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+struct Down;
+
+#[async_trait]
+impl BibleProvider for Down {
+    fn name(&self) -> &str {
+        "helloao"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Err(AppError::UpstreamUnavailable("down".to_string()))
+    }
+
+    async fn get_books(&self, _translation: &str) -> Result<Vec<Book>> {
+        Err(AppError::UpstreamUnavailable("down".to_string()))
+    }
+
+    async fn get_chapter(&self, _translation: &str, _book: &str, _chapter: u32) -> Result<Chapter> {
+        Err(AppError::UpstreamUnavailable("down".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn imported_translations_are_listed_while_the_upstream_is_down() {
+    let dir = std::env::temp_dir().join(format!("thebible-overlay-down-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("afr33.xml"), ZEFANIA).unwrap();
+    let files = FileProvider::load(dir.to_str().unwrap()).unwrap();
+    let ids: HashSet<String> = files.translation_ids().map(str::to_string).collect();
+    let provider = OverlayProvider::new(Arc::new(files), ids, Arc::new(Down));
+
+    let served = provider.serve_translations().await.unwrap();
+    assert_eq!(served.provider, "files");
+    assert_eq!(served.value.len(), 1);
+
+    // The catalog IDs are checked against is not cut down to the local part
+    let error = provider.get_translations().await.unwrap_err();
+    assert_eq!(error.code(), "upstream_unavailable");
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn imported_translations_are_tagged_with_the_canon_of_their_books() {
+    let dir = std::env::temp_dir().join(format!("thebible-canon-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("eng_dra")).unwrap();
+    std::fs::write(
+        dir.join("eng_dra/TOB.usfm"),
+        "\\id TOB\n\\c 1\n\\v 1 Tobias\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("eng_dra/JHN.usfm"),
+        "\\id JHN\n\\c 1\n\\v 1 Word\n",
+    )
+    .unwrap();
+    let provider = FileProvider::load(dir.to_str().unwrap()).unwrap();
+
+    let translations = provider.get_translations().await.unwrap();
+    assert_eq!(translations[0].canon.as_deref(), Some("catholic"));
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,608 @@
+use axum::{http::header, middleware, routing::get, Router};
+use serde_json::Value;
+use std::sync::Arc;
+
+use thebible::api::models::{ContentNode, Translation, VerseSegment};
+use thebible::api::HelloAOBibleClient;
+use thebible::state::AppState;
+use thebible::{handlers, problem};
+
+/// A chapter in the shape of HelloAO's `/api/BSB/PSA/23.json`, with John
+/// 3:16 added so every node and segment type appears
+const CHAPTER: &str = r#"{
+  "translation": { "id": "BSB", "name": "Berean Standard Bible" },
+  "book": { "id": "PSA", "name": "Psalms" },
+  "chapter": {
+    "number": 23,
+    "content": [
+      { "type": "heading", "content": ["The LORD", "Is My Shepherd"] },
+      { "type": "hebrew_subtitle", "content": ["A Psalm of David."] },
+      {
+        "type": "verse",
+        "number": 1,
+        "content": [
+          { "text": "The LORD is my shepherd;", "poem": 1 },
+          { "text": "I shall not want.", "poem": 2 },
+          { "noteId": 0 }
+        ]
+      },
+      { "type": "line_break" },
+      {
+        "type": "verse",
+        "number": 2,
+        "content": [
+          "He makes me lie down ",
+          { "lineBreak": true },
+          "  in green pastures;",
+          { "heading": "Still Waters" }
+        ]
+      },
+      {
+        "type": "verse",
+        "number": 16,
+        "content": [
+          { "text": "For God so loved the world", "wordsOfJesus": true },
+          { "noteId": "1" }
+        ]
+      },
+      { "type": "unknown_block", "content": ["ignored"] }
+    ],
+    "footnotes": [
+      {
+        "noteId": 0,
+        "text": "Or lack nothing",
+        "caller": "+",
+        "reference": { "chapter": 23, "verse": 1 }
+      },
+      { "noteId": "1", "text": "Or his only begotten Son" }
+    ]
+  },
+  "numberOfVerses": 6
+}"#;
+
+const TRANSLATIONS: &str = r#"{
+  "translations": [
+    { "id": "BSB", "name": "Berean Standard Bible", "language": "eng", "numberOfBooks": 66 },
+    { "id": "DRA", "name": "Douay-Rheims", "language": "eng", "numberOfBooks": 73 }
+  ]
+}"#;
+
+const BSB_BOOKS: &str = r#"{
+  "books": [
+    { "id": "GEN", "name": "Genesis", "order": 1 },
+    { "id": "MAT", "name": "Matthew", "order": 40 }
+  ]
+}"#;
+
+const DRA_BOOKS: &str = r#"[
+  { "id": "GEN", "name": "Genesis", "order": 1 },
+  { "id": "TOB", "name": "Tobit", "order": 17 }
+]"#;
+
+const COMMENTARIES: &str = r#"{
+  "commentaries": [
+    { "id": "matthew-henry", "name": "Matthew Henry Bible Commentary", "language": "eng" }
+  ]
+}"#;
+
+/// A commentary chapter in the shape of `/api/c/matthew-henry/JHN/1.json`
+const COMMENTARY: &str = r#"{
+  "commentary": { "id": "matthew-henry" },
+  "book": { "id": "JHN" },
+  "chapter": {
+    "number": 1,
+    "introduction": "The eternal Word.\n\nCompare Genesis 1:1 with this verse.",
+    "content": [
+      { "type": "heading", "content": ["The Word made flesh"] },
+      {
+        "type": "verse",
+        "number": 1,
+        "content": ["In the beginning was the Word.", "See also 1 John 1:1-2."]
+      },
+      { "type": "note", "content": "Verses 2 and 3 follow the same thought." },
+      { "type": "verse", "number": 14, "content": "The Word was made flesh (Phil 2:7)." },
+      { "type": "verse", "content": ["a verse without a number is skipped"] },
+      { "type": "note", "content": [] }
+    ]
+  }
+}"#;
+
+const COMMENTARY_BOOKS: &str = r#"{
+  "commentary": { "id": "matthew-henry" },
+  "books": [
+    {
+      "id": "JHN",
+      "name": "John",
+      "commonName": "John",
+      "order": 43,
+      "numberOfChapters": 21,
+      "introductionSummary": "The gospel of the Word",
+      "introduction": "John wrote last.\n\nHe supplies what Matthew 1:1 leaves out."
+    },
+    { "id": "ROM", "name": "Romans", "order": 45, "numberOfChapters": 16 }
+  ]
+}"#;
+
+const PROFILES: &str = r#"{
+  "commentary": { "id": "matthew-henry" },
+  "profiles": [
+    {
+      "id": "abraham",
+      "subject": "Abraham",
+      "reference": { "book": "GEN", "chapter": 12, "verse": 1 },
+      "thumbnailUrl": "https://example.com/abraham.jpg"
+    },
+    { "id": "moses", "subject": "Moses" }
+  ]
+}"#;
+
+const PROFILE: &str = r#"{
+  "commentary": { "id": "matthew-henry" },
+  "profile": { "id": "abraham", "subject": "Abraham" },
+  "content": ["The father of the faithful.\n\nSee Romans 4:16."]
+}"#;
+
+const DATASETS: &str = r#"{
+  "datasets": [
+    {
+      "id": "open-cross-ref",
+      "name": "Open Cross References",
+      "englishName": "Open Cross References",
+      "language": "eng",
+      "numberOfBooks": 66
+    }
+  ]
+}"#;
+
+const DATASET_BOOKS: &str = r#"{
+  "dataset": { "id": "open-cross-ref" },
+  "books": [
+    { "id": "GEN", "order": 1, "numberOfChapters": 50, "totalNumberOfReferences": 3000 }
+  ]
+}"#;
+
+const DATASET_CHAPTER: &str = r#"{
+  "dataset": { "id": "open-cross-ref" },
+  "book": { "id": "GEN" },
+  "chapter": {
+    "number": 1,
+    "content": [
+      {
+        "verse": 1,
+        "references": [
+          { "book": "JHN", "chapter": 1, "verse": 1, "endVerse": 3, "score": 51 },
+          { "book": "HEB", "chapter": 11, "verse": 3 }
+        ]
+      },
+      { "verse": 2 }
+    ]
+  }
+}"#;
+
+/// Serve `router` on an ephemeral local port and return its base URL
+async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn json(body: &'static str) -> ([(header::HeaderName, &'static str); 1], &'static str) {
+    ([(header::CONTENT_TYPE, "application/json")], body)
+}
+
+async fn mock_helloao() -> HelloAOBibleClient {
+    let router = Router::new()
+        .route("/api/BSB/PSA/23.json", get(|| async { json(CHAPTER) }))
+        .route(
+            "/api/available_translations.json",
+            get(|| async { json(TRANSLATIONS) }),
+        )
+        .route("/api/BSB/books.json", get(|| async { json(BSB_BOOKS) }))
+        .route("/api/DRA/books.json", get(|| async { json(DRA_BOOKS) }))
+        .route(
+            "/api/available_commentaries.json",
+            get(|| async { json(COMMENTARIES) }),
+        )
+        .route(
+            "/api/c/matthew-henry/JHN/1.json",
+            get(|| async { json(COMMENTARY) }),
+        )
+        .route(
+            "/api/c/matthew-henry/books.json",
+            get(|| async { json(COMMENTARY_BOOKS) }),
+        )
+        .route(
+            "/api/c/matthew-henry/profiles.json",
+            get(|| async { json(PROFILES) }),
+        )
+        .route(
+            "/api/c/matthew-henry/profiles/abraham.json",
+            get(|| async { json(PROFILE) }),
+        )
+        .route(
+            "/api/available_datasets.json",
+            get(|| async { json(DATASETS) }),
+        )
+        .route(
+            "/api/d/open-cross-ref/books.json",
+            get(|| async { json(DATASET_BOOKS) }),
+        )
+        .route(
+            "/api/d/open-cross-ref/GEN/1.json",
+            get(|| async { json(DATASET_CHAPTER) }),
+        );
+    HelloAOBibleClient::new(format!("{}/api", serve(router).await))
+}
+
+/// The API's routes over `helloao`, served locally; returns the base URL
+async fn app(helloao: HelloAOBibleClient) -> String {
+    let state = AppState::new(Arc::new(helloao.clone()), helloao);
+    let router = Router::new()
+        .route(
+            "/api/commentaries/:commentary_id/books/:book/introduction",
+            get(handlers::get_commentary_book_introduction),
+        )
+        .route(
+            "/api/commentaries/:commentary_id/profiles",
+            get(handlers::get_commentary_profiles),
+        )
+        .route(
+            "/api/commentaries/:commentary_id/profiles/:profile_id",
+            get(handlers::get_commentary_profile),
+        )
+        .route("/api/datasets", get(handlers::get_datasets))
+        .route(
+            "/api/datasets/:dataset_id/books",
+            get(handlers::get_dataset_books),
+        )
+        .route(
+            "/api/datasets/:dataset_id/books/:book/chapters/:chapter",
+            get(handlers::get_dataset_chapter),
+        )
+        .layer(middleware::from_fn(problem::problem_details))
+        .with_state(state);
+    serve(router).await
+}
+
+async fn get_json(url: String) -> (u16, Value) {
+    let response = reqwest::get(url).await.unwrap();
+    (response.status().as_u16(), response.json().await.unwrap())
+}
+
+fn text(text: &str, poem: Option<u32>) -> VerseSegment {
+    VerseSegment::Text {
+        text: text.to_string(),
+        poem,
+    }
+}
+
+#[tokio::test]
+async fn keeps_every_chapter_node_in_reading_order() {
+    let chapter = mock_helloao()
+        .await
+        .get_chapter("BSB", "PSA", 23)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        chapter.content,
+        vec![
+            ContentNode::Heading {
+                text: "The LORD Is My Shepherd".to_string()
+            },
+            ContentNode::HebrewSubtitle {
+                content: vec![text("A Psalm of David.", None)]
+            },
+            ContentNode::Verse {
+                number: 1,
+                content: vec![
+                    text("The LORD is my shepherd;", Some(1)),
+                    text("I shall not want.", Some(2)),
+                    VerseSegment::FootnoteMarker {
+                        id: "0".to_string()
+                    },
+                ],
+            },
+            ContentNode::LineBreak,
+            ContentNode::Verse {
+                number: 2,
+                content: vec![
+                    text("He makes me lie down ", None),
+                    VerseSegment::LineBreak,
+                    text("  in green pastures;", None),
+                    VerseSegment::Heading {
+                        text: "Still Waters".to_string()
+                    },
+                ],
+            },
+            ContentNode::Verse {
+                number: 16,
+                content: vec![
+                    VerseSegment::WordsOfJesus {
+                        text: "For God so loved the world".to_string(),
+                        poem: None,
+                    },
+                    VerseSegment::FootnoteMarker {
+                        id: "1".to_string()
+                    },
+                ],
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn flattens_verse_text_and_links_footnotes() {
+    let chapter = mock_helloao()
+        .await
+        .get_chapter("BSB", "PSA", 23)
+        .await
+        .unwrap();
+    assert_eq!(chapter.chapter, 23);
+
+    let verses: Vec<(u32, &str, Option<Vec<String>>)> = chapter
+        .verses
+        .iter()
+        .map(|v| (v.verse, v.text.as_str(), v.footnotes.clone()))
+        .collect();
+    assert_eq!(
+        verses,
+        vec![
+            (
+                1,
+                "The LORD is my shepherd; I shall not want.",
+                Some(vec!["0".to_string()])
+            ),
+            (2, "He makes me lie down in green pastures;", None),
+            (
+                16,
+                "For God so loved the world",
+                Some(vec!["1".to_string()])
+            ),
+        ]
+    );
+
+    let footnotes = chapter.footnotes.unwrap();
+    assert_eq!(footnotes.len(), 2);
+    assert_eq!(
+        (footnotes[0].id.as_str(), footnotes[0].text.as_str()),
+        ("0", "Or lack nothing")
+    );
+    assert_eq!(
+        (footnotes[0].caller.as_deref(), footnotes[0].verse),
+        (Some("+"), Some(1))
+    );
+    assert_eq!(
+        (footnotes[1].caller.as_deref(), footnotes[1].verse),
+        (None, None)
+    );
+}
+
+#[tokio::test]
+async fn splits_commentary_into_introduction_verses_and_notes() {
+    let commentary = mock_helloao()
+        .await
+        .get_commentary("matthew-henry", "JHN", 1)
+        .await
+        .unwrap();
+
+    let introduction = commentary.introduction.unwrap();
+    let paragraphs: Vec<&str> = introduction.iter().map(|p| p.text.as_str()).collect();
+    assert_eq!(
+        paragraphs,
+        vec!["The eternal Word.", "Compare Genesis 1:1 with this verse."]
+    );
+    assert_eq!(introduction[1].references[0].reference, "Genesis 1:1");
+
+    let verses: Vec<(u32, &str)> = commentary
+        .verses
+        .iter()
+        .map(|v| (v.verse, v.content.as_str()))
+        .collect();
+    assert_eq!(
+        verses,
+        vec![
+            (1, "In the beginning was the Word. See also 1 John 1:1-2."),
+            (14, "The Word was made flesh (Phil 2:7)."),
+        ]
+    );
+    let cited = &commentary.verses[0].paragraphs[1].references[0];
+    assert_eq!(
+        (cited.text.as_str(), cited.ranges[0].book.as_str()),
+        ("1 John 1:1-2", "1JN")
+    );
+    assert_eq!(
+        commentary.verses[1].paragraphs[0].references[0].reference,
+        "Philippians 2:7"
+    );
+
+    // Notes remember the verse they follow; empty ones are dropped
+    let notes: Vec<(&str, Option<u32>, &str)> = commentary
+        .notes
+        .iter()
+        .map(|n| {
+            (
+                n.kind.as_str(),
+                n.after_verse,
+                n.paragraphs[0].text.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        notes,
+        vec![
+            ("heading", None, "The Word made flesh"),
+            ("note", Some(1), "Verses 2 and 3 follow the same thought."),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn serves_a_commentary_book_introduction() {
+    let base = app(mock_helloao().await).await;
+
+    let (status, body) = get_json(format!(
+        "{}/api/commentaries/matthew-henry/books/jhn/introduction",
+        base
+    ))
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["book"], "JHN");
+    assert_eq!(body["summary"], "The gospel of the Word");
+    assert_eq!(body["introduction"][0]["text"], "John wrote last.");
+    assert_eq!(
+        body["introduction"][1]["references"][0]["reference"],
+        "Matthew 1:1"
+    );
+
+    // Romans is covered but has no introduction; Genesis is not covered
+    let (status, body) = get_json(format!(
+        "{}/api/commentaries/matthew-henry/books/ROM/introduction",
+        base
+    ))
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["introduction"], serde_json::json!([]));
+
+    let (status, body) = get_json(format!(
+        "{}/api/commentaries/matthew-henry/books/GEN/introduction",
+        base
+    ))
+    .await;
+    assert_eq!((status, body["code"].as_str()), (404, Some("not_found")));
+}
+
+#[tokio::test]
+async fn serves_commentary_profiles() {
+    let base = app(mock_helloao().await).await;
+
+    let (status, body) =
+        get_json(format!("{}/api/commentaries/matthew-henry/profiles", base)).await;
+    assert_eq!(status, 200);
+    assert_eq!(body.as_array().unwrap().len(), 2);
+    assert_eq!(body[0]["subject"], "Abraham");
+    assert_eq!(body[0]["reference"]["chapter"], 12);
+    assert_eq!(body[0]["thumbnailUrl"], "https://example.com/abraham.jpg");
+    assert_eq!(body[1]["reference"], Value::Null);
+
+    let (status, body) = get_json(format!(
+        "{}/api/commentaries/matthew-henry/profiles/abraham",
+        base
+    ))
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["commentary_id"], "matthew-henry");
+    assert_eq!(body["profile"]["id"], "abraham");
+    assert_eq!(body["content"][0]["text"], "The father of the faithful.");
+    assert_eq!(
+        body["content"][1]["references"][0]["reference"],
+        "Romans 4:16"
+    );
+
+    // Profiles the commentary does not have are upstream 404s
+    let (status, body) = get_json(format!(
+        "{}/api/commentaries/matthew-henry/profiles/nobody",
+        base
+    ))
+    .await;
+    assert_eq!((status, body["code"].as_str()), (404, Some("not_found")));
+}
+
+#[tokio::test]
+async fn serves_datasets_their_books_and_chapters() {
+    let base = app(mock_helloao().await).await;
+
+    let (status, body) = get_json(format!("{}/api/datasets", base)).await;
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["id"], "open-cross-ref");
+    assert_eq!(body[0]["numberOfBooks"], 66);
+
+    let (status, body) = get_json(format!("{}/api/datasets/open-cross-ref/books", base)).await;
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["totalNumberOfReferences"], 3000);
+
+    let (status, body) = get_json(format!(
+        "{}/api/datasets/open-cross-ref/books/gen/chapters/1",
+        base
+    ))
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(
+        (body["book"].as_str(), body["chapter"].as_u64()),
+        (Some("GEN"), Some(1))
+    );
+    let references = &body["verses"][0]["references"];
+    assert_eq!(references[0]["book"], "JHN");
+    assert_eq!(references[0]["endVerse"], 3);
+    assert_eq!(references[0]["score"], 51.0);
+    assert_eq!(references[1]["score"], Value::Null);
+    assert_eq!(body["verses"][1]["references"], serde_json::json!([]));
+
+    let (status, body) = get_json(format!(
+        "{}/api/datasets/open-cross-ref/books/gen/chapters/one",
+        base
+    ))
+    .await;
+    assert_eq!(
+        (status, body["code"].as_str()),
+        (400, Some("invalid_input"))
+    );
+}
+
+#[tokio::test]
+async fn caches_each_dataset_and_profile_response() {
+    let helloao = mock_helloao().await;
+    for _ in 0..2 {
+        helloao
+            .get_commentary_profiles("matthew-henry")
+            .await
+            .unwrap();
+        helloao
+            .get_commentary_profile("matthew-henry", "abraham")
+            .await
+            .unwrap();
+        helloao.get_datasets().await.unwrap();
+        helloao.get_dataset_books("open-cross-ref").await.unwrap();
+        helloao
+            .get_dataset_chapter("open-cross-ref", "GEN", 1)
+            .await
+            .unwrap();
+    }
+
+    let stats = helloao.cache_stats();
+    assert_eq!((stats.entries, stats.hits, stats.misses), (5, 5, 5));
+}
+
+#[tokio::test]
+async fn tags_translations_with_the_canon_of_their_cached_books() {
+    let helloao = mock_helloao().await;
+    let canons = |translations: Vec<Translation>| {
+        translations
+            .into_iter()
+            .map(|t| (t.id, t.canon))
+            .collect::<Vec<_>>()
+    };
+
+    // Nothing is known of either translation's books yet
+    let tagged = canons(helloao.get_translations().await.unwrap());
+    assert_eq!(
+        tagged,
+        vec![("BSB".to_string(), None), ("DRA".to_string(), None)]
+    );
+
+    helloao.get_books("BSB").await.unwrap();
+    helloao.get_books("DRA").await.unwrap();
+    let stats = helloao.cache_stats();
+    let tagged = canons(helloao.get_translations().await.unwrap());
+    assert_eq!(
+        tagged,
+        vec![
+            ("BSB".to_string(), Some("protestant".to_string())),
+            ("DRA".to_string(), Some("catholic".to_string())),
+        ]
+    );
+    // Looking at the cached book lists does not count as using them
+    assert_eq!(helloao.cache_stats().hits, stats.hits + 1);
+    assert_eq!(helloao.cache_stats().misses, stats.misses);
+}
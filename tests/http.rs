@@ -0,0 +1,141 @@
+use axum::{extract::State, http::StatusCode, routing::get, Router};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use thebible::api::http::UpstreamHttp;
+use thebible::config::HttpConfig;
+
+/// Statuses the mock answers with, in order; it answers 200 once they run
+/// out. A status of 0 is a slow 200.
+#[derive(Clone, Default)]
+struct Script {
+    statuses: Arc<Mutex<VecDeque<u16>>>,
+    hits: Arc<AtomicUsize>,
+}
+
+async fn scripted(State(script): State<Script>) -> (StatusCode, &'static str) {
+    script.hits.fetch_add(1, Ordering::SeqCst);
+    let status = script.statuses.lock().unwrap().pop_front().unwrap_or(200);
+    if status == 0 {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        return (StatusCode::OK, "body");
+    }
+    (StatusCode::from_u16(status).unwrap(), "body")
+}
+
+async fn mock(statuses: &[u16]) -> (String, Script) {
+    let script = Script::default();
+    script.statuses.lock().unwrap().extend(statuses);
+    let router = Router::new()
+        .route("/", get(scripted))
+        .with_state(script.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (format!("http://{}/", addr), script)
+}
+
+fn http(max_retries: u32, breaker_threshold: u32) -> UpstreamHttp {
+    UpstreamHttp::new(HttpConfig {
+        read_timeout_ms: 5_000,
+        max_retries,
+        backoff_base_ms: 1,
+        backoff_max_ms: 5,
+        breaker_threshold,
+        breaker_cooldown_secs: 1,
+        ..HttpConfig::default()
+    })
+}
+
+fn hits(script: &Script) -> usize {
+    script.hits.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn server_errors_are_retried_until_one_succeeds() {
+    let (url, script) = mock(&[503, 500]).await;
+
+    let response = http(2, 10).get(&url, &[], &[]).await.unwrap();
+    assert_eq!(response.status, 200);
+    assert_eq!(hits(&script), 3);
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let (url, script) = mock(&[404]).await;
+
+    let response = http(2, 10).get(&url, &[], &[]).await.unwrap();
+    assert_eq!(response.status, 404);
+    assert_eq!(hits(&script), 1);
+}
+
+#[tokio::test]
+async fn the_circuit_opens_after_consecutive_failures() {
+    let (url, script) = mock(&[500, 500, 500]).await;
+    let http = http(0, 3);
+
+    for _ in 0..3 {
+        let error = http.get(&url, &[], &[]).await.unwrap_err();
+        assert_eq!(error.code(), "upstream_unavailable");
+    }
+    let error = http.get(&url, &[], &[]).await.unwrap_err();
+    assert_eq!(error.code(), "circuit_open");
+    assert_eq!(hits(&script), 3);
+}
+
+#[tokio::test]
+async fn a_successful_trial_closes_the_circuit() {
+    let (url, script) = mock(&[500]).await;
+    let http = http(0, 1);
+
+    http.get(&url, &[], &[]).await.unwrap_err();
+    assert_eq!(
+        http.get(&url, &[], &[]).await.unwrap_err().code(),
+        "circuit_open"
+    );
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(http.get(&url, &[], &[]).await.unwrap().status, 200);
+    assert_eq!(http.get(&url, &[], &[]).await.unwrap().status, 200);
+    assert_eq!(hits(&script), 3);
+}
+
+#[tokio::test]
+async fn a_failed_trial_reopens_the_circuit() {
+    let (url, script) = mock(&[500, 500]).await;
+    let http = http(2, 1);
+
+    http.get(&url, &[], &[]).await.unwrap_err();
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // The trial gets one attempt, not the usual retries
+    let error = http.get(&url, &[], &[]).await.unwrap_err();
+    assert_eq!(error.code(), "upstream_unavailable");
+    assert_eq!(
+        http.get(&url, &[], &[]).await.unwrap_err().code(),
+        "circuit_open"
+    );
+    assert_eq!(hits(&script), 2);
+}
+
+#[tokio::test]
+async fn an_abandoned_trial_still_settles_the_circuit() {
+    let (url, script) = mock(&[500, 0]).await;
+    let http = http(0, 1);
+
+    http.get(&url, &[], &[]).await.unwrap_err();
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // The trial is slow and its caller gives up on it
+    let trial = tokio::time::timeout(Duration::from_millis(50), http.get(&url, &[], &[])).await;
+    assert!(trial.is_err());
+
+    // It still completes and closes the circuit
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let response = http.get(&url, &[], &[("page", "2".to_string())]).await;
+    assert_eq!(response.unwrap().status, 200);
+    assert_eq!(hits(&script), 3);
+    assert_eq!(http.coalescing_stats().in_flight, 0);
+}
@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use thebible::api::chain::{MappedProvider, ProviderChain};
+use thebible::api::models::{Book, Chapter, Translation};
+use thebible::api::provider::BibleProvider;
+use thebible::api::HelloAOBibleClient;
+use thebible::error::{AppError, Result};
+use thebible::ids::{BookId, CommentaryId, KnownIds, TranslationId};
+use thebible::state::AppState;
+
+#[test]
+fn rejects_segments_that_change_the_upstream_path() {
+    for raw in [
+        "", "..", "../books", "BSB/GEN", "BSB?x=1", "BSB%2F", "BSB#", "-BSB", "B S B",
+    ] {
+        let error = TranslationId::parse(raw).unwrap_err();
+        assert_eq!(error.code(), "invalid_input", "{:?}", raw);
+    }
+    assert!(CommentaryId::parse(&"a".repeat(65)).is_err());
+}
+
+#[test]
+fn accepts_ordinary_ids() {
+    assert_eq!(TranslationId::parse("eng_kjv").unwrap().as_str(), "eng_kjv");
+    assert_eq!(
+        CommentaryId::parse("matthew-henry").unwrap().as_str(),
+        "matthew-henry"
+    );
+}
+
+#[test]
+fn book_ids_resolve_through_the_catalog() {
+    assert_eq!(BookId::parse("gen").unwrap().as_str(), "GEN");
+    assert_eq!(BookId::parse("1co").unwrap().as_str(), "1CO");
+    assert_eq!(BookId::parse("SIR").unwrap().as_str(), "SIR");
+    assert_eq!(BookId::parse("Johannes").unwrap().as_str(), "JHN");
+    assert_eq!(BookId::parse("XYZ").unwrap_err().code(), "invalid_input");
+    assert_eq!(BookId::parse("../GEN").unwrap_err().code(), "invalid_input");
+}
+
+#[tokio::test]
+async fn unknown_ids_are_not_found_and_catalogs_are_reused() {
+    let known = KnownIds::default();
+    let fetches = AtomicUsize::new(0);
+    let catalog = || async {
+        fetches.fetch_add(1, Ordering::SeqCst);
+        Ok(vec!["BSB".to_string(), "eng_kjv".to_string()])
+    };
+
+    let bsb = TranslationId::parse("BSB").unwrap();
+    let missing = TranslationId::parse("NOPE").unwrap();
+    assert!(known.check_translation(&bsb, catalog).await.is_ok());
+    let error = known
+        .check_translation(&missing, catalog)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "not_found");
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn unavailable_catalogs_let_ids_through() {
+    let known = KnownIds::default();
+    let id = CommentaryId::parse("anything").unwrap();
+    let result = known
+        .check_commentary(&id, || async {
+            Err(AppError::UpstreamUnavailable("down".to_string()))
+        })
+        .await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn failed_catalog_fetches_are_not_retried_at_once() {
+    let fetches = AtomicUsize::new(0);
+    let down = || async {
+        fetches.fetch_add(1, Ordering::SeqCst);
+        Err::<Vec<String>, _>(AppError::UpstreamUnavailable("down".to_string()))
+    };
+    let id = TranslationId::parse("BSB").unwrap();
+
+    let known = KnownIds::default();
+    assert!(known.check_translation(&id, down).await.is_ok());
+    assert!(known.check_translation(&id, down).await.is_ok());
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+    let known = KnownIds::default().with_failure_ttl(Duration::ZERO);
+    assert!(known.check_translation(&id, down).await.is_ok());
+    assert!(known.check_translation(&id, down).await.is_ok());
+    assert_eq!(fetches.load(Ordering::SeqCst), 3);
+}
+
+/// A provider listing `ids` in its own ID space
+struct Lists {
+    name: &'static str,
+    ids: &'static [&'static str],
+}
+
+#[async_trait]
+impl BibleProvider for Lists {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(self
+            .ids
+            .iter()
+            .map(|id| Translation {
+                id: id.to_string(),
+                name: id.to_string(),
+                language: None,
+                english_name: None,
+                language_name: None,
+                language_english_name: None,
+                short_name: None,
+                description: None,
+                number_of_books: None,
+                canon: None,
+            })
+            .collect())
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        Err(AppError::NotFound(translation.to_string()))
+    }
+
+    async fn get_chapter(&self, translation: &str, _book: &str, _chapter: u32) -> Result<Chapter> {
+        Err(AppError::NotFound(translation.to_string()))
+    }
+}
+
+#[tokio::test]
+async fn ids_only_a_later_provider_serves_are_known() {
+    let helloao = Arc::new(Lists {
+        name: "helloao",
+        ids: &["BSB"],
+    });
+    let brain = MappedProvider::new(
+        Arc::new(Lists {
+            name: "bible_brain",
+            ids: &["ENGKJV"],
+        }),
+        HashMap::from([("eng_kjv".to_string(), "ENGKJV".to_string())]),
+    );
+    let chain = ProviderChain::new(vec![helloao, Arc::new(brain)], Duration::from_secs(5));
+    let state = AppState::new(
+        Arc::new(chain),
+        HelloAOBibleClient::new("http://127.0.0.1:9/api".to_string()),
+    );
+
+    assert_eq!(state.translation_id("BSB").await.unwrap().as_str(), "BSB");
+    assert_eq!(
+        state.translation_id("eng_kjv").await.unwrap().as_str(),
+        "eng_kjv"
+    );
+    // Only our ID is listed, not the provider's own
+    let error = state.translation_id("ENGKJV").await.unwrap_err();
+    assert_eq!(error.code(), "not_found");
+}
@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use thebible::lexicon::{normalize_strongs, LexiconIndex};
+use thebible::original::{self, OriginalTexts};
+use thebible::problem;
+use thebible::versification::Scheme;
+
+const MALACHI: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<osis xmlns="http://www.bibletechnologies.net/2003/OSIS/namespace">
+<osisText osisIDWork="WLC">
+<div type="book" osisID="Mal">
+<chapter osisID="Mal.3">
+<verse osisID="Mal.3.23"><w lemma="2009" morph="HTi" id="39Nz1">הִנֵּ֤ה</w>
+<w lemma="595" morph="HPp1cs" id="39Nz2">אָֽנֹכִי֙</w>
+<note type="variant"><rdg><w lemma="9999" morph="X">x</w></rdg></note>
+<w lemma="c/d/776" morph="HC/Td/Ncbsa" id="39Nz3">וְ/הָ/אָ֖רֶץ</w></verse>
+</chapter>
+</div>
+</osisText>
+</osis>
+"#;
+
+const JOHN: &str = "040101 P- -------- Ἐν Ἐν ἐν ἐν
+040101 N- ----DSF- ἀρχῇ ἀρχῇ ἀρχῇ ἀρχή
+040101 V- 3IAI-S-- ἦν ἦν ἦν εἰμί
+040101 RA ----NSM- ὁ ὁ ὁ ὁ
+040101 N- ----NSM- λόγος, λόγος λόγος λόγος
+";
+
+const LEXICON: &str = r#"var strongsDictionary = {
+"H2009":{"lemma":"הִנֵּה","xlit":"hinnêh","kjv_def":":--behold, lo, see."},
+"H776":{"lemma":"אֶרֶץ","xlit":"ʼerets","kjv_def":"X common, country, earth, field."},
+"G3056":{"lemma":"λόγος","xlit":"lógos","kjv_def":"account, cause, communication","strongs_def":"something said"},
+"G746":{"lemma":"ἀρχή","xlit":"archḗ","gloss":"beginning"}
+};"#;
+
+fn fixtures() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("thebible-original-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("hebrew")).unwrap();
+    std::fs::create_dir_all(dir.join("greek")).unwrap();
+    std::fs::write(dir.join("hebrew/Mal.xml"), MALACHI).unwrap();
+    std::fs::write(dir.join("greek/64-Jn-morphgnt.txt"), JOHN).unwrap();
+    dir
+}
+
+fn lexicon() -> LexiconIndex {
+    let mut index = LexiconIndex::default();
+    index.load(LEXICON, "lexicon.js").unwrap();
+    index
+}
+
+#[test]
+fn parses_oshb_words_without_variants() {
+    let book = original::parse_oshb(MALACHI, "MAL", "Mal.xml").unwrap();
+    let words = &book.verses[&(3, 23)];
+    assert_eq!(words.len(), 3);
+    assert_eq!(words[2].text, "וְהָאָ֖רֶץ");
+    assert_eq!(words[2].strongs.as_deref(), Some("H776"));
+    assert_eq!(words[2].morph, "HC/Td/Ncbsa");
+}
+
+#[test]
+fn data_errors_name_the_file_and_line() {
+    let xml = "<osis>\n<verse osisID=\"Mal.x\"></verse>\n</osis>";
+    let error = original::parse_oshb(xml, "MAL", "Mal.xml").unwrap_err();
+    assert!(error.to_string().contains("Mal.xml:2:"), "{}", error);
+
+    let lines = "040101 P- -------- Ἐν Ἐν ἐν ἐν\n0401 x\n";
+    let error = original::parse_morphgnt(lines, "JHN", "64.txt").unwrap_err();
+    assert!(error.to_string().contains("64.txt:2:"), "{}", error);
+
+    let mut index = LexiconIndex::default();
+    let error = index
+        .load("{\n\"H1\": {\"lemma\": 5}\n}", "lex.json")
+        .unwrap_err();
+    assert!(error.to_string().contains("lex.json:2:"), "{}", error);
+
+    // Clients only learn that something went wrong on our side
+    assert_eq!(error.code(), "data_error");
+    assert_eq!(error.status(), 500);
+    assert!(!problem::client_detail(&error).contains("lex.json"));
+}
+
+#[test]
+fn lexicon_normalizes_numbers_and_glosses() {
+    assert_eq!(normalize_strongs("h07225").as_deref(), Some("H7225"));
+    assert_eq!(normalize_strongs("G3056a").as_deref(), Some("G3056"));
+    assert_eq!(normalize_strongs("3056"), None);
+    assert_eq!(normalize_strongs("H../1"), None);
+
+    let index = lexicon();
+    assert_eq!(index.get("H2009").unwrap().gloss.as_deref(), Some("behold"));
+    assert_eq!(index.get("H776").unwrap().gloss.as_deref(), Some("country"));
+    assert_eq!(
+        index.get("G746").unwrap().gloss.as_deref(),
+        Some("beginning")
+    );
+    assert_eq!(index.greek_strongs("λόγος"), Some("G3056"));
+}
+
+#[tokio::test]
+async fn interlinear_maps_hebrew_numbering_and_glosses_greek_by_lemma() {
+    let dir = fixtures();
+    let texts = OriginalTexts::new(
+        dir.join("hebrew").to_str().unwrap(),
+        dir.join("greek").to_str().unwrap(),
+    );
+    let lexicon = lexicon();
+
+    let hebrew = texts
+        .interlinear("Mal 4:5", Scheme::English, Some(&lexicon))
+        .await
+        .unwrap();
+    let verse = &hebrew.verses[0];
+    assert_eq!((verse.chapter, verse.verse), (4, 5));
+    assert_eq!(verse.native.unwrap().chapter, 3);
+    assert_eq!(verse.words[0].lemma, "הִנֵּה");
+    assert_eq!(verse.words[0].gloss.as_deref(), Some("behold"));
+
+    let greek = texts
+        .interlinear("John 1:1", Scheme::English, Some(&lexicon))
+        .await
+        .unwrap();
+    let words = &greek.verses[0].words;
+    assert_eq!(words[4].text, "λόγος,");
+    assert_eq!(words[4].strongs.as_deref(), Some("G3056"));
+    assert_eq!(words[1].gloss.as_deref(), Some("beginning"));
+    assert_eq!(words[2].strongs, None);
+
+    let error = texts
+        .interlinear("Tob 1:1", Scheme::English, None)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "not_found");
+
+    let error = texts
+        .interlinear("Mal 1-4000000000", Scheme::English, None)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), "invalid_input");
+
+    std::fs::remove_dir_all(dir).ok();
+}
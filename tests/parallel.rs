@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+
+use thebible::api::models::{Book, Chapter, Translation, Verse};
+use thebible::api::provider::BibleProvider;
+use thebible::error::{AppError, Result};
+use thebible::parallel::fetch_parallel;
+use thebible::versification::Scheme;
+
+/// Matthew 17 in two translations; ESV leaves out verse 21, as modern
+/// translations based on the critical text do
+struct Matthew;
+
+#[async_trait]
+impl BibleProvider for Matthew {
+    fn name(&self) -> &str {
+        "stub"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        Err(AppError::NotFound(format!("no books for {}", translation)))
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        let verses: &[u32] = match translation {
+            "KJV" => &[20, 21, 22],
+            "ESV" => &[20, 22],
+            _ => {
+                return Err(AppError::NotFound(format!(
+                    "{} does not exist",
+                    translation
+                )))
+            }
+        };
+        Ok(Chapter {
+            translation: translation.to_string(),
+            book: book.to_string(),
+            chapter,
+            verses: verses
+                .iter()
+                .map(|&verse| Verse {
+                    verse,
+                    text: format!("{} {}", translation, verse),
+                    footnotes: None,
+                })
+                .collect(),
+            footnotes: None,
+            content: Vec::new(),
+        })
+    }
+}
+
+fn translations(ids: &[&str]) -> Vec<(String, Scheme)> {
+    ids.iter()
+        .map(|id| (id.to_string(), Scheme::English))
+        .collect()
+}
+
+#[tokio::test]
+async fn aligns_verses_and_flags_the_ones_a_translation_lacks() {
+    let parallel = fetch_parallel(
+        &Matthew,
+        &translations(&["ESV", "KJV"]),
+        "Matt 17:20-22",
+        Scheme::English,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(parallel.translations, vec!["ESV", "KJV"]);
+    assert!(parallel.errors.is_empty());
+    let numbers: Vec<u32> = parallel.verses.iter().map(|v| v.verse).collect();
+    assert_eq!(numbers, vec![20, 21, 22]);
+
+    let verse = &parallel.verses[1];
+    assert_eq!((verse.book.as_str(), verse.chapter), ("MAT", 17));
+    assert_eq!(verse.missing, vec!["ESV"]);
+    assert_eq!(verse.texts["ESV"], None);
+    assert_eq!(verse.texts["KJV"].as_deref(), Some("KJV 21"));
+
+    let last = &parallel.verses[2];
+    assert!(last.missing.is_empty());
+    assert_eq!(last.texts["ESV"].as_deref(), Some("ESV 22"));
+}
+
+#[tokio::test]
+async fn a_failing_translation_is_reported_without_failing_the_rest() {
+    let parallel = fetch_parallel(
+        &Matthew,
+        &translations(&["KJV", "NONE"]),
+        "Matt 17:21",
+        Scheme::English,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(parallel.errors.len(), 1);
+    assert_eq!(parallel.errors[0].translation, "NONE");
+    assert_eq!(parallel.errors[0].code, "not_found");
+    assert_eq!(parallel.verses.len(), 1);
+    // Only translations that served the passage are aligned
+    assert!(parallel.verses[0].missing.is_empty());
+    assert!(!parallel.verses[0].texts.contains_key("NONE"));
+
+    let error = fetch_parallel(
+        &Matthew,
+        &translations(&["NONE"]),
+        "Matt 17:21",
+        Scheme::English,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error.code(), "not_found");
+}
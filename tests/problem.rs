@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Path, Query},
+    middleware,
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use thebible::{error::AppError, problem};
+
+async fn failing() -> Result<&'static str, AppError> {
+    Err(AppError::UpstreamUnavailable(
+        "Failed to fetch chapter: 500 Internal Server Error - error sending request for url (http://10.0.0.7:8080/api/BSB/GEN/1.json): <html><body>oops</body></html>".to_string(),
+    ))
+}
+
+async fn broken() -> Result<&'static str, AppError> {
+    Err(AppError::Internal(anyhow::anyhow!(
+        "Failed to read /srv/thebible/secret/lexicon.json"
+    )))
+}
+
+#[derive(Deserialize)]
+struct Lookup {
+    #[serde(rename = "ref")]
+    _reference: String,
+}
+
+async fn lookup(Path(_chapter): Path<u32>, Query(_lookup): Query<Lookup>) -> &'static str {
+    "ok"
+}
+
+async fn serve() -> String {
+    let router = Router::new()
+        .route("/fail", get(failing))
+        .route("/broken", get(broken))
+        .route("/chapters/:chapter", get(lookup))
+        .route("/ok", get(|| async { "ok" }))
+        .layer(middleware::from_fn(problem::problem_details));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn errors_are_problem_documents() {
+    let base = serve().await;
+    let response = reqwest::get(format!("{}/fail", base)).await.unwrap();
+
+    assert_eq!(response.status(), 502);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    let request_id = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let problem: Value = response.json().await.unwrap();
+    assert_eq!(problem["type"], "/problems/upstream_unavailable");
+    assert_eq!(problem["title"], "Upstream unavailable");
+    assert_eq!(problem["status"], 502);
+    assert_eq!(problem["instance"], "/fail");
+    assert_eq!(problem["code"], "upstream_unavailable");
+    assert_eq!(problem["request_id"], request_id.as_str());
+
+    let detail = problem["detail"].as_str().unwrap();
+    assert!(!detail.contains("10.0.0.7"));
+    assert!(!detail.contains("<html>"));
+    assert!(detail.contains("[upstream]"));
+}
+
+#[tokio::test]
+async fn plain_text_when_preferred() {
+    let base = serve().await;
+    let response = reqwest::Client::new()
+        .get(format!("{}/fail", base))
+        .header("accept", "text/plain, application/json;q=0.5")
+        .header("x-request-id", "cli-42")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 502);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    assert_eq!(response.headers()["x-request-id"], "cli-42");
+
+    let text = response.text().await.unwrap();
+    assert!(text.starts_with("502 Upstream unavailable: "));
+    assert!(text.contains("request id: cli-42"));
+}
+
+#[tokio::test]
+async fn successful_responses_get_a_request_id() {
+    let base = serve().await;
+    let response = reqwest::Client::new()
+        .get(format!("{}/ok", base))
+        .header("x-request-id", "not a valid id!")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let request_id = response.headers()["x-request-id"].to_str().unwrap();
+    assert_eq!(request_id.len(), 32);
+    assert!(request_id.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+#[test]
+fn sanitize_masks_urls_and_truncates() {
+    assert_eq!(
+        problem::sanitize("GET https://internal.example/api?key=secret failed"),
+        "GET [upstream] failed"
+    );
+    let long = "x".repeat(1000);
+    assert!(problem::sanitize(&long).chars().count() <= 301);
+}
+
+#[tokio::test]
+async fn internal_errors_hide_their_detail() {
+    let base = serve().await;
+    let problem: Value = reqwest::get(format!("{}/broken", base))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(problem["status"], 500);
+    assert_eq!(problem["code"], "internal_error");
+    assert!(!problem["detail"].as_str().unwrap().contains("/srv"));
+}
+
+#[tokio::test]
+async fn rejections_are_problem_documents() {
+    let base = serve().await;
+    let client = reqwest::Client::new();
+
+    for (path, status, code) in [
+        ("/chapters/1", 400, "invalid_input"),
+        ("/chapters/one?ref=John", 400, "invalid_input"),
+        ("/nowhere", 404, "not_found"),
+    ] {
+        let response = client
+            .get(format!("{}{}", base, path))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "{}", path);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/problem+json"
+        );
+        assert!(response.headers().contains_key("x-request-id"));
+
+        let problem: Value = response.json().await.unwrap();
+        assert_eq!(problem["code"], code, "{}", path);
+        assert_eq!(problem["instance"], path.split('?').next().unwrap());
+    }
+
+    let response = client.post(format!("{}/ok", base)).send().await.unwrap();
+    assert_eq!(response.status(), 405);
+    assert!(response.headers().contains_key("allow"));
+    let problem: Value = response.json().await.unwrap();
+    assert_eq!(problem["status"], 405);
+}
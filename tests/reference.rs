@@ -0,0 +1,434 @@
+use thebible::reference::{
+    book_name, find_references, parse_references, resolve_book, ReferenceError, ScriptureRange,
+    VersePoint,
+};
+
+fn verses(book: &str, chapter: u32, first: u32, last: u32) -> ScriptureRange {
+    ScriptureRange::new(
+        book,
+        VersePoint::verse(chapter, first),
+        VersePoint::verse(chapter, last),
+    )
+}
+
+fn chapters(book: &str, first: u32, last: u32) -> ScriptureRange {
+    ScriptureRange::new(book, VersePoint::chapter(first), VersePoint::chapter(last))
+}
+
+fn parse(input: &str) -> Vec<ScriptureRange> {
+    parse_references(input).unwrap_or_else(|e| panic!("failed to parse {:?}: {}", input, e))
+}
+
+#[test]
+fn resolves_full_names() {
+    assert_eq!(resolve_book("Genesis"), Ok("GEN"));
+    assert_eq!(resolve_book("Song of Songs"), Ok("SNG"));
+    assert_eq!(resolve_book("Revelation"), Ok("REV"));
+    assert_eq!(resolve_book("1 Corinthians"), Ok("1CO"));
+}
+
+#[test]
+fn resolves_case_insensitively() {
+    assert_eq!(resolve_book("genesis"), Ok("GEN"));
+    assert_eq!(resolve_book("GENESIS"), Ok("GEN"));
+    assert_eq!(resolve_book("gEnEsIs"), Ok("GEN"));
+}
+
+#[test]
+fn resolves_usfm_codes() {
+    assert_eq!(resolve_book("JHN"), Ok("JHN"));
+    assert_eq!(resolve_book("jhn"), Ok("JHN"));
+    assert_eq!(resolve_book("1JN"), Ok("1JN"));
+    assert_eq!(resolve_book("JUD"), Ok("JUD"));
+    assert_eq!(resolve_book("PHP"), Ok("PHP"));
+}
+
+#[test]
+fn resolves_abbreviations() {
+    assert_eq!(resolve_book("Jn"), Ok("JHN"));
+    assert_eq!(resolve_book("Mt"), Ok("MAT"));
+    assert_eq!(resolve_book("Ps"), Ok("PSA"));
+    assert_eq!(resolve_book("Psalm"), Ok("PSA"));
+    assert_eq!(resolve_book("Phil"), Ok("PHP"));
+    assert_eq!(resolve_book("Phlm"), Ok("PHM"));
+    assert_eq!(resolve_book("Song of Solomon"), Ok("SNG"));
+    assert_eq!(resolve_book("Qoh"), Ok("ECC"));
+}
+
+#[test]
+fn resolves_unambiguous_prefixes() {
+    assert_eq!(resolve_book("Gen"), Ok("GEN"));
+    assert_eq!(resolve_book("Rom"), Ok("ROM"));
+    assert_eq!(resolve_book("Deut"), Ok("DEU"));
+    assert_eq!(resolve_book("Habak"), Ok("HAB"));
+    assert_eq!(resolve_book("Revel"), Ok("REV"));
+}
+
+#[test]
+fn resolves_abbreviations_with_periods() {
+    assert_eq!(resolve_book("Jn."), Ok("JHN"));
+    assert_eq!(resolve_book("Gen."), Ok("GEN"));
+    assert_eq!(resolve_book("1 Cor."), Ok("1CO"));
+}
+
+#[test]
+fn resolves_numbered_books_in_every_style() {
+    for name in [
+        "1 John",
+        "1John",
+        "1 Jn",
+        "1Jn",
+        "I John",
+        "I Jn",
+        "First John",
+        "1st John",
+    ] {
+        assert_eq!(resolve_book(name), Ok("1JN"), "{}", name);
+    }
+    for name in ["2 Kings", "II Kings", "Second Kings", "2nd Kgs", "2 Kgs"] {
+        assert_eq!(resolve_book(name), Ok("2KI"), "{}", name);
+    }
+    for name in ["3 John", "III John", "Third John", "3rd Jn", "3Jn"] {
+        assert_eq!(resolve_book(name), Ok("3JN"), "{}", name);
+    }
+}
+
+#[test]
+fn rejects_ambiguous_prefixes() {
+    assert_eq!(
+        resolve_book("Jo"),
+        Err(ReferenceError::AmbiguousBook("Jo".to_string()))
+    );
+    assert_eq!(
+        resolve_book("Ph"),
+        Err(ReferenceError::AmbiguousBook("Ph".to_string()))
+    );
+}
+
+#[test]
+fn rejects_unknown_books() {
+    assert_eq!(
+        resolve_book("Hezekiah"),
+        Err(ReferenceError::UnknownBook("Hezekiah".to_string()))
+    );
+}
+
+#[test]
+fn every_book_resolves_by_name_and_code() {
+    let codes = [
+        "GEN", "EXO", "LEV", "NUM", "DEU", "JOS", "JDG", "RUT", "1SA", "2SA", "1KI", "2KI", "1CH",
+        "2CH", "EZR", "NEH", "EST", "JOB", "PSA", "PRO", "ECC", "SNG", "ISA", "JER", "LAM", "EZK",
+        "DAN", "HOS", "JOL", "AMO", "OBA", "JON", "MIC", "NAM", "HAB", "ZEP", "HAG", "ZEC", "MAL",
+        "MAT", "MRK", "LUK", "JHN", "ACT", "ROM", "1CO", "2CO", "GAL", "EPH", "PHP", "COL", "1TH",
+        "2TH", "1TI", "2TI", "TIT", "PHM", "HEB", "JAS", "1PE", "2PE", "1JN", "2JN", "3JN", "JUD",
+        "REV",
+    ];
+    for code in codes {
+        assert_eq!(resolve_book(code), Ok(code));
+        let name = book_name(code).expect("every code has a name");
+        assert_eq!(resolve_book(name), Ok(code), "{}", name);
+    }
+}
+
+#[test]
+fn parses_single_verse() {
+    assert_eq!(parse("John 3:16"), vec![verses("JHN", 3, 16, 16)]);
+}
+
+#[test]
+fn parses_verse_range() {
+    assert_eq!(parse("John 3:16-18"), vec![verses("JHN", 3, 16, 18)]);
+}
+
+#[test]
+fn parses_en_and_em_dashes() {
+    assert_eq!(parse("John 3:16–18"), vec![verses("JHN", 3, 16, 18)]);
+    assert_eq!(parse("John 3:16—18"), vec![verses("JHN", 3, 16, 18)]);
+}
+
+#[test]
+fn parses_period_separator() {
+    assert_eq!(parse("John 3.16"), vec![verses("JHN", 3, 16, 16)]);
+    assert_eq!(parse("Ps 119.105-112"), vec![verses("PSA", 119, 105, 112)]);
+}
+
+#[test]
+fn tolerates_extra_whitespace() {
+    assert_eq!(
+        parse("  John   3 : 16 - 18  "),
+        vec![verses("JHN", 3, 16, 18)]
+    );
+    assert_eq!(parse("John3:16"), vec![verses("JHN", 3, 16, 16)]);
+}
+
+#[test]
+fn parses_abbreviation_with_period() {
+    assert_eq!(parse("Jn. 3:16"), vec![verses("JHN", 3, 16, 16)]);
+}
+
+#[test]
+fn parses_cross_chapter_range() {
+    assert_eq!(
+        parse("Gen 1:26-2:3"),
+        vec![ScriptureRange::new(
+            "GEN",
+            VersePoint::verse(1, 26),
+            VersePoint::verse(2, 3)
+        )]
+    );
+}
+
+#[test]
+fn parses_whole_chapter() {
+    assert_eq!(parse("Psalm 23"), vec![chapters("PSA", 23, 23)]);
+}
+
+#[test]
+fn parses_chapter_range() {
+    assert_eq!(parse("Rom 8-9"), vec![chapters("ROM", 8, 9)]);
+}
+
+#[test]
+fn bare_range_end_after_verse_is_a_verse() {
+    assert!(matches!(
+        parse_references("Rom 8:28-9"),
+        Err(ReferenceError::InvalidRange(_))
+    ));
+}
+
+#[test]
+fn parses_chapter_start_to_later_verse() {
+    assert_eq!(
+        parse("Gen 1-2:3"),
+        vec![ScriptureRange::new(
+            "GEN",
+            VersePoint::chapter(1),
+            VersePoint::verse(2, 3)
+        )]
+    );
+}
+
+#[test]
+fn parses_whole_book() {
+    assert_eq!(parse("Ruth"), vec![ScriptureRange::whole_book("RUT")]);
+    assert_eq!(parse("1 John"), vec![ScriptureRange::whole_book("1JN")]);
+    assert_eq!(
+        parse("Song of Songs"),
+        vec![ScriptureRange::whole_book("SNG")]
+    );
+}
+
+#[test]
+fn single_chapter_books_take_verse_numbers() {
+    assert_eq!(parse("Jude 3"), vec![verses("JUD", 1, 3, 3)]);
+    assert_eq!(parse("Philemon 4-7"), vec![verses("PHM", 1, 4, 7)]);
+    assert_eq!(parse("3 John 1:4"), vec![verses("3JN", 1, 4, 4)]);
+}
+
+#[test]
+fn parses_semicolon_list_with_books() {
+    assert_eq!(
+        parse("John 3:16-18; Rom 8:28"),
+        vec![verses("JHN", 3, 16, 18), verses("ROM", 8, 28, 28)]
+    );
+}
+
+#[test]
+fn semicolon_list_continues_book() {
+    assert_eq!(
+        parse("John 3:16; 4:1-2"),
+        vec![verses("JHN", 3, 16, 16), verses("JHN", 4, 1, 2)]
+    );
+}
+
+#[test]
+fn bare_number_after_semicolon_is_a_chapter() {
+    assert_eq!(
+        parse("John 3:16; 5"),
+        vec![verses("JHN", 3, 16, 16), chapters("JHN", 5, 5)]
+    );
+}
+
+#[test]
+fn comma_list_continues_chapter() {
+    assert_eq!(
+        parse("John 3:16, 18, 20-21"),
+        vec![
+            verses("JHN", 3, 16, 16),
+            verses("JHN", 3, 18, 18),
+            verses("JHN", 3, 20, 21),
+        ]
+    );
+}
+
+#[test]
+fn comma_list_after_cross_chapter_range_uses_end_chapter() {
+    assert_eq!(
+        parse("Gen 1:26-2:3, 7"),
+        vec![
+            ScriptureRange::new("GEN", VersePoint::verse(1, 26), VersePoint::verse(2, 3)),
+            verses("GEN", 2, 7, 7),
+        ]
+    );
+}
+
+#[test]
+fn comma_list_of_chapters() {
+    assert_eq!(
+        parse("Ps 1, 23"),
+        vec![chapters("PSA", 1, 1), chapters("PSA", 23, 23)]
+    );
+}
+
+#[test]
+fn comma_item_with_chapter_and_verse() {
+    assert_eq!(
+        parse("John 3:16, 4:2"),
+        vec![verses("JHN", 3, 16, 16), verses("JHN", 4, 2, 2)]
+    );
+}
+
+#[test]
+fn comma_list_can_switch_books() {
+    assert_eq!(
+        parse("Rom 8:28, 1 Cor 13:4-7"),
+        vec![verses("ROM", 8, 28, 28), verses("1CO", 13, 4, 7)]
+    );
+}
+
+#[test]
+fn ignores_trailing_separators() {
+    assert_eq!(parse("John 3:16;"), vec![verses("JHN", 3, 16, 16)]);
+    assert_eq!(parse("John 3:16,"), vec![verses("JHN", 3, 16, 16)]);
+}
+
+#[test]
+fn mixed_list() {
+    assert_eq!(
+        parse("Gen 1; Ex 20:1-17; Ps 23, 24; Jude 24-25"),
+        vec![
+            chapters("GEN", 1, 1),
+            verses("EXO", 20, 1, 17),
+            chapters("PSA", 23, 23),
+            chapters("PSA", 24, 24),
+            verses("JUD", 1, 24, 25),
+        ]
+    );
+}
+
+#[test]
+fn rejects_empty_input() {
+    assert_eq!(parse_references(""), Err(ReferenceError::Empty));
+    assert_eq!(parse_references("   "), Err(ReferenceError::Empty));
+    assert_eq!(parse_references(";,"), Err(ReferenceError::Empty));
+}
+
+#[test]
+fn rejects_missing_book() {
+    assert_eq!(
+        parse_references("3:16"),
+        Err(ReferenceError::MissingBook("3:16".to_string()))
+    );
+}
+
+#[test]
+fn rejects_unknown_book_in_reference() {
+    assert_eq!(
+        parse_references("Hezekiah 3:16"),
+        Err(ReferenceError::UnknownBook("Hezekiah".to_string()))
+    );
+}
+
+#[test]
+fn rejects_zero_and_garbage_numbers() {
+    assert!(matches!(
+        parse_references("John 0:1"),
+        Err(ReferenceError::InvalidNumber(_))
+    ));
+    assert!(matches!(
+        parse_references("John 3:0"),
+        Err(ReferenceError::InvalidNumber(_))
+    ));
+    assert!(parse_references("John 3:x").is_err());
+    assert!(matches!(
+        parse_references("John 3:16-"),
+        Err(ReferenceError::InvalidNumber(_))
+    ));
+    assert!(matches!(
+        parse_references("John 1:1-2:3-4"),
+        Err(ReferenceError::InvalidNumber(_))
+    ));
+}
+
+#[test]
+fn rejects_backwards_ranges() {
+    assert!(matches!(
+        parse_references("John 3:18-16"),
+        Err(ReferenceError::InvalidRange(_))
+    ));
+    assert!(matches!(
+        parse_references("Gen 2:3-1:26"),
+        Err(ReferenceError::InvalidRange(_))
+    ));
+    assert!(matches!(
+        parse_references("Ps 3-1"),
+        Err(ReferenceError::InvalidRange(_))
+    ));
+}
+
+#[test]
+fn displays_canonical_references() {
+    let cases = [
+        ("jn 3:16", "John 3:16"),
+        ("JHN 3:16-18", "John 3:16-18"),
+        ("gen 1:26-2:3", "Genesis 1:26-2:3"),
+        ("ps 23", "Psalms 23"),
+        ("rom 8-9", "Romans 8-9"),
+        ("gen 1-2:3", "Genesis 1-2:3"),
+        ("1 jn", "1 John"),
+        ("jude 3", "Jude 1:3"),
+    ];
+    for (input, expected) in cases {
+        let ranges = parse(input);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].to_string(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn whole_book_flag() {
+    assert!(parse("Ruth")[0].is_whole_book());
+    assert!(!parse("Ruth 1")[0].is_whole_book());
+}
+
+#[test]
+fn finds_references_in_prose() {
+    let text = "God rested (Gen. 2:2-3), as Paul explains in Heb 4:4 and 1 Cor 13:4, 7.";
+    let found = find_references(text);
+    let canonical: Vec<&str> = found.iter().map(|f| f.reference.as_str()).collect();
+    assert_eq!(
+        canonical,
+        vec![
+            "Genesis 2:2-3",
+            "Hebrews 4:4",
+            "1 Corinthians 13:4; 1 Corinthians 13:7"
+        ]
+    );
+    assert_eq!(found[0].text, "Gen. 2:2-3");
+    assert_eq!(&text[found[0].start..found[0].end], "Gen. 2:2-3");
+    assert_eq!(found[2].text, "1 Cor 13:4, 7");
+}
+
+#[test]
+fn finds_multi_word_book_names() {
+    let found = find_references("Compare Song of Songs 2:1 here.");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].reference, "Song of Songs 2:1");
+}
+
+#[test]
+fn ignores_ordinary_words_and_bare_numbers() {
+    assert!(find_references("in 3:16 there is 4:2 and Chapter 5").is_empty());
+    assert!(find_references("Romans 8 is a chapter").is_empty());
+    assert!(find_references("at 10:30 we met").is_empty());
+}
@@ -0,0 +1,107 @@
+use axum::{extract::State, routing::get, Json, Router};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use thebible::api::HelloAOBibleClient;
+use thebible::search::{SearchFilter, SearchIndexes};
+use thebible::store::DiskStore;
+
+async fn books() -> Json<Value> {
+    Json(json!({
+        "books": [{ "id": "GEN", "name": "Genesis", "numberOfChapters": 2 }]
+    }))
+}
+
+/// Chapters read "light" until the mock is switched to its second edition
+async fn chapter(State(edition): State<Arc<AtomicUsize>>) -> Json<Value> {
+    let word = if edition.load(Ordering::SeqCst) == 0 {
+        "light"
+    } else {
+        "darkness"
+    };
+    Json(json!({
+        "chapter": {
+            "content": [{ "type": "verse", "number": 1, "content": [format!("Let there be {}", word)] }]
+        }
+    }))
+}
+
+async fn mock_helloao() -> (String, Arc<AtomicUsize>) {
+    let edition = Arc::new(AtomicUsize::new(0));
+    let router = Router::new()
+        .route("/api/:translation/books.json", get(books))
+        .route("/api/:translation/:book/:chapter", get(chapter))
+        .with_state(edition.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (format!("http://{}/api", addr), edition)
+}
+
+fn store_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("thebible-{}-{}", name, std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    dir
+}
+
+async fn total(indexes: &SearchIndexes, store: &DiskStore, query: &str) -> usize {
+    let index = indexes.get_or_build(store, "BSB").await.unwrap();
+    index
+        .search(query, &SearchFilter::default(), 1, 20)
+        .unwrap()
+        .total
+}
+
+#[tokio::test]
+async fn searches_only_complete_mirrors() {
+    let (base_url, _) = mock_helloao().await;
+    let client = HelloAOBibleClient::new(base_url);
+    let dir = store_dir("search-complete");
+    let store = DiskStore::new(&dir);
+    let indexes = SearchIndexes::default();
+
+    // Chapters cached while serving are not a mirror
+    let books = client.get_books("BSB").await.unwrap();
+    store.save_books("BSB", &books).await.unwrap();
+    let genesis = client.get_chapter("BSB", "GEN", 1).await.unwrap();
+    store.save_chapter(&genesis).await.unwrap();
+    let error = indexes.get_or_build(&store, "BSB").await.err().unwrap();
+    assert_eq!(error.code(), "not_found");
+
+    store.mirror_translation(&client, "BSB", 2).await.unwrap();
+    assert_eq!(total(&indexes, &store, "light").await, 2);
+
+    // A chapter lost after mirroring fails the build rather than hiding verses
+    std::fs::remove_file(dir.join("BSB/GEN/2.json")).unwrap();
+    let error = SearchIndexes::default()
+        .get_or_build(&store, "BSB")
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.code(), "not_found");
+    assert!(error.to_string().contains("GEN 2 is missing"), "{}", error);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn mirroring_again_rebuilds_the_index() {
+    let (base_url, edition) = mock_helloao().await;
+    let dir = store_dir("search-rebuild");
+    let store = DiskStore::new(&dir);
+    let indexes = SearchIndexes::default();
+
+    let client = HelloAOBibleClient::new(base_url.clone());
+    store.mirror_translation(&client, "BSB", 2).await.unwrap();
+    assert_eq!(total(&indexes, &store, "darkness").await, 0);
+
+    edition.store(1, Ordering::SeqCst);
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let client = HelloAOBibleClient::new(base_url);
+    store.mirror_translation(&client, "BSB", 2).await.unwrap();
+    assert_eq!(total(&indexes, &store, "darkness").await, 2);
+
+    std::fs::remove_dir_all(dir).ok();
+}
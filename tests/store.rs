@@ -0,0 +1,247 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use thebible::api::chain::ProviderChain;
+use thebible::api::models::{Book, Chapter, Translation, Verse};
+use thebible::api::provider::BibleProvider;
+use thebible::error::{AppError, Result};
+use thebible::store::{DiskStore, StoreBackedProvider};
+
+fn translation(id: &str) -> Translation {
+    Translation {
+        id: id.to_string(),
+        name: id.to_string(),
+        language: None,
+        english_name: None,
+        language_name: None,
+        language_english_name: None,
+        short_name: None,
+        description: None,
+        number_of_books: None,
+        canon: None,
+    }
+}
+
+/// An upstream serving a fixed translation list, or failing when it is empty
+struct Upstream {
+    name: &'static str,
+    translations: Vec<&'static str>,
+}
+
+#[async_trait]
+impl BibleProvider for Upstream {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        if self.translations.is_empty() {
+            return Err(AppError::UpstreamUnavailable(format!(
+                "{} is down",
+                self.name
+            )));
+        }
+        Ok(self.translations.iter().map(|id| translation(id)).collect())
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        Err(AppError::NotFound(format!("no books for {}", translation)))
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        Err(AppError::NotFound(format!(
+            "{} {} {}",
+            translation, book, chapter
+        )))
+    }
+}
+
+fn store_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("thebible-{}-{}", name, std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    dir
+}
+
+/// The default `mirror,helloao,bible_brain` chain, over `store`
+fn chain(
+    store: &DiskStore,
+    primary: Vec<&'static str>,
+    fallback: Vec<&'static str>,
+) -> ProviderChain {
+    let primary = Upstream {
+        name: "helloao",
+        translations: primary,
+    };
+    let fallback = Upstream {
+        name: "bible_brain",
+        translations: fallback,
+    };
+    ProviderChain::new(
+        vec![
+            Arc::new(store.clone()),
+            Arc::new(StoreBackedProvider::new(Arc::new(primary), store.clone())),
+            Arc::new(
+                StoreBackedProvider::new(Arc::new(fallback), store.clone()).without_catalogs(),
+            ),
+        ],
+        Duration::from_secs(5),
+    )
+}
+
+fn ids(translations: &[Translation]) -> Vec<&str> {
+    translations.iter().map(|t| t.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn a_stored_translation_list_does_not_hide_the_upstream_one() {
+    let dir = store_dir("store-refresh");
+    let store = DiskStore::new(&dir);
+    store
+        .save_translations(&[translation("OLD")])
+        .await
+        .unwrap();
+
+    let served = chain(&store, vec!["BSB", "NEW"], vec![])
+        .serve_translations()
+        .await
+        .unwrap();
+    assert_eq!(served.provider, "helloao");
+    assert_eq!(ids(&served.value), vec!["BSB", "NEW"]);
+    let stored = store.load_translations().await.unwrap().unwrap();
+    assert_eq!(ids(&stored), vec!["BSB", "NEW"]);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn a_fallback_catalog_is_not_stored_over_the_primary_one() {
+    let dir = store_dir("store-fallback");
+    let store = DiskStore::new(&dir);
+    store
+        .save_translations(&[translation("BSB")])
+        .await
+        .unwrap();
+
+    // The primary serves its stored list, merged with the fallback's,
+    // and the merged list is not stored in its place
+    let served = chain(&store, vec![], vec!["ENGKJV"])
+        .serve_translations()
+        .await
+        .unwrap();
+    assert_eq!(ids(&served.value), vec!["BSB", "ENGKJV"]);
+    let stored = store.load_translations().await.unwrap().unwrap();
+    assert_eq!(ids(&stored), vec!["BSB"]);
+
+    // With nothing stored, the fallback answers without being persisted
+    std::fs::remove_dir_all(&dir).unwrap();
+    let served = chain(&store, vec![], vec!["ENGKJV"])
+        .serve_translations()
+        .await
+        .unwrap();
+    assert_eq!(served.provider, "bible_brain");
+    assert_eq!(ids(&served.value), vec!["ENGKJV"]);
+    assert!(store.load_translations().await.unwrap().is_none());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+fn chapter(translation: &str, verses: u32) -> Chapter {
+    Chapter {
+        translation: translation.to_string(),
+        book: "GEN".to_string(),
+        chapter: 1,
+        verses: (1..=verses)
+            .map(|verse| Verse {
+                verse,
+                text: "In the beginning".to_string(),
+                footnotes: None,
+            })
+            .collect(),
+        footnotes: None,
+        content: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn saves_refuse_paths_outside_the_store() {
+    let dir = store_dir("store-escape");
+    let store = DiskStore::new(&dir);
+
+    let error = store.save_chapter(&chapter("..", 1)).await.unwrap_err();
+    assert!(
+        error.to_string().contains("invalid path segment"),
+        "{}",
+        error
+    );
+    assert!(!dir.exists());
+}
+
+#[tokio::test]
+async fn concurrent_saves_of_one_document_all_succeed() {
+    let dir = store_dir("store-concurrent");
+    let store = DiskStore::new(&dir);
+
+    let chapters: Vec<Chapter> = (1..=20).map(|verses| chapter("BSB", verses)).collect();
+    let saves = futures::future::join_all(chapters.iter().map(|c| store.save_chapter(c))).await;
+    assert!(saves.iter().all(|save| save.is_ok()));
+
+    let stored = store.load_chapter("BSB", "GEN", 1).await.unwrap().unwrap();
+    assert!(!stored.verses.is_empty());
+    let leftovers = std::fs::read_dir(dir.join("BSB/GEN")).unwrap().count();
+    assert_eq!(leftovers, 1);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+/// An upstream serving the same chapter on every read
+struct Steady;
+
+#[async_trait]
+impl BibleProvider for Steady {
+    fn name(&self) -> &str {
+        "helloao"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(vec![translation("BSB")])
+    }
+
+    async fn get_books(&self, translation: &str) -> Result<Vec<Book>> {
+        Err(AppError::NotFound(format!("no books for {}", translation)))
+    }
+
+    async fn get_chapter(&self, translation: &str, _book: &str, _chapter: u32) -> Result<Chapter> {
+        Ok(chapter(translation, 3))
+    }
+}
+
+#[tokio::test]
+async fn repeated_reads_do_not_rewrite_stored_documents() {
+    let dir = store_dir("store-unchanged");
+    let store = DiskStore::new(&dir);
+    let provider = StoreBackedProvider::new(Arc::new(Steady), store.clone());
+
+    let written = |path: &str| {
+        std::fs::metadata(dir.join(path))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    provider.get_chapter("BSB", "GEN", 1).await.unwrap();
+    provider.get_translations().await.unwrap();
+    let (chapter_at, catalog_at) = (written("BSB/GEN/1.json"), written("translations.json"));
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    provider.get_chapter("BSB", "GEN", 1).await.unwrap();
+    provider.get_translations().await.unwrap();
+    assert_eq!(written("BSB/GEN/1.json"), chapter_at);
+    assert_eq!(written("translations.json"), catalog_at);
+
+    // A changed document is still written
+    store.save_chapter(&chapter("BSB", 5)).await.unwrap();
+    assert_ne!(written("BSB/GEN/1.json"), chapter_at);
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,62 @@
+use axum::{extract::State, routing::get, Json, Router};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use thebible::api::HelloAOBibleClient;
+
+async fn slow_chapter(State(hits): State<Arc<AtomicUsize>>) -> Json<Value> {
+    hits.fetch_add(1, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    Json(json!({
+        "chapter": {
+            "number": 1,
+            "content": [{ "type": "verse", "number": 1, "content": ["In the beginning"] }]
+        }
+    }))
+}
+
+async fn mock_helloao() -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let router = Router::new()
+        .route("/api/:translation/:book/:chapter", get(slow_chapter))
+        .with_state(hits.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (format!("http://{}/api", addr), hits)
+}
+
+#[tokio::test]
+async fn concurrent_identical_requests_share_one_fetch() {
+    let (base_url, hits) = mock_helloao().await;
+    let client = HelloAOBibleClient::new(base_url);
+
+    let chapters =
+        futures::future::join_all((0..20).map(|_| client.get_chapter("BSB", "GEN", 1))).await;
+
+    assert!(chapters.iter().all(|chapter| chapter.is_ok()));
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    let stats = client.coalescing_stats();
+    assert_eq!(stats.requests, 20);
+    assert_eq!(stats.upstream_fetches, 1);
+    assert_eq!(stats.deduplicated, 19);
+    assert_eq!(stats.in_flight, 0);
+}
+
+#[tokio::test]
+async fn different_requests_are_not_coalesced() {
+    let (base_url, hits) = mock_helloao().await;
+    let client = HelloAOBibleClient::new(base_url);
+
+    let (genesis, exodus) = futures::join!(
+        client.get_chapter("BSB", "GEN", 1),
+        client.get_chapter("BSB", "EXO", 1),
+    );
+
+    assert!(genesis.is_ok() && exodus.is_ok());
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert_eq!(client.coalescing_stats().deduplicated, 0);
+}
@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+
+use thebible::api::models::{Book, Chapter, Translation, Verse};
+use thebible::api::provider::BibleProvider;
+use thebible::error::{AppError, Result};
+use thebible::passage;
+use thebible::versification::{self, Scheme};
+
+#[test]
+fn malachi_four_is_malachi_three_in_hebrew() {
+    let map = |verse| versification::map_verse(Scheme::English, Scheme::Hebrew, "MAL", 4, verse);
+    assert_eq!(map(1), Some((3, 19)));
+    assert_eq!(map(5), Some((3, 23)));
+    assert_eq!(
+        versification::map_verse(Scheme::Hebrew, Scheme::English, "MAL", 3, 24),
+        Some((4, 6))
+    );
+    assert_eq!(
+        versification::chapters(Scheme::English, Scheme::Hebrew, "MAL", 4),
+        vec![3]
+    );
+}
+
+#[test]
+fn psalm_titles_shift_verses() {
+    assert_eq!(
+        versification::map_verse(Scheme::English, Scheme::Hebrew, "PSA", 51, 1),
+        Some((51, 3))
+    );
+    // The title has no English verse number
+    assert_eq!(
+        versification::map_verse(Scheme::Hebrew, Scheme::English, "PSA", 3, 1),
+        None
+    );
+    assert_eq!(
+        versification::map_verse(Scheme::Hebrew, Scheme::English, "PSA", 3, 2),
+        Some((3, 1))
+    );
+}
+
+#[test]
+fn greek_and_latin_psalms_are_numbered_lower() {
+    assert_eq!(
+        versification::map_verse(Scheme::English, Scheme::Vulgate, "PSA", 23, 1),
+        Some((22, 1))
+    );
+    assert_eq!(
+        versification::map_verse(Scheme::English, Scheme::Septuagint, "PSA", 10, 1),
+        Some((9, 22))
+    );
+    assert_eq!(
+        versification::chapters(Scheme::Synodal, Scheme::English, "PSA", 113),
+        vec![114, 115]
+    );
+    assert_eq!(
+        versification::map_verse(Scheme::Vulgate, Scheme::Hebrew, "PSA", 50, 3),
+        Some((51, 3))
+    );
+}
+
+#[test]
+fn round_trips_through_every_scheme() {
+    for scheme in Scheme::ALL {
+        let verses = [
+            ("GEN", 32, 1),
+            ("PSA", 119, 176),
+            ("JOL", 2, 28),
+            ("JHN", 3, 16),
+        ];
+        for (book, chapter, verse) in verses {
+            let there =
+                versification::map_verse(Scheme::English, scheme, book, chapter, verse).unwrap();
+            let back = versification::map_verse(scheme, Scheme::English, book, there.0, there.1);
+            assert_eq!(back, Some((chapter, verse)), "{} {}", scheme, book);
+        }
+    }
+}
+
+#[test]
+fn schemes_parse_aliases_and_guess_from_language() {
+    assert_eq!("KJV".parse::<Scheme>().unwrap(), Scheme::English);
+    assert_eq!("original".parse::<Scheme>().unwrap(), Scheme::Hebrew);
+    assert_eq!("septuagint".parse::<Scheme>().unwrap(), Scheme::Septuagint);
+    assert!("nope".parse::<Scheme>().is_err());
+    assert_eq!(Scheme::for_language("heb"), Scheme::Hebrew);
+    assert_eq!(Scheme::for_language("rus"), Scheme::Synodal);
+    assert_eq!(Scheme::for_language("eng"), Scheme::English);
+}
+
+/// Serves Malachi in Hebrew numbering: 3 chapters, the last with 24 verses
+struct HebrewMalachi;
+
+#[async_trait]
+impl BibleProvider for HebrewMalachi {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    async fn get_translations(&self) -> Result<Vec<Translation>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_books(&self, _translation: &str) -> Result<Vec<Book>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_chapter(&self, translation: &str, book: &str, chapter: u32) -> Result<Chapter> {
+        let verses = match chapter {
+            3 => 24,
+            1 | 2 => 10,
+            _ => return Err(AppError::NotFound(format!("No chapter {}", chapter))),
+        };
+        Ok(Chapter {
+            translation: translation.to_string(),
+            book: book.to_string(),
+            chapter,
+            verses: (1..=verses)
+                .map(|verse| Verse {
+                    verse,
+                    text: format!("{}:{}", chapter, verse),
+                    footnotes: None,
+                })
+                .collect(),
+            footnotes: None,
+            content: Vec::new(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn passages_are_fetched_in_the_translation_scheme() {
+    let passage = passage::fetch_passage(
+        &HebrewMalachi,
+        "heb_test",
+        Scheme::Hebrew,
+        "Mal 4:5-6",
+        Scheme::English,
+    )
+    .await
+    .unwrap();
+
+    let verses: Vec<_> = passage
+        .verses
+        .iter()
+        .map(|v| (v.chapter, v.verse, v.text.as_str()))
+        .collect();
+    assert_eq!(verses, vec![(4, 5, "3:23"), (4, 6, "3:24")]);
+    assert_eq!(passage.verses[0].native.unwrap().chapter, 3);
+}
+
+#[tokio::test]
+async fn huge_chapter_spans_are_rejected_before_fetching() {
+    let error = passage::fetch_passage(
+        &HebrewMalachi,
+        "heb_test",
+        Scheme::Hebrew,
+        "Gen 1-4000000000",
+        Scheme::English,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error.code(), "invalid_input");
+}
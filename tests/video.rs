@@ -0,0 +1,195 @@
+use axum::{extract::State, routing::get, Json, Router};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use thebible::api::bible_brain::BibleBrainClient;
+use thebible::api::video::Video;
+use thebible::api::HelloAOBibleClient;
+use thebible::handlers;
+use thebible::state::AppState;
+
+/// Seconds from now that the mock's signed links expire, per request number
+type Expiry = fn(usize) -> i64;
+
+#[derive(Clone)]
+struct MockDbp {
+    hits: Arc<AtomicUsize>,
+    expiry: Expiry,
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+async fn bible_videos(State(mock): State<MockDbp>) -> Json<Value> {
+    let hit = mock.hits.fetch_add(1, Ordering::SeqCst);
+    let expires = unix_now() + (mock.expiry)(hit);
+    Json(json!({
+        "data": [
+            {
+                "id": 101,
+                "bible_id": "ENGESV",
+                "book_id": "mrk",
+                "chapter_id": "3",
+                "path": format!("https://cdn.example/101.m3u8?Expires={}&Signature=abc", expires),
+                "duration": 240,
+                "title": "Jesus Appoints the Twelve",
+                "language_code": "ENG",
+                "resolution": "1280x720",
+                "type": "video_stream"
+            },
+            {
+                "id": "102",
+                "bible_id": "ENGESV",
+                "book_id": "MRK",
+                "chapter_id": 4,
+                "url": format!("https://cdn.example/102.m3u8?Expires={}", expires),
+                "language_code": "spa",
+                "resolution": "480",
+                "type": "video_stream"
+            }
+        ],
+        "meta": {}
+    }))
+}
+
+/// Serve `router` on an ephemeral local port and return its base URL
+async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn mock_dbp(expiry: Expiry) -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let router = Router::new()
+        .route("/bibles/:bible_id/videos", get(bible_videos))
+        .with_state(MockDbp {
+            hits: hits.clone(),
+            expiry,
+        });
+    (serve(router).await, hits)
+}
+
+fn a_day(_: usize) -> i64 {
+    24 * 60 * 60
+}
+
+async fn app(dbp_url: String) -> String {
+    let helloao = HelloAOBibleClient::new("http://127.0.0.1:9".to_string());
+    let mut state = AppState::new(Arc::new(helloao.clone()), helloao);
+    state.brain = Some(BibleBrainClient::new(dbp_url, "test-key".to_string()));
+    let router = Router::new()
+        .route(
+            "/api/brain/bibles/:bible_id/videos",
+            get(handlers::get_brain_bible_videos),
+        )
+        .route(
+            "/api/brain/bibles/:bible_id/videos/:video_id",
+            get(handlers::get_brain_video),
+        )
+        .route(
+            "/api/brain/bibles/:bible_id/books/:book/chapters/:chapter/videos",
+            get(handlers::get_brain_chapter_videos),
+        )
+        .with_state(state);
+    serve(router).await
+}
+
+#[tokio::test]
+async fn normalizes_video_fields() {
+    let (dbp, _) = mock_dbp(a_day).await;
+    let client = BibleBrainClient::new(dbp, "test-key".to_string());
+
+    let videos = client.get_bible_videos("ENGESV").await.unwrap();
+    assert_eq!(videos.len(), 2);
+
+    let first = &videos[0];
+    assert_eq!(first.id, "101");
+    assert_eq!(first.book_id.as_deref(), Some("MRK"));
+    assert_eq!(first.chapter, Some(3));
+    assert_eq!(first.language.as_deref(), Some("eng"));
+    assert_eq!(first.resolution.as_deref(), Some("720p"));
+    assert!(first
+        .url
+        .as_deref()
+        .unwrap()
+        .starts_with("https://cdn.example/101.m3u8"));
+    assert!(first.expires_at.unwrap() as i64 > unix_now());
+
+    let second = &videos[1];
+    assert_eq!(second.chapter, Some(4));
+    assert_eq!(second.resolution.as_deref(), Some("480p"));
+}
+
+#[tokio::test]
+async fn filters_by_language_and_resolution() {
+    let (dbp, _) = mock_dbp(a_day).await;
+    let app = app(dbp).await;
+
+    let url = format!(
+        "{}/api/brain/bibles/ENGESV/videos?language=ENG&resolution=720",
+        app
+    );
+    let videos: Vec<Video> = reqwest::get(&url).await.unwrap().json().await.unwrap();
+    assert_eq!(videos.len(), 1);
+    assert_eq!(videos[0].id, "101");
+
+    let url = format!("{}/api/brain/bibles/ENGESV/videos?resolution=1080p", app);
+    let videos: Vec<Video> = reqwest::get(&url).await.unwrap().json().await.unwrap();
+    assert!(videos.is_empty());
+}
+
+#[tokio::test]
+async fn reuses_listing_while_links_are_valid() {
+    let (dbp, hits) = mock_dbp(a_day).await;
+    let client = BibleBrainClient::new(dbp, "test-key".to_string());
+
+    client.get_bible_videos("ENGESV").await.unwrap();
+    client.get_bible_videos("ENGESV").await.unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn refetches_listing_when_links_are_about_to_expire() {
+    let (dbp, hits) = mock_dbp(|_| 60).await;
+    let client = BibleBrainClient::new(dbp, "test-key".to_string());
+
+    client.get_bible_videos("ENGESV").await.unwrap();
+    client.get_bible_videos("ENGESV").await.unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn single_video_route_re_signs_expired_link() {
+    // The first listing carries an already expired link
+    let (dbp, hits) = mock_dbp(|hit| if hit == 0 { -10 } else { 3600 }).await;
+    let app = app(dbp).await;
+
+    let url = format!("{}/api/brain/bibles/ENGESV/videos/101", app);
+    let video: Video = reqwest::get(&url).await.unwrap().json().await.unwrap();
+
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert!(video.expires_at.unwrap() as i64 > unix_now() + 3000);
+}
+
+#[tokio::test]
+async fn chapter_videos_reject_non_numeric_chapters() {
+    let (dbp, hits) = mock_dbp(a_day).await;
+    let app = app(dbp).await;
+
+    // Decodes to "../../ENGESV" and would otherwise reach another DBP path
+    let url = format!(
+        "{}/api/brain/bibles/ENGESV/books/MRK/chapters/..%2F..%2FENGESV/videos",
+        app
+    );
+    let response = reqwest::get(&url).await.unwrap();
+    assert_eq!(response.status(), 400);
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+}